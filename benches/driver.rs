@@ -0,0 +1,82 @@
+//! Benchmarks for rendering citations and bibliographies.
+
+use std::fmt::Write;
+
+use citationberg::{IndependentStyle, Locale, Style};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use hayagriva::archive::{locales, ArchivedStyle};
+use hayagriva::io::from_biblatex_str;
+use hayagriva::{
+    BibliographyDriver, BibliographyRequest, CitationItem, CitationRequest,
+    CompiledStyle, Entry, Library,
+};
+
+/// A library with many entries by few authors, so that the driver has to
+/// sort and disambiguate.
+fn library() -> Library {
+    let mut bib = String::new();
+    for i in 0..300 {
+        writeln!(
+            bib,
+            "@book{{key{i}, title = {{Book {i}}}, author = {{Author{}, Jane}}, \
+             date = {{{}}}, publisher = {{Publisher}}}}",
+            i % 20,
+            2000 + i % 5,
+        )
+        .unwrap();
+    }
+    from_biblatex_str(&bib).unwrap()
+}
+
+fn style(name: &str) -> IndependentStyle {
+    match ArchivedStyle::by_name(name).unwrap().get() {
+        Style::Independent(style) => style,
+        Style::Dependent(_) => panic!("{name} is a dependent style"),
+    }
+}
+
+fn render(
+    lib: &Library,
+    style: &IndependentStyle,
+    locales: &[Locale],
+    compiled: Option<&CompiledStyle>,
+) {
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    for entry in lib.iter() {
+        let items = vec![CitationItem::with_entry(entry)];
+        driver.citation(match compiled {
+            Some(compiled) => CitationRequest::with_compiled(items, compiled, None, None),
+            None => CitationRequest::from_items(items, style, locales),
+        });
+    }
+
+    let request = match compiled {
+        Some(compiled) => BibliographyRequest::with_compiled(compiled, None),
+        None => BibliographyRequest::new(style, None, locales),
+    };
+    black_box(driver.finish(request));
+}
+
+/// Compares rendering with a plain style to rendering with a compiled style
+/// that is reused across documents, like in batch rendering.
+fn driver(c: &mut Criterion) {
+    let lib = library();
+    let locales = locales();
+
+    for name in ["apa", "chicago-author-date", "ieee"] {
+        let style = style(name);
+        let compiled = CompiledStyle::new(&style, &locales);
+
+        let mut group = c.benchmark_group(name);
+        group.sample_size(10);
+        group
+            .bench_function("style", |b| b.iter(|| render(&lib, &style, &locales, None)));
+        group.bench_function("compiled", |b| {
+            b.iter(|| render(&lib, &style, &locales, Some(&compiled)))
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, driver);
+criterion_main!(benches);
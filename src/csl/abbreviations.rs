@@ -0,0 +1,367 @@
+//! Abbreviation lists for the short forms of variables.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use citationberg::taxonomy::StandardVariable;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+/// Words that are omitted from abbreviated titles according to ISO 4.
+const OMITTED_WORDS: &[&str] = &[
+    "a", "an", "and", "at", "by", "for", "from", "in", "of", "on", "the", "to", "with",
+    "&", "der", "die", "das", "und", "für", "de", "des", "du", "la", "le", "les", "et",
+];
+
+/// Abbreviations for the short forms of `container-title`, `publisher`, and
+/// `authority`.
+///
+/// They are used when a style requests the short form of one of these
+/// variables and the entry does not define its own short form. Abbreviations
+/// can be loaded from abbreviation lists in the JSON format of Zotero and
+/// citeproc-js. Journal titles without an entry in these lists can be
+/// abbreviated word by word with the ISO 4 List of Title Word Abbreviations
+/// (LTWA).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Abbreviations {
+    /// Abbreviations of `container-title` by normalized long form.
+    container_titles: BTreeMap<String, String>,
+    /// Abbreviations of `publisher` by normalized long form.
+    publishers: BTreeMap<String, String>,
+    /// Abbreviations of `authority` by normalized long form.
+    authorities: BTreeMap<String, String>,
+    /// LTWA abbreviations of whole words.
+    words: BTreeMap<String, String>,
+    /// LTWA abbreviations of words starting with a stem.
+    stems: Vec<(String, String)>,
+}
+
+/// A list in the JSON format of citeproc-js, optionally grouped by
+/// jurisdiction. The maps keep the order of the file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AbbreviationJson {
+    Jurisdictions(IndexMap<String, IndexMap<String, IndexMap<String, String>>>),
+    Categories(IndexMap<String, IndexMap<String, String>>),
+}
+
+impl Abbreviations {
+    /// Create an empty set of abbreviations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse an abbreviation list in the JSON format of Zotero and
+    /// citeproc-js.
+    ///
+    /// The list maps categories like `container-title` or `institution-part`
+    /// to pairs of long and short forms. It may be nested in a map of
+    /// jurisdictions. If a long form is abbreviated more than once, the
+    /// abbreviation that comes last in the file wins, except that the
+    /// `default` jurisdiction takes precedence over all others.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut res = Self::new();
+        res.extend_from_json(json)?;
+        Ok(res)
+    }
+
+    /// Add the abbreviations of a list in the JSON format of Zotero and
+    /// citeproc-js. See [`Abbreviations::from_json`].
+    pub fn extend_from_json(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let mut jurisdictions: Vec<_> = match serde_json::from_str(json)? {
+            AbbreviationJson::Jurisdictions(map) => map.into_iter().collect(),
+            AbbreviationJson::Categories(map) => vec![("default".into(), map)],
+        };
+
+        // Insert the default jurisdiction last so that it wins. The sort is
+        // stable, so the other jurisdictions keep the order of the file.
+        jurisdictions.sort_by_key(|(name, _)| name == "default");
+        for (_, categories) in jurisdictions {
+            for (category, entries) in categories {
+                for &variable in category_variables(&category) {
+                    for (long, short) in entries.iter() {
+                        self.insert(variable, long, short.clone());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add word abbreviations from the ISO 4 List of Title Word
+    /// Abbreviations.
+    ///
+    /// Each line contains a word, its abbreviation, and optionally its
+    /// languages, separated by tabs or semicolons. Words ending with a hyphen
+    /// are stems that match all words starting with them. An abbreviation of
+    /// `n.a.` marks a word that is not abbreviated. Lines that cannot be
+    /// parsed are skipped.
+    pub fn extend_from_ltwa(&mut self, list: &str) {
+        for line in list.lines() {
+            let sep = if line.contains('\t') { '\t' } else { ';' };
+            let mut fields = line.split(sep).map(str::trim);
+            let (Some(word), Some(abbr)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+
+            let word = word.to_lowercase();
+            if word.is_empty()
+                || word == "words"
+                || word.starts_with('-')
+                || word.contains(' ')
+                || abbr.is_empty()
+            {
+                continue;
+            }
+
+            // Not abbreviated words map to themselves.
+            let abbr = if abbr.eq_ignore_ascii_case("n.a.") {
+                word.trim_end_matches('-').to_string()
+            } else {
+                abbr.to_lowercase()
+            };
+
+            match word.strip_suffix('-') {
+                Some(stem) => self.stems.push((stem.to_string(), abbr)),
+                None => {
+                    self.words.insert(word, abbr);
+                }
+            }
+        }
+
+        // Prefer the longest stem.
+        self.stems.sort_by_key(|(stem, _)| Reverse(stem.len()));
+    }
+
+    /// Add an abbreviation for a variable. Abbreviations for variables other
+    /// than `container-title`, `publisher`, and `authority` are ignored.
+    pub fn insert(
+        &mut self,
+        variable: StandardVariable,
+        long: &str,
+        short: impl Into<String>,
+    ) {
+        if let Some(list) = self.list_mut(variable) {
+            list.insert(normalize(long), short.into());
+        }
+    }
+
+    /// Abbreviate the value of a variable. Returns `None` if there is no
+    /// abbreviation.
+    ///
+    /// Values of `container-title` that are not in any list are abbreviated
+    /// with the LTWA word abbreviations, if any were loaded.
+    pub fn abbreviate(&self, variable: StandardVariable, value: &str) -> Option<String> {
+        if let Some(short) = self.list(variable).and_then(|l| l.get(&normalize(value))) {
+            return Some(short.clone());
+        }
+
+        if variable == StandardVariable::ContainerTitle {
+            return self.abbreviate_title(value);
+        }
+
+        None
+    }
+
+    /// The abbreviation list of a variable.
+    fn list(&self, variable: StandardVariable) -> Option<&BTreeMap<String, String>> {
+        match variable {
+            StandardVariable::ContainerTitle => Some(&self.container_titles),
+            StandardVariable::Publisher => Some(&self.publishers),
+            StandardVariable::Authority => Some(&self.authorities),
+            _ => None,
+        }
+    }
+
+    /// The mutable abbreviation list of a variable.
+    fn list_mut(
+        &mut self,
+        variable: StandardVariable,
+    ) -> Option<&mut BTreeMap<String, String>> {
+        match variable {
+            StandardVariable::ContainerTitle => Some(&mut self.container_titles),
+            StandardVariable::Publisher => Some(&mut self.publishers),
+            StandardVariable::Authority => Some(&mut self.authorities),
+            _ => None,
+        }
+    }
+
+    /// Abbreviate a serial title word by word according to ISO 4.
+    fn abbreviate_title(&self, title: &str) -> Option<String> {
+        if self.words.is_empty() && self.stems.is_empty() {
+            return None;
+        }
+
+        let words: Vec<&str> = title
+            .split_whitespace()
+            .filter(|w| !OMITTED_WORDS.contains(&w.to_lowercase().as_str()))
+            .collect();
+
+        // Titles consisting of a single word are not abbreviated.
+        if words.len() < 2 {
+            return None;
+        }
+
+        // Dropping omitted words alone does not make an abbreviation.
+        let mut abbreviated = false;
+        let res = words
+            .iter()
+            .map(|word| match self.abbreviate_word(word) {
+                Some(abbr) => {
+                    abbreviated = true;
+                    abbr
+                }
+                None => word.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        abbreviated.then_some(res)
+    }
+
+    /// Abbreviate a single word, keeping surrounding punctuation and the
+    /// capitalization of its first letter. Returns `None` if the word has no
+    /// shorter abbreviation.
+    fn abbreviate_word(&self, word: &str) -> Option<String> {
+        let end = word.trim_end_matches(|c: char| !c.is_alphanumeric()).len();
+        let (core, trailing) = word.split_at(end);
+        let lower = core.to_lowercase();
+
+        let abbr = self.words.get(&lower).or_else(|| {
+            self.stems
+                .iter()
+                .find(|(stem, _)| lower.starts_with(stem.as_str()))
+                .map(|(_, abbr)| abbr)
+        });
+
+        let abbr = abbr.filter(|abbr| abbr.len() < core.len())?;
+
+        let mut res = String::with_capacity(word.len());
+        let mut chars = abbr.chars();
+        if core.starts_with(char::is_uppercase) {
+            res.extend(chars.next().into_iter().flat_map(char::to_uppercase));
+        }
+        res.extend(chars);
+        res.push_str(if res.ends_with('.') {
+            trailing.strip_prefix('.').unwrap_or(trailing)
+        } else {
+            trailing
+        });
+        Some(res)
+    }
+}
+
+/// The variables that a citeproc-js abbreviation category applies to.
+fn category_variables(category: &str) -> &'static [StandardVariable] {
+    match category {
+        "container-title" => &[StandardVariable::ContainerTitle],
+        "publisher" => &[StandardVariable::Publisher],
+        "authority" => &[StandardVariable::Authority],
+        "institution-entire" | "institution-part" => {
+            &[StandardVariable::Publisher, StandardVariable::Authority]
+        }
+        _ => &[],
+    }
+}
+
+/// Normalize a long form for lookup.
+fn normalize(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches('.')
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_lists() {
+        let abbrs = Abbreviations::from_json(
+            r#"{
+                "default": {
+                    "container-title": {
+                        "Physical Review Letters": "Phys. Rev. Lett."
+                    },
+                    "institution-part": {
+                        "World Health Organization": "WHO"
+                    }
+                },
+                "us": {
+                    "container-title": {
+                        "physical review letters": "PRL",
+                        "Nature": "Nat. (US)"
+                    }
+                },
+                "de": {
+                    "container-title": {
+                        "Nature": "Nat. (DE)"
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            abbrs
+                .abbreviate(StandardVariable::ContainerTitle, "Physical  Review Letters")
+                .as_deref(),
+            Some("Phys. Rev. Lett.")
+        );
+        assert_eq!(
+            abbrs
+                .abbreviate(StandardVariable::Authority, "World Health Organization")
+                .as_deref(),
+            Some("WHO")
+        );
+        assert_eq!(
+            abbrs.abbreviate(StandardVariable::Title, "World Health Organization"),
+            None
+        );
+
+        // Among the other jurisdictions, the last one in the file wins.
+        assert_eq!(
+            abbrs
+                .abbreviate(StandardVariable::ContainerTitle, "Nature")
+                .as_deref(),
+            Some("Nat. (DE)")
+        );
+    }
+
+    #[test]
+    fn ltwa() {
+        let mut abbrs = Abbreviations::new();
+        abbrs.extend_from_ltwa(
+            "WORDS\tABBREVIATIONS\tLANGUAGES\n\
+             american\tam.\teng\n\
+             chemical\tchem.\teng\n\
+             journal\tj.\teng\n\
+             physic-\tphys.\teng\n\
+             review\trev.\teng\n\
+             society-\tsoc.\teng\n\
+             nature\tn.a.\teng\n\
+             condensed\tcondens.\teng\n\
+             matter\tn.a.\teng",
+        );
+
+        let abbreviate =
+            |title| abbrs.abbreviate(StandardVariable::ContainerTitle, title);
+        assert_eq!(
+            abbreviate("Journal of the American Chemical Society").as_deref(),
+            Some("J. Am. Chem. Soc.")
+        );
+        assert_eq!(
+            abbreviate("Journal of Physics: Condensed Matter").as_deref(),
+            Some("J. Phys.: Condens. Matter")
+        );
+        assert_eq!(abbreviate("Physical Review").as_deref(), Some("Phys. Rev."));
+        assert_eq!(abbreviate("Nature"), None);
+        assert_eq!(abbreviate("Annals of History"), None);
+        assert_eq!(
+            abbrs.abbreviate(StandardVariable::Publisher, "Physical Review"),
+            None
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Styles prepared for rendering many documents.
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ptr;
+use std::sync::{Arc, RwLock};
+
+use citationberg::{CslMacro, IndependentStyle, Locale, LocaleCode, XmlError};
+
+use super::locale::{LocaleChain, LocaleResolver, Source};
+use super::InText;
+use crate::lang::collation::Collators;
+
+/// The resolved locale and the sources of a cached locale chain.
+type CachedChain = (LocaleCode, Arc<[Source]>);
+
+/// A style prepared for rendering many citations and bibliographies.
+///
+/// It resolves the macros of the style once and caches the locale chains and
+/// the collators used for sorting. It is `Send + Sync`, so one compiled style
+/// can be shared by drivers on multiple threads. Create requests for it with
+/// [`CitationRequest::with_compiled`](super::CitationRequest::with_compiled)
+/// and [`BibliographyRequest::with_compiled`](super::BibliographyRequest::with_compiled).
+#[derive(Debug)]
+pub struct CompiledStyle<'a> {
+    style: &'a IndependentStyle,
+    locale_files: &'a [Locale],
+    resolver: LocaleResolver<'a>,
+    macros: HashMap<&'a str, &'a CslMacro>,
+    intext: Option<InText>,
+    chains: RwLock<HashMap<Option<LocaleCode>, CachedChain>>,
+    collators: Collators,
+}
+
+impl<'a> CompiledStyle<'a> {
+    /// Prepare a style for rendering with the given locale files.
+    pub fn new(style: &'a IndependentStyle, locale_files: &'a [Locale]) -> Self {
+        Self {
+            style,
+            locale_files,
+            resolver: LocaleResolver::new(locale_files),
+            macros: style.macros.iter().map(|m| (m.name.as_str(), m)).collect(),
+            intext: None,
+            chains: RwLock::new(HashMap::new()),
+            collators: Collators::default(),
+        }
+    }
+
+    /// Prepare a style for rendering with the XML it was parsed from.
+    ///
+    /// In addition to [`CompiledStyle::new`], this reads the `cs:intext`
+    /// element of CSL 1.0.2 styles, which is used for items cited with
+    /// [`CitePurpose::Prose`](super::CitePurpose::Prose).
+    pub fn from_xml(
+        style: &'a IndependentStyle,
+        xml: &str,
+        locale_files: &'a [Locale],
+    ) -> Result<Self, XmlError> {
+        Ok(Self {
+            intext: InText::from_style_xml(xml)?,
+            ..Self::new(style, locale_files)
+        })
+    }
+
+    /// The style.
+    pub fn style(&self) -> &'a IndependentStyle {
+        self.style
+    }
+
+    /// The locale files of the style.
+    pub fn locale_files(&self) -> &'a [Locale] {
+        self.locale_files
+    }
+
+    /// The `cs:intext` layout of the style, if any.
+    pub fn intext(&self) -> Option<&InText> {
+        self.intext.as_ref()
+    }
+
+    /// Retrieve a macro.
+    pub(super) fn get_macro(&self, name: &str) -> Option<&'a CslMacro> {
+        self.macros.get(name).copied()
+    }
+
+    /// The locale chain for a requested locale.
+    pub(super) fn chain(&self, locale: Option<&LocaleCode>) -> LocaleChain<'a> {
+        if let Some((code, sources)) = self.chains.read().unwrap().get(&locale.cloned()) {
+            return LocaleChain::from_parts(
+                code.clone(),
+                self.style,
+                self.locale_files,
+                sources.clone(),
+            );
+        }
+
+        let chain = self.resolver.resolve(self.style, locale);
+        self.chains
+            .write()
+            .unwrap()
+            .insert(locale.cloned(), (chain.locale().clone(), chain.sources().clone()));
+        chain
+    }
+
+    /// The collators used for sorting.
+    pub(super) fn collators(&self) -> &Collators {
+        &self.collators
+    }
+}
+
+impl PartialEq for CompiledStyle<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.style, other.style) && ptr::eq(self.locale_files, other.locale_files)
+    }
+}
+
+impl Eq for CompiledStyle<'_> {}
+
+impl Hash for CompiledStyle<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(self.style, state);
+        ptr::hash(self.locale_files, state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<CompiledStyle>();
+    }
+}
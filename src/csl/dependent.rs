@@ -0,0 +1,75 @@
+//! Resolution of dependent styles to their independent parents.
+
+use citationberg::{DependentStyle, IndependentStyle, Style};
+
+#[cfg(feature = "archive")]
+use super::archive::ArchivedStyle;
+
+/// Resolve a style to the independent style that renders it.
+///
+/// Independent styles are returned as they are. Dependent styles are resolved
+/// to their parent, which `lookup` retrieves by its CSL ID. Returns `None` if
+/// the parent cannot be found.
+pub fn resolve_style<F>(style: Style, lookup: F) -> Option<IndependentStyle>
+where
+    F: FnOnce(&str) -> Option<IndependentStyle>,
+{
+    match style {
+        Style::Independent(style) => Some(style),
+        Style::Dependent(dependent) => {
+            let parent = lookup(&dependent.parent_link.href)?;
+            Some(apply_dependent(&dependent, parent))
+        }
+    }
+}
+
+/// Apply the overrides of a dependent style to its parent. The default locale
+/// of the dependent style replaces the one of the parent.
+pub fn apply_dependent(
+    dependent: &DependentStyle,
+    mut parent: IndependentStyle,
+) -> IndependentStyle {
+    if let Some(locale) = &dependent.default_locale {
+        parent.default_locale = Some(locale.clone());
+    }
+    parent
+}
+
+/// Resolve a style to the independent style that renders it, looking up the
+/// parents of dependent styles in the archive.
+#[cfg(feature = "archive")]
+pub fn resolve_archived_style(style: Style) -> Option<IndependentStyle> {
+    resolve_style(style, |id| match ArchivedStyle::by_id(id)?.get() {
+        Style::Independent(parent) => Some(parent),
+        Style::Dependent(_) => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "archive")]
+    use citationberg::LocaleCode;
+
+    #[test]
+    #[cfg(feature = "archive")]
+    fn locale_override() {
+        let style = Style::from_xml(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<style xmlns="http://purl.org/net/xbiblio/csl" version="1.0" default-locale="de-DE">
+  <info>
+    <title>Zeitschrift</title>
+    <id>http://www.zotero.org/styles/zeitschrift</id>
+    <link href="http://www.zotero.org/styles/apa" rel="independent-parent"/>
+    <updated>2024-01-01T00:00:00+00:00</updated>
+  </info>
+</style>"#,
+        )
+        .unwrap();
+
+        let resolved = resolve_archived_style(style.clone()).unwrap();
+        assert_eq!(resolved.info.id, "http://www.zotero.org/styles/apa");
+        assert_eq!(resolved.default_locale, Some(LocaleCode("de-DE".into())));
+        assert_eq!(resolve_style(style, |_| None), None);
+    }
+}
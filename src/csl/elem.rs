@@ -7,6 +7,9 @@ use citationberg::{
     Display, FontStyle, FontVariant, FontWeight, TextDecoration, VerticalAlign,
 };
 
+use super::math::{BuiltinMathRenderer, MathRenderer};
+use crate::types::{ChunkFormat, ChunkedString};
+
 /// A container for elements with useful methods.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Elem {
@@ -37,6 +40,7 @@ impl Elem {
         &self,
         w: &mut impl fmt::Write,
         format: BufWriteFormat,
+        math: &dyn MathRenderer,
     ) -> Result<(), fmt::Error> {
         match (format, self.display) {
             (BufWriteFormat::Html, Some(Display::Block)) => w.write_str("<div>")?,
@@ -54,7 +58,7 @@ impl Elem {
         }
 
         for child in &self.children.0 {
-            child.write_buf(w, format)?;
+            child.write_buf_with(w, format, math)?;
         }
 
         match (format, self.display) {
@@ -89,11 +93,9 @@ impl Elem {
 
 impl fmt::Display for Elem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if f.alternate() {
-            self.write_buf(f, BufWriteFormat::Plain)
-        } else {
-            self.write_buf(f, BufWriteFormat::VT100)
-        }
+        let format =
+            if f.alternate() { BufWriteFormat::Plain } else { BufWriteFormat::VT100 };
+        self.write_buf(f, format, &BuiltinMathRenderer)
     }
 }
 
@@ -198,14 +200,26 @@ impl ElemChildren {
         None
     }
 
-    /// Write the children to the given buffer.
+    /// Write the children to the given buffer. Math is rendered with the
+    /// [`BuiltinMathRenderer`].
     pub fn write_buf(
         &self,
         w: &mut impl fmt::Write,
         format: BufWriteFormat,
+    ) -> Result<(), fmt::Error> {
+        self.write_buf_with(w, format, &BuiltinMathRenderer)
+    }
+
+    /// Write the children to the given buffer and render math with the given
+    /// renderer.
+    pub fn write_buf_with(
+        &self,
+        w: &mut impl fmt::Write,
+        format: BufWriteFormat,
+        math: &dyn MathRenderer,
     ) -> Result<(), fmt::Error> {
         for child in &self.0 {
-            child.write_buf(w, format)?;
+            child.write_buf_with(w, format, math)?;
         }
         Ok(())
     }
@@ -254,7 +268,8 @@ pub enum ElemChild {
     Text(Formatted),
     /// A child element.
     Elem(Elem),
-    /// This should be processed by Typst.
+    /// Math markup that should be processed by Typst. Other consumers can
+    /// render it with a [`MathRenderer`].
     Markup(String),
     /// This is a link.
     Link {
@@ -274,35 +289,47 @@ pub enum ElemChild {
 }
 
 impl ElemChild {
-    /// Write the child to a buffer.
+    /// Write the child to a buffer. Math is rendered with the
+    /// [`BuiltinMathRenderer`].
     pub fn write_buf(
         &self,
         w: &mut impl fmt::Write,
         format: BufWriteFormat,
+    ) -> Result<(), fmt::Error> {
+        self.write_buf_with(w, format, &BuiltinMathRenderer)
+    }
+
+    /// Write the child to a buffer and render math with the given renderer.
+    pub fn write_buf_with(
+        &self,
+        w: &mut impl fmt::Write,
+        format: BufWriteFormat,
+        math: &dyn MathRenderer,
     ) -> Result<(), fmt::Error> {
         match self {
-            ElemChild::Text(t) => {
-                t.formatting.write_start(w, format)?;
-                w.write_str(&t.text)?;
-                t.formatting.write_end(w, format)?;
-                Ok(())
-            }
-            ElemChild::Elem(e) => e.write_buf(w, format),
-            ElemChild::Markup(m) => w.write_str(m),
+            ElemChild::Text(t) => t.write_buf(w, format),
+            ElemChild::Elem(e) => e.write_buf(w, format, math),
+            ElemChild::Markup(m) => math.render(m, format, w),
             ElemChild::Link { text, url } if format == BufWriteFormat::Html => {
                 w.write_str("<a href=\"")?;
                 w.write_str(url)?;
                 w.write_str("\">")?;
-                text.formatting.write_start(w, format)?;
-                w.write_str(&text.text)?;
-                text.formatting.write_end(w, format)?;
+                text.write_buf(w, format)?;
                 w.write_str("</a>")
             }
-            ElemChild::Link { text, .. } => {
-                text.formatting.write_start(w, format)?;
-                w.write_str(&text.text)?;
-                text.formatting.write_end(w, format)
+            ElemChild::Link { text, url } if format == BufWriteFormat::Latex => {
+                w.write_str("\\href{")?;
+                for c in url.chars() {
+                    if matches!(c, '%' | '#' | '\\' | '{' | '}') {
+                        w.write_char('\\')?;
+                    }
+                    w.write_char(c)?;
+                }
+                w.write_str("}{")?;
+                text.write_buf(w, format)?;
+                w.write_char('}')
             }
+            ElemChild::Link { text, .. } => text.write_buf(w, format),
             ElemChild::Transparent { .. } => Ok(()),
         }
     }
@@ -362,6 +389,41 @@ impl From<Formatted> for ElemChild {
     }
 }
 
+impl From<&ChunkedString> for ElemChildren {
+    fn from(chunked: &ChunkedString) -> Self {
+        let base = Formatting::default();
+        Self(
+            chunked
+                .0
+                .iter()
+                .map(|chunk| {
+                    base.apply(base.chunk_format(chunk.format))
+                        .add_text(chunk.value.clone())
+                        .into()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl From<ChunkedString> for ElemChildren {
+    fn from(chunked: ChunkedString) -> Self {
+        (&chunked).into()
+    }
+}
+
+impl From<String> for ElemChildren {
+    fn from(text: String) -> Self {
+        Self(vec![Formatting::default().add_text(text).into()])
+    }
+}
+
+impl From<&str> for ElemChildren {
+    fn from(text: &str) -> Self {
+        text.to_string().into()
+    }
+}
+
 /// The format with which to write an [element](Elem).
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum BufWriteFormat {
@@ -372,6 +434,8 @@ pub enum BufWriteFormat {
     VT100,
     /// Write HTML.
     Html,
+    /// Write LaTeX.
+    Latex,
 }
 
 /// A piece of formatted text.
@@ -383,6 +447,36 @@ pub struct Formatted {
     pub formatting: Formatting,
 }
 
+impl Formatted {
+    /// Write the formatted text to a buffer.
+    fn write_buf(&self, w: &mut impl fmt::Write, format: BufWriteFormat) -> fmt::Result {
+        self.formatting.write_start(w, format)?;
+        if format == BufWriteFormat::Latex {
+            write_latex_escaped(w, &self.text)?;
+        } else {
+            w.write_str(&self.text)?;
+        }
+        self.formatting.write_end(w, format)
+    }
+}
+
+/// Write text with the LaTeX special characters escaped.
+fn write_latex_escaped(w: &mut impl fmt::Write, text: &str) -> fmt::Result {
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                w.write_char('\\')?;
+                w.write_char(c)?;
+            }
+            '~' => w.write_str("\\textasciitilde{}")?,
+            '^' => w.write_str("\\textasciicircum{}")?,
+            '\\' => w.write_str("\\textbackslash{}")?,
+            c => w.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
 /// Some formatting information.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Formatting {
@@ -428,6 +522,41 @@ impl Formatting {
         self
     }
 
+    /// The partial formatting that applies the inline formatting of a string
+    /// chunk on top of this formatting. Italics, boldface, and small caps
+    /// flip-flop.
+    pub(super) fn chunk_format(&self, format: ChunkFormat) -> citationberg::Formatting {
+        let mut res = citationberg::Formatting::default();
+        if format.italic {
+            res.font_style = Some(match self.font_style {
+                FontStyle::Normal => FontStyle::Italic,
+                FontStyle::Italic => FontStyle::Normal,
+            });
+        }
+
+        if format.bold {
+            res.font_weight = Some(match self.font_weight {
+                FontWeight::Bold => FontWeight::Normal,
+                FontWeight::Normal | FontWeight::Light => FontWeight::Bold,
+            });
+        }
+
+        if format.small_caps {
+            res.font_variant = Some(match self.font_variant {
+                FontVariant::Normal => FontVariant::SmallCaps,
+                FontVariant::SmallCaps => FontVariant::Normal,
+            });
+        }
+
+        if format.superscript {
+            res.vertical_align = Some(VerticalAlign::Sup);
+        } else if format.subscript {
+            res.vertical_align = Some(VerticalAlign::Sub);
+        }
+
+        res
+    }
+
     pub(super) fn write_vt100(
         &self,
         buf: &mut impl fmt::Write,
@@ -477,6 +606,34 @@ impl Formatting {
         Ok(())
     }
 
+    /// The LaTeX commands that apply this formatting.
+    fn latex_commands(&self) -> Vec<&'static str> {
+        let mut commands = vec![];
+        if self.font_style == FontStyle::Italic {
+            commands.push("\\textit");
+        }
+
+        if self.font_weight == FontWeight::Bold {
+            commands.push("\\textbf");
+        }
+
+        if self.text_decoration == TextDecoration::Underline {
+            commands.push("\\underline");
+        }
+
+        if self.font_variant == FontVariant::SmallCaps {
+            commands.push("\\textsc");
+        }
+
+        match self.vertical_align {
+            VerticalAlign::Sub => commands.push("\\textsubscript"),
+            VerticalAlign::Sup => commands.push("\\textsuperscript"),
+            _ => {}
+        }
+
+        commands
+    }
+
     pub(super) fn write_start(
         &self,
         buf: &mut impl fmt::Write,
@@ -485,6 +642,13 @@ impl Formatting {
         match format {
             BufWriteFormat::Plain => Ok(()),
             BufWriteFormat::VT100 => self.write_vt100(buf),
+            BufWriteFormat::Latex => {
+                for command in self.latex_commands() {
+                    buf.write_str(command)?;
+                    buf.write_char('{')?;
+                }
+                Ok(())
+            }
             BufWriteFormat::Html => {
                 let is_default = self == &Formatting::default();
                 if !is_default {
@@ -505,6 +669,12 @@ impl Formatting {
         match format {
             BufWriteFormat::Plain => Ok(()),
             BufWriteFormat::VT100 => buf.write_str("\x1b[0m"),
+            BufWriteFormat::Latex => {
+                for _ in self.latex_commands() {
+                    buf.write_char('}')?;
+                }
+                Ok(())
+            }
             BufWriteFormat::Html => {
                 let is_default = self == &Formatting::default();
                 if !is_default {
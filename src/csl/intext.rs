@@ -0,0 +1,65 @@
+//! The `cs:intext` element of CSL 1.0.2.
+
+use std::num::NonZeroUsize;
+
+use citationberg::{Layout, XmlError};
+use quick_xml::de::Deserializer;
+use serde::Deserialize;
+
+/// The layout of narrative citations like "Smith (2020)", defined by the
+/// `cs:intext` element of CSL 1.0.2 styles.
+///
+/// It is used for items cited with [`CitePurpose::Prose`](super::CitePurpose).
+/// Citationberg does not retain the element, so it is read from the XML by
+/// [`CompiledStyle::from_xml`](super::CompiledStyle::from_xml). If a style has
+/// no `cs:intext` layout, the narrative citation is derived from the regular
+/// citation layout.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub struct InText {
+    /// The formatting rules of the narrative citation.
+    pub layout: Layout,
+}
+
+impl InText {
+    /// Parse the `cs:intext` element of a style. Returns `None` if the style
+    /// does not have one.
+    pub fn from_style_xml(xml: &str) -> Result<Option<Self>, XmlError> {
+        #[derive(Deserialize)]
+        struct Style {
+            intext: Option<InText>,
+        }
+
+        let mut de = Deserializer::from_str(xml);
+        de.event_buffer_size(NonZeroUsize::new(4096));
+        Ok(Style::deserialize(&mut de)?.intext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let style = r#"<?xml version="1.0" encoding="utf-8"?>
+        <style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0.2">
+          <info><id>test</id><title>Test</title></info>
+          <citation>
+            <layout><text variable="title"/></layout>
+          </citation>
+          <intext>
+            <layout prefix="[" suffix="]">
+              <names variable="author"/>
+              <text variable="title" prefix=" "/>
+            </layout>
+          </intext>
+        </style>"#;
+
+        let intext = InText::from_style_xml(style).unwrap().unwrap();
+        assert_eq!(intext.layout.elements.len(), 2);
+        assert_eq!(intext.layout.prefix.as_deref(), Some("["));
+
+        let style = style.replace("intext", "unknown");
+        assert_eq!(InText::from_style_xml(&style).unwrap(), None);
+    }
+}
@@ -0,0 +1,642 @@
+//! Checks that find problems in styles before they are used for rendering.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::iter::successors;
+
+use citationberg::taxonomy::{NumberVariable, Term, Variable};
+use citationberg::{
+    Choose, ChooseBranch, ChooseMatch, CslMacro, IndependentStyle, Layout,
+    LayoutRenderingElement, Locale, LocaleCode, NamesChild, Sort, SortKey, TermForm,
+    TextTarget,
+};
+use serde::Serialize;
+
+use super::locale::{LocaleChain, LocaleResolver};
+use super::rendering::trace::{describe_branch, describe_element, kebab};
+use super::taxonomy::is_unsupported_variable;
+
+/// A problem that [`lint_style`] found in a style.
+///
+/// Lints serialize to JSON objects with kebab-case names so that they can be
+/// consumed by other tools.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct StyleLint {
+    /// How severe the problem is.
+    pub severity: LintSeverity,
+    /// What kind of problem this is.
+    pub kind: LintKind,
+    /// The path of CSL elements at which the problem was found, e.g.
+    /// `["bibliography", "layout", "text macro=\"author\""]`.
+    pub path: Vec<String>,
+    /// A description of the problem.
+    pub message: String,
+}
+
+/// How severe a [`StyleLint`] is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintSeverity {
+    /// The style cannot be rendered as written.
+    Error,
+    /// Part of the style has no effect.
+    Warning,
+}
+
+/// The kind of a [`StyleLint`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintKind {
+    /// A macro is called but not defined.
+    UndefinedMacro,
+    /// A macro calls itself, directly or through other macros.
+    RecursiveMacro,
+    /// A macro is defined but never called.
+    UnusedMacro,
+    /// A branch of a `cs:choose` element can never be chosen.
+    UnreachableCondition,
+    /// A variable is used that Hayagriva entries never provide or that the
+    /// element cannot print.
+    UnsupportedVariable,
+    /// A feature of CSL is used that Hayagriva does not implement.
+    UnsupportedFeature,
+    /// A term is not defined by any locale of the selected locale chains.
+    MissingTerm,
+    /// No locale is available for the language of a selected locale.
+    MissingLocale,
+}
+
+impl LintKind {
+    /// The severity of lints of this kind.
+    pub fn severity(self) -> LintSeverity {
+        match self {
+            Self::UndefinedMacro | Self::RecursiveMacro => LintSeverity::Error,
+            _ => LintSeverity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for StyleLint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]", self.severity, self.kind)?;
+        if !self.path.is_empty() {
+            write!(f, " {}", self.path.join(" > "))?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&kebab(self))
+    }
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&kebab(self))
+    }
+}
+
+/// Check a style for problems that would make it render differently than
+/// intended.
+///
+/// Terms are looked up for the default locale of the style in the locales
+/// embedded in the style and, if the `archive` feature is enabled, the
+/// archived locales. Use [`lint_style_with_locales`] to select the locales.
+pub fn lint_style(style: &IndependentStyle) -> Vec<StyleLint> {
+    lint_with_resolver(style, &LocaleResolver::new(&[]).with_archive(true), &[])
+}
+
+/// Check a style for problems, looking up terms in the locale chains of the
+/// given locales.
+///
+/// The chains are resolved with a [`LocaleResolver`] for the given locale
+/// files. If no locales are given, the default locale of the style is used.
+pub fn lint_style_with_locales(
+    style: &IndependentStyle,
+    locale_files: &[Locale],
+    locales: &[LocaleCode],
+) -> Vec<StyleLint> {
+    lint_with_resolver(style, &LocaleResolver::new(locale_files), locales)
+}
+
+/// Check a style for problems in the locale chains of the given resolver.
+fn lint_with_resolver(
+    style: &IndependentStyle,
+    resolver: &LocaleResolver,
+    locales: &[LocaleCode],
+) -> Vec<StyleLint> {
+    let chains: Vec<_> = if locales.is_empty() {
+        vec![resolver.resolve(style, None)]
+    } else {
+        locales.iter().map(|l| resolver.resolve(style, Some(l))).collect()
+    };
+
+    let mut linter = Linter {
+        macros: style.macros.iter().map(|m| (m.name.as_str(), m)).collect(),
+        chains,
+        bibliography_only: false,
+        path: vec![],
+        lints: vec![],
+    };
+
+    for chain in linter.chains.clone() {
+        if !chain.has_language() {
+            linter.report(
+                LintKind::MissingLocale,
+                format!(
+                    "no locale is available for {}, terms fall back to en-US",
+                    chain.locale()
+                ),
+            );
+        }
+    }
+
+    let cited = linter.reachable(&style.citation.layout, &style.citation.sort);
+    linter.path.push("citation".into());
+    linter.sort(&style.citation.sort);
+    linter.layout(&style.citation.layout);
+    linter.path.pop();
+
+    let mut listed = HashSet::new();
+    if let Some(bibliography) = &style.bibliography {
+        listed = linter.reachable(&bibliography.layout, &bibliography.sort);
+        linter.path.push("bibliography".into());
+        if bibliography.subsequent_author_substitute.is_some() {
+            linter.report(
+                LintKind::UnsupportedFeature,
+                "subsequent-author-substitute is not implemented".into(),
+            );
+        }
+        linter.bibliography_only = true;
+        linter.sort(&bibliography.sort);
+        linter.layout(&bibliography.layout);
+        linter.bibliography_only = false;
+        linter.path.pop();
+    }
+
+    for mac in style.macros.iter() {
+        let name = mac.name.as_str();
+        linter.path.push(format!("macro name=\"{name}\""));
+        if linter.calls_itself(name) {
+            linter.report(
+                LintKind::RecursiveMacro,
+                format!("the macro \"{name}\" calls itself"),
+            );
+        }
+        if !cited.contains(name) && !listed.contains(name) {
+            linter.report(
+                LintKind::UnusedMacro,
+                format!("the macro \"{name}\" is never used"),
+            );
+        }
+        linter.bibliography_only = listed.contains(name) && !cited.contains(name);
+        linter.elements(&mac.children);
+        linter.path.pop();
+    }
+
+    linter.lints
+}
+
+/// Walks the elements of a style and collects lints.
+struct Linter<'a> {
+    /// The macros of the style by name.
+    macros: HashMap<&'a str, &'a CslMacro>,
+    /// The locale chains in which terms are looked up.
+    chains: Vec<LocaleChain<'a>>,
+    /// Whether the current elements are only rendered for the bibliography,
+    /// where no locators are available.
+    bibliography_only: bool,
+    /// The path of the current element.
+    path: Vec<String>,
+    /// The lints found so far.
+    lints: Vec<StyleLint>,
+}
+
+impl<'a> Linter<'a> {
+    /// Report a problem at the current element. Problems that were already
+    /// reported at the same element are ignored.
+    fn report(&mut self, kind: LintKind, message: String) {
+        let lint = StyleLint {
+            severity: kind.severity(),
+            kind,
+            path: self.path.clone(),
+            message,
+        };
+        if !self.lints.contains(&lint) {
+            self.lints.push(lint);
+        }
+    }
+
+    fn sort(&mut self, sort: &Option<Sort>) {
+        let Some(sort) = sort else { return };
+        self.path.push("sort".into());
+        for key in sort.keys.iter() {
+            match key {
+                SortKey::Variable { variable, .. } => {
+                    self.path.push(format!("key variable=\"{variable}\""));
+                    self.variable(*variable);
+                }
+                SortKey::MacroName { name, .. } => {
+                    self.path.push(format!("key macro=\"{name}\""));
+                    self.macro_call(name);
+                }
+            }
+            self.path.pop();
+        }
+        self.path.pop();
+    }
+
+    fn layout(&mut self, layout: &Layout) {
+        self.path.push("layout".into());
+        self.elements(&layout.elements);
+        self.path.pop();
+    }
+
+    fn elements(&mut self, elements: &[LayoutRenderingElement]) {
+        for element in elements {
+            self.element(element);
+        }
+    }
+
+    fn element(&mut self, element: &LayoutRenderingElement) {
+        self.path.push(describe_element(element));
+        match element {
+            LayoutRenderingElement::Text(text) => match &text.target {
+                TextTarget::Variable { var: var @ Variable::Date(_), .. } => self.report(
+                    LintKind::UnsupportedVariable,
+                    format!(
+                        "cs:text cannot print the date variable \"{var}\", use cs:date"
+                    ),
+                ),
+                TextTarget::Variable { var: var @ Variable::Name(_), .. } => self.report(
+                    LintKind::UnsupportedVariable,
+                    format!(
+                        "cs:text cannot print the name variable \"{var}\", use cs:names"
+                    ),
+                ),
+                TextTarget::Variable { var, .. } => self.variable(*var),
+                TextTarget::Macro { name } => self.macro_call(name),
+                TextTarget::Term { term, form, .. } => self.term(*term, *form),
+                TextTarget::Value { .. } => {}
+            },
+            LayoutRenderingElement::Number(number) => {
+                self.variable(number.variable.into());
+            }
+            LayoutRenderingElement::Label(label) => {
+                self.variable(label.variable.into());
+                // The term of the locator label depends on the cited locator.
+                if label.variable != NumberVariable::Locator {
+                    self.term(Term::NumberVariable(label.variable), label.label.form);
+                }
+            }
+            LayoutRenderingElement::Date(date) => {
+                if let Some(variable) = date.variable {
+                    self.variable(variable.into());
+                }
+                if date.date_part.iter().any(|part| part.range_delimiter.is_some()) {
+                    self.report(
+                        LintKind::UnsupportedFeature,
+                        "date ranges are not rendered, so range-delimiter has no effect"
+                            .into(),
+                    );
+                }
+            }
+            LayoutRenderingElement::Names(names) => {
+                for &variable in names.variable.iter() {
+                    self.variable(variable.into());
+                }
+                for child in names.children.iter() {
+                    match child {
+                        NamesChild::Label(label) => {
+                            for &variable in names.variable.iter() {
+                                self.term(Term::NameVariable(variable), label.form);
+                            }
+                        }
+                        NamesChild::Substitute(substitute) => {
+                            self.path.push("substitute".into());
+                            self.elements(&substitute.children);
+                            self.path.pop();
+                        }
+                        NamesChild::Name(_) | NamesChild::EtAl(_) => {}
+                    }
+                }
+            }
+            LayoutRenderingElement::Choose(choose) => self.choose(choose),
+            LayoutRenderingElement::Group(group) => self.elements(&group.children),
+        }
+        self.path.pop();
+    }
+
+    fn choose(&mut self, choose: &Choose) {
+        let mut previous: Vec<&ChooseBranch> = vec![];
+        // Whether an earlier branch is always chosen.
+        let mut always = false;
+        for (i, branch) in choose.branches().enumerate() {
+            let element = if i == 0 { "if" } else { "else-if" };
+            self.path.push(describe_branch(element, branch));
+
+            let variables = branch.variable.iter().flatten().copied();
+            let numeric = branch.is_numeric.iter().flatten().copied();
+            let dates = branch.is_uncertain_date.iter().flatten().map(|&v| v.into());
+            for variable in variables.chain(numeric).chain(dates) {
+                self.variable(variable);
+            }
+
+            if always {
+                self.report(
+                    LintKind::UnreachableCondition,
+                    "an earlier branch is always chosen".into(),
+                );
+            } else if let Some(reason) = self.never_matches(branch) {
+                self.report(LintKind::UnreachableCondition, reason);
+            } else if previous.iter().any(|p| same_conditions(p, branch)) {
+                self.report(
+                    LintKind::UnreachableCondition,
+                    "an earlier branch has the same conditions".into(),
+                );
+            }
+
+            always |= !has_conditions(branch) && branch.match_ != ChooseMatch::Any;
+            previous.push(branch);
+            self.elements(&branch.children);
+            self.path.pop();
+        }
+
+        if let Some(otherwise) = &choose.otherwise {
+            self.path.push("else".into());
+            if always {
+                self.report(
+                    LintKind::UnreachableCondition,
+                    "an earlier branch is always chosen".into(),
+                );
+            }
+            self.elements(&otherwise.children);
+            self.path.pop();
+        }
+    }
+
+    /// Why a branch can never be chosen, if it cannot.
+    fn never_matches(&self, branch: &ChooseBranch) -> Option<String> {
+        if !has_conditions(branch) {
+            return (branch.match_ == ChooseMatch::Any)
+                .then(|| "the branch has no conditions".into());
+        }
+
+        if branch.match_ != ChooseMatch::All {
+            return (self.bibliography_only
+                && branch.match_ == ChooseMatch::Any
+                && !has_conditions_besides_locator(branch))
+            .then(|| "locators are not available in the bibliography".into());
+        }
+
+        if let Some(types) = &branch.type_ {
+            if types.iter().any(|t| *t != types[0]) {
+                return Some("an item cannot have more than one type".into());
+            }
+        }
+
+        if let Some(var) = branch
+            .is_numeric
+            .iter()
+            .flatten()
+            .find(|v| matches!(v, Variable::Date(_) | Variable::Name(_)))
+        {
+            return Some(format!("the variable \"{var}\" is never numeric"));
+        }
+
+        if self.bibliography_only && branch.locator.is_some() {
+            return Some("locators are not available in the bibliography".into());
+        }
+
+        None
+    }
+
+    /// Check that Hayagriva provides a variable.
+    fn variable(&mut self, variable: Variable) {
+        if is_unsupported_variable(variable) {
+            self.report(
+                LintKind::UnsupportedVariable,
+                format!("the variable \"{variable}\" is never set by Hayagriva entries"),
+            );
+        }
+    }
+
+    /// Check that a called macro is defined.
+    fn macro_call(&mut self, name: &str) {
+        if !self.macros.contains_key(name) {
+            self.report(
+                LintKind::UndefinedMacro,
+                format!("the macro \"{name}\" is not defined"),
+            );
+        }
+    }
+
+    /// Check that a term is defined in all locale chains, falling back to
+    /// other forms like the renderer does.
+    fn term(&mut self, term: Term, form: TermForm) {
+        let missing: Vec<_> = self
+            .chains
+            .iter()
+            .filter(|chain| {
+                !successors(Some(form), |form| form.fallback())
+                    .any(|form| chain.iter().any(|l| l.term(term, form).is_some()))
+            })
+            .map(|chain| chain.locale().clone())
+            .collect();
+
+        for locale in missing {
+            self.report(
+                LintKind::MissingTerm,
+                format!("the term \"{}\" is not defined for {locale}", kebab(&term)),
+            );
+        }
+    }
+
+    /// The names of the macros that are called by a layout and its sort keys,
+    /// directly or through other macros.
+    fn reachable(&self, layout: &'a Layout, sort: &'a Option<Sort>) -> HashSet<&'a str> {
+        let mut queue = vec![];
+        macro_calls(&layout.elements, &mut queue);
+        for key in sort.iter().flat_map(|s| s.keys.iter()) {
+            if let SortKey::MacroName { name, .. } = key {
+                queue.push(name.as_str());
+            }
+        }
+        self.closure(queue)
+    }
+
+    /// Whether a macro calls itself, directly or through other macros.
+    fn calls_itself(&self, name: &str) -> bool {
+        let Some(mac) = self.macros.get(name) else { return false };
+        let mut queue = vec![];
+        macro_calls(&mac.children, &mut queue);
+        self.closure(queue).contains(name)
+    }
+
+    /// All macros reachable from the given macros.
+    fn closure(&self, mut queue: Vec<&'a str>) -> HashSet<&'a str> {
+        let mut seen = HashSet::new();
+        while let Some(name) = queue.pop() {
+            if seen.insert(name) {
+                if let Some(mac) = self.macros.get(name) {
+                    macro_calls(&mac.children, &mut queue);
+                }
+            }
+        }
+        seen
+    }
+}
+
+/// Collect the names of the macros that elements call directly.
+fn macro_calls<'a>(elements: &'a [LayoutRenderingElement], out: &mut Vec<&'a str>) {
+    for element in elements {
+        match element {
+            LayoutRenderingElement::Text(text) => {
+                if let TextTarget::Macro { name } = &text.target {
+                    out.push(name);
+                }
+            }
+            LayoutRenderingElement::Names(names) => {
+                for child in names.children.iter() {
+                    if let NamesChild::Substitute(substitute) = child {
+                        macro_calls(&substitute.children, out);
+                    }
+                }
+            }
+            LayoutRenderingElement::Choose(choose) => {
+                for branch in choose.branches() {
+                    macro_calls(&branch.children, out);
+                }
+                if let Some(otherwise) = &choose.otherwise {
+                    macro_calls(&otherwise.children, out);
+                }
+            }
+            LayoutRenderingElement::Group(group) => macro_calls(&group.children, out),
+            LayoutRenderingElement::Number(_)
+            | LayoutRenderingElement::Label(_)
+            | LayoutRenderingElement::Date(_) => {}
+        }
+    }
+}
+
+/// Whether a branch tests any conditions.
+fn has_conditions(branch: &ChooseBranch) -> bool {
+    branch.locator.is_some() || has_conditions_besides_locator(branch)
+}
+
+/// Whether a branch tests any conditions other than `locator`.
+fn has_conditions_besides_locator(branch: &ChooseBranch) -> bool {
+    branch.disambiguate.is_some()
+        || branch.is_numeric.is_some()
+        || branch.is_uncertain_date.is_some()
+        || branch.position.is_some()
+        || branch.type_.is_some()
+        || branch.variable.is_some()
+}
+
+/// Whether two branches test the same conditions.
+fn same_conditions(a: &ChooseBranch, b: &ChooseBranch) -> bool {
+    a.disambiguate == b.disambiguate
+        && a.is_numeric == b.is_numeric
+        && a.is_uncertain_date == b.is_uncertain_date
+        && a.locator == b.locator
+        && a.position == b.position
+        && a.type_ == b.type_
+        && a.variable == b.variable
+        && a.match_ == b.match_
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STYLE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+  <info>
+    <title>Lint</title>
+    <id>lint</id>
+    <updated>2024-01-01T00:00:00+00:00</updated>
+  </info>
+  <locale xml:lang="en">
+    <terms>
+      <term name="in">in</term>
+    </terms>
+  </locale>
+  <macro name="loop">
+    <text macro="loop"/>
+  </macro>
+  <macro name="unused">
+    <text variable="title"/>
+  </macro>
+  <macro name="pages">
+    <choose>
+      <if locator="page">
+        <text variable="locator"/>
+      </if>
+    </choose>
+  </macro>
+  <citation>
+    <layout>
+      <text macro="missing"/>
+      <text macro="loop"/>
+      <names variable="author">
+        <label/>
+      </names>
+    </layout>
+  </citation>
+  <bibliography>
+    <layout>
+      <text macro="pages"/>
+      <text term="in"/>
+      <choose>
+        <if type="book thesis">
+          <text variable="medium"/>
+        </if>
+        <else-if match="all">
+          <text variable="title"/>
+        </else-if>
+        <else>
+          <text variable="publisher"/>
+        </else>
+      </choose>
+    </layout>
+  </bibliography>
+</style>"#;
+
+    #[test]
+    fn lints() {
+        let style = IndependentStyle::from_xml(STYLE).unwrap();
+        let lints = lint_style(&style);
+        let find = |kind: LintKind, last: &str| {
+            lints.iter().find(|l| {
+                l.kind == kind && l.path.last().map(String::as_str) == Some(last)
+            })
+        };
+
+        let undefined = find(LintKind::UndefinedMacro, "text macro=\"missing\"").unwrap();
+        assert_eq!(undefined.severity, LintSeverity::Error);
+        assert_eq!(undefined.path, ["citation", "layout", "text macro=\"missing\""]);
+        assert!(find(LintKind::RecursiveMacro, "macro name=\"loop\"").is_some());
+        assert!(find(LintKind::UnusedMacro, "macro name=\"unused\"").is_some());
+        assert!(find(LintKind::UnusedMacro, "macro name=\"pages\"").is_none());
+        assert!(find(LintKind::UnreachableCondition, "if type=\"book thesis\"").is_some());
+        assert!(find(LintKind::UnreachableCondition, "else").is_some());
+        assert!(find(LintKind::UnreachableCondition, "if locator=\"page\"").is_some());
+        assert!(find(LintKind::UnsupportedVariable, "text variable=\"medium\"").is_some());
+        assert!(find(LintKind::MissingTerm, "names variable=\"author\"").is_some());
+        assert!(find(LintKind::MissingTerm, "text term=\"in\"").is_none());
+        assert!(lints.iter().all(|l| l.kind != LintKind::MissingLocale));
+
+        assert_eq!(
+            undefined.to_string(),
+            "error[undefined-macro] citation > layout > text macro=\"missing\": \
+             the macro \"missing\" is not defined"
+        );
+        assert_eq!(
+            serde_json::to_string(undefined).unwrap(),
+            r#"{"severity":"error","kind":"undefined-macro","path":["citation","layout","text macro=\"missing\""],"message":"the macro \"missing\" is not defined"}"#
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! Resolution of the locales that provide terms and options for a style.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use citationberg::{BaseLanguage, IndependentStyle, Locale, LocaleCode};
+
+/// Builds and caches the chains of locales that are consulted for the terms
+/// and options of a locale.
+///
+/// For a requested locale like `de-AT`, the chain contains, in order:
+///
+/// 1. The locale embedded in the style for `de-AT`,
+/// 2. the embedded locale for `de`,
+/// 3. the embedded locale without a language,
+/// 4. the locale files for `de-AT` and `de`,
+/// 5. the locale file for the primary dialect `de-DE`,
+/// 6. and the locale file for `en-US`.
+///
+/// Only the locale files passed to the resolver are searched. If the
+/// `archive` feature is enabled, [`LocaleResolver::with_archive`] additionally
+/// falls back to the archived locales for languages that none of these files
+/// cover.
+#[derive(Debug)]
+pub struct LocaleResolver<'a> {
+    files: &'a [Locale],
+    archive: bool,
+    chains: Mutex<HashMap<LocaleCode, Arc<[Source]>>>,
+}
+
+/// Where a locale of a chain is defined. Chains store indices rather than
+/// references so that they can be cached without tying the caches to the
+/// lifetime of the style.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(super) enum Source {
+    /// A locale embedded in the style.
+    Style(usize),
+    /// A locale file passed to the resolver.
+    File(usize),
+    /// An archived locale.
+    Archive(usize),
+}
+
+impl<'a> LocaleResolver<'a> {
+    /// Create a resolver that searches the given locale files.
+    pub fn new(files: &'a [Locale]) -> Self {
+        Self {
+            files,
+            archive: false,
+            chains: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set whether to fall back to the archived locales for languages that
+    /// the locale files do not cover. This has no effect if the `archive`
+    /// feature is disabled.
+    pub fn with_archive(mut self, archive: bool) -> Self {
+        self.archive = archive && cfg!(feature = "archive");
+        self.chains.get_mut().unwrap().clear();
+        self
+    }
+
+    /// Resolve the locale chain of a style for a locale. If no locale is
+    /// given, the default locale of the style or `en-US` is used.
+    pub fn resolve<'s>(
+        &self,
+        style: &'s IndependentStyle,
+        locale: Option<&LocaleCode>,
+    ) -> LocaleChain<'s>
+    where
+        'a: 's,
+    {
+        let locale = locale
+            .or(style.default_locale.as_ref())
+            .cloned()
+            .unwrap_or_else(LocaleCode::en_us);
+
+        let base = base_language(&locale);
+        let mut sources = Vec::new();
+        for lang in [Some(&locale), base.as_ref(), None] {
+            sources.extend(
+                style
+                    .locale
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, l)| l.lang.as_ref() == lang)
+                    .map(|(i, _)| Source::Style(i)),
+            );
+        }
+
+        sources.extend(self.file_sources(&locale).iter());
+        LocaleChain {
+            locale,
+            style,
+            files: self.files,
+            sources: sources.into(),
+        }
+    }
+
+    /// The locale files that the resolver searches.
+    pub(super) fn locale_files(&self) -> &'a [Locale] {
+        self.files
+    }
+
+    /// The locale files consulted for a locale, most specific first.
+    pub fn files(&self, locale: &LocaleCode) -> Vec<&'a Locale> {
+        self.file_sources(locale)
+            .iter()
+            .filter_map(|&source| source.get(&[], self.files))
+            .collect()
+    }
+
+    /// The sources of the locale files consulted for a locale.
+    fn file_sources(&self, locale: &LocaleCode) -> Arc<[Source]> {
+        let mut chains = self.chains.lock().unwrap();
+        if let Some(chain) = chains.get(locale) {
+            return chain.clone();
+        }
+
+        let mut codes = vec![locale.clone()];
+        codes.extend(base_language(locale));
+        codes.extend(locale.fallback());
+        codes.push(LocaleCode::en_us());
+
+        let mut chain = Vec::new();
+        for code in codes.iter() {
+            let matches = |l: &Locale| l.lang.as_ref() == Some(code);
+            let before = chain.len();
+            chain.extend(
+                self.files
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, l)| matches(l))
+                    .map(|(i, _)| Source::File(i)),
+            );
+            if chain.len() == before && self.archive {
+                chain.extend(
+                    archived_locales()
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, l)| matches(l))
+                        .map(|(i, _)| Source::Archive(i)),
+                );
+            }
+        }
+
+        let chain: Arc<[Source]> = chain.into();
+        chains.insert(locale.clone(), chain.clone());
+        chain
+    }
+}
+
+impl Source {
+    /// Retrieve the locale.
+    fn get<'a>(self, style: &'a [Locale], files: &'a [Locale]) -> Option<&'a Locale> {
+        match self {
+            Self::Style(i) => style.get(i),
+            Self::File(i) => files.get(i),
+            Self::Archive(i) => archived_locales().get(i),
+        }
+    }
+}
+
+/// The locales that are consulted for the terms and options of a style, most
+/// specific first. Created by a [`LocaleResolver`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LocaleChain<'a> {
+    locale: LocaleCode,
+    style: &'a IndependentStyle,
+    files: &'a [Locale],
+    sources: Arc<[Source]>,
+}
+
+impl<'a> LocaleChain<'a> {
+    /// Recreate a chain from its parts.
+    pub(super) fn from_parts(
+        locale: LocaleCode,
+        style: &'a IndependentStyle,
+        files: &'a [Locale],
+        sources: Arc<[Source]>,
+    ) -> Self {
+        Self { locale, style, files, sources }
+    }
+
+    /// The sources of the locales in the chain.
+    pub(super) fn sources(&self) -> &Arc<[Source]> {
+        &self.sources
+    }
+
+    /// The locale the chain was resolved for.
+    pub fn locale(&self) -> &LocaleCode {
+        &self.locale
+    }
+
+    /// Iterate over the locales in the chain, most specific first.
+    pub fn iter(&self) -> impl Iterator<Item = &'a Locale> + '_ {
+        let (style, files) = (self.style.locale.as_slice(), self.files);
+        self.sources
+            .iter()
+            .filter_map(move |&source| source.get(style, files))
+    }
+
+    /// Whether the chain contains a locale for the language of the requested
+    /// locale. If not, terms come from the `en-US` fallback.
+    pub fn has_language(&self) -> bool {
+        let base = base_language(&self.locale);
+        self.iter()
+            .any(|l| l.lang.as_ref().and_then(base_language).as_ref() == base.as_ref())
+    }
+}
+
+/// The code of the language of a locale without its region, e.g. `de` for
+/// `de-AT`.
+fn base_language(locale: &LocaleCode) -> Option<LocaleCode> {
+    match locale.parse_base()? {
+        BaseLanguage::Iso639_1(lang) => {
+            Some(LocaleCode(String::from_utf8(lang.to_vec()).ok()?))
+        }
+        BaseLanguage::Iana(lang) => Some(LocaleCode(lang)),
+        _ => None,
+    }
+}
+
+/// The archived locales. They are only decoded once.
+#[cfg(feature = "archive")]
+fn archived_locales() -> &'static [Locale] {
+    static LOCALES: std::sync::OnceLock<Vec<Locale>> = std::sync::OnceLock::new();
+    LOCALES.get_or_init(super::archive::locales)
+}
+
+#[cfg(not(feature = "archive"))]
+fn archived_locales() -> &'static [Locale] {
+    &[]
+}
+
+#[cfg(test)]
+mod tests {
+    use citationberg::LocaleFile;
+
+    use super::*;
+
+    fn locale(lang: &str) -> Locale {
+        LocaleFile::from_xml(&format!(
+            r#"<locale xmlns="http://purl.org/net/xbiblio/csl" version="1.0" xml:lang="{lang}"/>"#
+        ))
+        .unwrap()
+        .into()
+    }
+
+    fn langs(chain: &[&Locale]) -> Vec<String> {
+        chain.iter().map(|l| l.lang.as_ref().unwrap().0.clone()).collect()
+    }
+
+    #[test]
+    fn file_chain() {
+        let files = [locale("en-US"), locale("de-DE"), locale("de"), locale("de-AT")];
+        let resolver = LocaleResolver::new(&files);
+
+        let chain = resolver.files(&LocaleCode("de-AT".into()));
+        assert_eq!(langs(&chain), ["de-AT", "de", "de-DE", "en-US"]);
+        assert_eq!(
+            langs(&resolver.files(&LocaleCode("de-CH".into()))),
+            ["de", "de-DE", "en-US"]
+        );
+        assert_eq!(langs(&resolver.files(&LocaleCode("fr-FR".into()))), ["en-US"]);
+        assert_eq!(resolver.files(&LocaleCode("de-AT".into())), chain);
+    }
+
+    #[test]
+    #[cfg(feature = "archive")]
+    fn archive_fallback() {
+        let files = [locale("en-US"), locale("de")];
+        let resolver = LocaleResolver::new(&files).with_archive(true);
+        let chain = resolver.files(&LocaleCode("de-AT".into()));
+        assert_eq!(langs(&chain), ["de-AT", "de", "de-DE", "en-US"]);
+        assert!(std::ptr::eq(chain[1], &files[1]));
+        assert!(std::ptr::eq(chain[3], &files[0]));
+
+        // The archive is only consulted on request.
+        let resolver = LocaleResolver::new(&files);
+        assert_eq!(langs(&resolver.files(&LocaleCode("de-AT".into()))), ["de", "en-US"]);
+    }
+}
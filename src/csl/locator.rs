@@ -0,0 +1,341 @@
+//! Parsing of human-readable locators like "pp. 12–15" or "ch. 3".
+
+use std::cmp::Reverse;
+
+use citationberg::taxonomy::{Locator, Term};
+use citationberg::{IndependentStyle, Locale, LocaleCode, TermForm};
+
+use super::{LocatorPayload, SpecificLocator, StyleContext};
+
+/// Common abbreviations that are not part of the CSL locale files.
+const ALIASES: [(&str, Locator); 10] = [
+    ("ch.", Locator::Chapter),
+    ("chs.", Locator::Chapter),
+    ("pg.", Locator::Page),
+    ("pgs.", Locator::Page),
+    ("sect.", Locator::Section),
+    ("sects.", Locator::Section),
+    ("eq.", Locator::Equation),
+    ("eqs.", Locator::Equation),
+    ("tab.", Locator::Table),
+    ("s.v.", Locator::SubVerbo),
+];
+
+/// A locator parsed from a human-readable string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParsedLocator<'a> {
+    /// The recognized locator and its value.
+    pub locator: SpecificLocator<'a>,
+    /// Text following the locator value that does not belong to it, e.g.
+    /// ", for a critique" in "p. 4, for a critique". It can be used as the
+    /// suffix of a [`CitationItem`](super::CitationItem).
+    pub suffix: Option<&'a str>,
+}
+
+/// Parse a human-readable locator like "pp. 12–15" or "ch. 3".
+///
+/// The label is recognized using the long, short, and symbol forms (singular
+/// and plural) of the locator terms of the locale that the style would use
+/// for the given locale code. The canonical CSL locator names and a few common
+/// English abbreviations are recognized, too. If there is no label, the
+/// locator defaults to [`Locator::Page`].
+///
+/// This collects the labels of the locale on every call. Use a
+/// [`LocatorParser`] to parse multiple locators.
+pub fn parse_locator<'s>(
+    s: &'s str,
+    style: &IndependentStyle,
+    locale: Option<LocaleCode>,
+    locale_files: &[Locale],
+) -> ParsedLocator<'s> {
+    LocatorParser::new(style, locale, locale_files).parse(s)
+}
+
+/// Parses human-readable locators with the terms of a style's locale.
+///
+/// The locator labels are collected once when the parser is created, so it
+/// can be reused for all locators cited with the same style and locale. See
+/// [`parse_locator`] for the recognized labels.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LocatorParser {
+    /// The locator labels of the locale, longest first.
+    labels: Vec<(String, Locator)>,
+}
+
+impl LocatorParser {
+    /// Create a parser for the locale that the style would use for the given
+    /// locale code.
+    pub fn new(
+        style: &IndependentStyle,
+        locale: Option<LocaleCode>,
+        locale_files: &[Locale],
+    ) -> Self {
+        StyleContext::new(style, locale, locale_files).locator_parser()
+    }
+
+    /// Parse a locator string.
+    pub fn parse<'s>(&self, s: &'s str) -> ParsedLocator<'s> {
+        let s = s.trim();
+        let (locator, rest) = self
+            .match_label(s)
+            .map_or((Locator::Page, s), |(locator, rest)| (locator, rest.trim_start()));
+
+        let (value, suffix) = split_locator_value(rest);
+        let (value, suffix) = if value.is_empty() { (rest, "") } else { (value, suffix) };
+
+        ParsedLocator {
+            locator: SpecificLocator(locator, LocatorPayload::Str(value)),
+            suffix: Some(suffix).filter(|s| !s.is_empty()),
+        }
+    }
+
+    /// Find the longest locator label at the start of the string and return
+    /// the locator together with the remaining string.
+    fn match_label<'s>(&self, s: &'s str) -> Option<(Locator, &'s str)> {
+        self.labels.iter().find_map(|(label, locator)| {
+            let rest = strip_prefix_ignore_case(s, label)
+                .filter(|rest| {
+                    // The label must not continue into a word.
+                    label.ends_with(|c: char| !c.is_alphanumeric())
+                        || !matches!(rest.chars().next(), Some(c) if c.is_alphabetic())
+                })
+                .or_else(|| {
+                    // Accept abbreviations without their final period, but
+                    // only if they are followed by a space. Otherwise, "S12"
+                    // would be read as "S. 12".
+                    let rest = strip_prefix_ignore_case(s, label.strip_suffix('.')?)?;
+                    rest.starts_with(char::is_whitespace).then_some(rest)
+                })?;
+            Some((*locator, rest))
+        })
+    }
+}
+
+impl StyleContext<'_> {
+    /// Create a locator parser with the labels of this style's locale.
+    pub(super) fn locator_parser(&self) -> LocatorParser {
+        // The locale files define a term for every locator they know. Terms
+        // like "page" are deserialized as variables or kinds, so the locator
+        // is recognized by the term's name.
+        let mut locators: Vec<Locator> = Vec::new();
+        for term in self
+            .locales
+            .iter()
+            .filter_map(|l| l.terms.as_ref())
+            .flat_map(|t| &t.terms)
+        {
+            let Some(locator) = term_locator(term.name) else { continue };
+            if !locators.contains(&locator) {
+                locators.push(locator);
+            }
+        }
+
+        let mut labels: Vec<(String, Locator)> = Vec::new();
+        for locator in locators {
+            for form in [TermForm::Long, TermForm::Short, TermForm::Symbol] {
+                let Some(term) = self.lookup_locale(|l| l.term(locator.into(), form))
+                else {
+                    continue;
+                };
+
+                for label in [term.single(), term.multiple()].into_iter().flatten() {
+                    labels.push((label.trim().to_string(), locator));
+                }
+            }
+
+            labels.push((locator_name(locator).to_string(), locator));
+        }
+
+        labels
+            .extend(ALIASES.iter().map(|&(label, locator)| (label.to_string(), locator)));
+
+        // Prefer longer labels so that "pp." is not mistaken for "p.".
+        labels.retain(|(label, _)| !label.is_empty());
+        labels.sort_by_key(|(label, _)| Reverse(label.chars().count()));
+        LocatorParser { labels }
+    }
+}
+
+/// The locator that a term with the same name refers to.
+fn term_locator(term: Term) -> Option<Locator> {
+    match term {
+        Term::Locator(locator) => Some(locator),
+        _ => serde_json::to_value(term).ok()?.as_str()?.parse().ok(),
+    }
+}
+
+/// The CSL name of a locator.
+fn locator_name(locator: Locator) -> &'static str {
+    match locator {
+        Locator::Act => "act",
+        Locator::Appendix => "appendix",
+        Locator::ArticleLocator => "article-locator",
+        Locator::Book => "book",
+        Locator::Canon => "canon",
+        Locator::Chapter => "chapter",
+        Locator::Column => "column",
+        Locator::Elocation => "elocation",
+        Locator::Equation => "equation",
+        Locator::Figure => "figure",
+        Locator::Folio => "folio",
+        Locator::Issue => "issue",
+        Locator::Line => "line",
+        Locator::Note => "note",
+        Locator::Opus => "opus",
+        Locator::Page => "page",
+        Locator::Paragraph => "paragraph",
+        Locator::Part => "part",
+        Locator::Rule => "rule",
+        Locator::Scene => "scene",
+        Locator::Section => "section",
+        Locator::SubVerbo => "sub verbo",
+        Locator::Supplement => "supplement",
+        Locator::Table => "table",
+        Locator::Timestamp => "timestamp",
+        Locator::Title => "title",
+        Locator::TitleLocator => "title-locator",
+        Locator::Verse => "verse",
+        Locator::Volume => "volume",
+        Locator::Custom => "",
+    }
+}
+
+/// Strip a prefix from a string, ignoring case.
+fn strip_prefix_ignore_case<'s>(s: &'s str, prefix: &str) -> Option<&'s str> {
+    let mut chars = s.char_indices();
+    for p in prefix.chars() {
+        let (_, c) = chars.next()?;
+        if !c.to_lowercase().eq(p.to_lowercase()) {
+            return None;
+        }
+    }
+
+    Some(chars.next().map_or("", |(i, _)| &s[i..]))
+}
+
+/// Split a string into the locator value at its start and the remainder.
+///
+/// The value is a list of tokens (numbers, Roman numerals, alphanumeric page
+/// numbers, ...) separated by range and list delimiters. A list delimiter
+/// must be followed by a token with a digit to be part of the value.
+fn split_locator_value(s: &str) -> (&str, &str) {
+    let is_token_char = |c: char| c.is_alphanumeric() || matches!(c, '.' | ':' | '/');
+    let token_len = |s: &str| s.find(|c| !is_token_char(c)).unwrap_or(s.len());
+
+    let mut end = token_len(s);
+    if end == 0 {
+        return ("", s);
+    }
+
+    loop {
+        let rest = &s[end..];
+        let trimmed = rest.trim_start();
+        let Some(sep) = trimmed.chars().next() else { break };
+
+        let (is_range, after) = match sep {
+            '-' | '–' | '—' => (true, trimmed.trim_start_matches(['-', '–', '—'])),
+            ',' | '&' => (false, &trimmed[sep.len_utf8()..]),
+            _ => break,
+        };
+
+        let after = after.trim_start();
+        let len = token_len(after);
+        if len == 0
+            || (!is_range
+                && !after[..len].trim_end_matches('.').contains(char::is_numeric))
+        {
+            break;
+        }
+
+        end = s.len() - after.len() + len;
+    }
+
+    // A final period ends the sentence rather than the value.
+    let value = s[..end].trim_end_matches('.');
+    (value, &s[value.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use citationberg::LocaleFile;
+
+    use super::*;
+
+    fn assert_parses(
+        parser: &LocatorParser,
+        s: &str,
+        locator: Locator,
+        value: &str,
+        suffix: Option<&str>,
+    ) {
+        let parsed = parser.parse(s);
+        assert_eq!(
+            parsed.locator,
+            SpecificLocator(locator, LocatorPayload::Str(value)),
+            "{s}"
+        );
+        assert_eq!(parsed.suffix, suffix, "{s}");
+    }
+
+    #[test]
+    fn english_locators() {
+        let workspace = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let en_locale =
+            fs::read_to_string(workspace.join("tests/data/locales-en-US.xml")).unwrap();
+        let locales = [LocaleFile::from_xml(&en_locale).unwrap().into()];
+        let style =
+            fs::read_to_string(workspace.join("tests/data/art-history.csl")).unwrap();
+        let style = IndependentStyle::from_xml(&style).unwrap();
+        let parser = LocatorParser::new(&style, None, &locales);
+
+        assert_parses(&parser, "pp. 12–15", Locator::Page, "12–15", None);
+        assert_parses(&parser, "p.4", Locator::Page, "4", None);
+        assert_parses(&parser, "ch. 3", Locator::Chapter, "3", None);
+        assert_parses(&parser, "chapters 3 & 4", Locator::Chapter, "3 & 4", None);
+        assert_parses(&parser, "§ 12", Locator::Section, "12", None);
+        assert_parses(&parser, "vol 2", Locator::Volume, "2", None);
+        assert_parses(&parser, "bk. 2", Locator::Book, "2", None);
+        assert_parses(&parser, "xiv-xx", Locator::Page, "xiv-xx", None);
+        assert_parses(&parser, "12, 15-17", Locator::Page, "12, 15-17", None);
+        assert_parses(
+            &parser,
+            "p. 4, for a critique",
+            Locator::Page,
+            "4",
+            Some(", for a critique"),
+        );
+        assert_parses(&parser, "fig. 2 (left)", Locator::Figure, "2", Some(" (left)"));
+        assert_parses(&parser, "passim", Locator::Page, "passim", None);
+    }
+
+    #[test]
+    #[cfg(feature = "archive")]
+    fn localized_locators() {
+        let locales = crate::archive::locales();
+        let workspace = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let style =
+            fs::read_to_string(workspace.join("tests/data/art-history.csl")).unwrap();
+        let style = IndependentStyle::from_xml(&style).unwrap();
+
+        let de = LocatorParser::new(&style, Some(LocaleCode("de-DE".into())), &locales);
+        assert_parses(&de, "S. 12–15", Locator::Page, "12–15", None);
+        assert_parses(&de, "Kap. 3", Locator::Chapter, "3", None);
+        assert_parses(&de, "S 4", Locator::Page, "4", None);
+
+        // A prefixed page range is not mistaken for a label.
+        let parsed = de.parse("S12–S19");
+        assert_eq!(
+            parsed.locator,
+            SpecificLocator(Locator::Page, LocatorPayload::Str("S12–S19"))
+        );
+        let numeric: crate::types::Numeric = "S12–S19".parse().unwrap();
+        assert_eq!(numeric.notation(), crate::types::NumberNotation::PrefixedArabic);
+
+        let fr = LocatorParser::new(&style, Some(LocaleCode("fr-FR".into())), &locales);
+        assert_parses(&fr, "chap. 3", Locator::Chapter, "3", None);
+        assert_parses(&fr, "12", Locator::Page, "12", None);
+    }
+}
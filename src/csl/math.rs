@@ -0,0 +1,782 @@
+//! Rendering of math markup in the output formats.
+
+use std::fmt::{self, Write};
+
+use unscanny::Scanner;
+
+use super::BufWriteFormat;
+
+/// Renders the math markup of [`ElemChild::Markup`](super::ElemChild::Markup)
+/// when an element is written to a buffer.
+pub trait MathRenderer {
+    /// Write the math markup in the given output format.
+    fn render(
+        &self,
+        math: &str,
+        format: BufWriteFormat,
+        w: &mut dyn fmt::Write,
+    ) -> fmt::Result;
+}
+
+/// The built-in math renderer.
+///
+/// Converts a common subset of LaTeX and Typst math to MathML for HTML and to
+/// Unicode for plain text and terminals. The LaTeX output receives the markup
+/// as-is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BuiltinMathRenderer;
+
+impl MathRenderer for BuiltinMathRenderer {
+    fn render(
+        &self,
+        math: &str,
+        format: BufWriteFormat,
+        w: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        match format {
+            BufWriteFormat::Plain | BufWriteFormat::VT100 => {
+                w.write_str(&math_to_unicode(math))
+            }
+            BufWriteFormat::Html => w.write_str(&math_to_mathml(math)),
+            BufWriteFormat::Latex => write!(w, "${math}$"),
+        }
+    }
+}
+
+/// A math renderer that writes the markup as-is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RawMathRenderer;
+
+impl MathRenderer for RawMathRenderer {
+    fn render(
+        &self,
+        math: &str,
+        _: BufWriteFormat,
+        w: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        w.write_str(math)
+    }
+}
+
+/// Convert LaTeX or Typst math to Unicode text, e.g. `\alpha^2` to `α²`.
+pub fn math_to_unicode(math: &str) -> String {
+    let mut buf = String::new();
+    for node in parse(math) {
+        node.write_unicode(&mut buf);
+    }
+    buf
+}
+
+/// Convert LaTeX or Typst math to an inline MathML `<math>` element.
+pub fn math_to_mathml(math: &str) -> String {
+    let mut buf = String::from("<math>");
+    let nodes = parse(math);
+    write_mathml_row(&nodes, &mut buf);
+    buf.push_str("</math>");
+    buf
+}
+
+/// A node of parsed math.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// A variable or symbol that is set in italics.
+    Ident(String),
+    /// A number.
+    Number(String),
+    /// An operator or punctuation.
+    Op(String),
+    /// Upright text.
+    Text(String),
+    /// Whitespace in the source.
+    Space,
+    /// Bold content.
+    Bold(Vec<Node>),
+    /// A group of nodes.
+    Group(Vec<Node>),
+    /// A base with an optional subscript and superscript.
+    Script { base: Box<Node>, sub: Option<Box<Node>>, sup: Option<Box<Node>> },
+    /// A fraction.
+    Frac(Box<Node>, Box<Node>),
+    /// A square root.
+    Sqrt(Box<Node>),
+}
+
+/// Symbols that can be written as LaTeX commands or Typst identifiers, with
+/// whether they are operators.
+const SYMBOLS: &[(&str, &str, bool)] = &[
+    ("alpha", "α", false),
+    ("beta", "β", false),
+    ("gamma", "γ", false),
+    ("delta", "δ", false),
+    ("epsilon", "ε", false),
+    ("varepsilon", "ε", false),
+    ("zeta", "ζ", false),
+    ("eta", "η", false),
+    ("theta", "θ", false),
+    ("vartheta", "ϑ", false),
+    ("iota", "ι", false),
+    ("kappa", "κ", false),
+    ("lambda", "λ", false),
+    ("mu", "μ", false),
+    ("nu", "ν", false),
+    ("xi", "ξ", false),
+    ("omicron", "ο", false),
+    ("pi", "π", false),
+    ("rho", "ρ", false),
+    ("sigma", "σ", false),
+    ("tau", "τ", false),
+    ("upsilon", "υ", false),
+    ("phi", "φ", false),
+    ("varphi", "φ", false),
+    ("chi", "χ", false),
+    ("psi", "ψ", false),
+    ("omega", "ω", false),
+    ("Gamma", "Γ", false),
+    ("Delta", "Δ", false),
+    ("Theta", "Θ", false),
+    ("Lambda", "Λ", false),
+    ("Xi", "Ξ", false),
+    ("Pi", "Π", false),
+    ("Sigma", "Σ", false),
+    ("Upsilon", "Υ", false),
+    ("Phi", "Φ", false),
+    ("Psi", "Ψ", false),
+    ("Omega", "Ω", false),
+    ("hbar", "ℏ", false),
+    ("ell", "ℓ", false),
+    ("infty", "∞", false),
+    ("infinity", "∞", false),
+    ("oo", "∞", false),
+    ("partial", "∂", false),
+    ("nabla", "∇", false),
+    ("emptyset", "∅", false),
+    ("nothing", "∅", false),
+    ("AA", "Å", false),
+    ("angstrom", "Å", false),
+    ("degree", "°", false),
+    ("prime", "′", false),
+    ("times", "×", true),
+    ("cdot", "⋅", true),
+    ("dot.op", "⋅", true),
+    ("div", "÷", true),
+    ("pm", "±", true),
+    ("plus.minus", "±", true),
+    ("mp", "∓", true),
+    ("minus.plus", "∓", true),
+    ("leq", "≤", true),
+    ("le", "≤", true),
+    ("lt.eq", "≤", true),
+    ("geq", "≥", true),
+    ("ge", "≥", true),
+    ("gt.eq", "≥", true),
+    ("neq", "≠", true),
+    ("ne", "≠", true),
+    ("eq.not", "≠", true),
+    ("approx", "≈", true),
+    ("equiv", "≡", true),
+    ("sim", "∼", true),
+    ("tilde.op", "∼", true),
+    ("propto", "∝", true),
+    ("prop", "∝", true),
+    ("ll", "≪", true),
+    ("gg", "≫", true),
+    ("to", "→", true),
+    ("rightarrow", "→", true),
+    ("arrow.r", "→", true),
+    ("leftarrow", "←", true),
+    ("arrow.l", "←", true),
+    ("leftrightarrow", "↔", true),
+    ("arrow.l.r", "↔", true),
+    ("Rightarrow", "⇒", true),
+    ("arrow.r.double", "⇒", true),
+    ("rightleftharpoons", "⇌", true),
+    ("harpoons.rtlb", "⇌", true),
+    ("in", "∈", true),
+    ("notin", "∉", true),
+    ("in.not", "∉", true),
+    ("subset", "⊂", true),
+    ("subseteq", "⊆", true),
+    ("subset.eq", "⊆", true),
+    ("cup", "∪", true),
+    ("union", "∪", true),
+    ("cap", "∩", true),
+    ("sect", "∩", true),
+    ("forall", "∀", true),
+    ("exists", "∃", true),
+    ("circ", "∘", true),
+    ("compose", "∘", true),
+    ("sum", "∑", true),
+    ("prod", "∏", true),
+    ("product", "∏", true),
+    ("int", "∫", true),
+    ("integral", "∫", true),
+    ("ldots", "…", true),
+    ("dots", "…", true),
+    ("cdots", "⋯", true),
+    ("dots.c", "⋯", true),
+    ("perp", "⊥", true),
+    ("parallel", "∥", true),
+    ("angle", "∠", true),
+];
+
+/// Typst shorthands for operators.
+const SHORTHANDS: &[(&str, &str)] = &[
+    ("...", "…"),
+    ("<=>", "⇔"),
+    ("->", "→"),
+    ("<-", "←"),
+    ("=>", "⇒"),
+    ("<=", "≤"),
+    (">=", "≥"),
+    ("!=", "≠"),
+];
+
+/// Look up a symbol by its name.
+fn symbol(name: &str) -> Option<Node> {
+    SYMBOLS.iter().find(|(n, _, _)| *n == name).map(|&(_, s, op)| {
+        if op {
+            Node::Op(s.into())
+        } else {
+            Node::Ident(s.into())
+        }
+    })
+}
+
+/// Parse LaTeX or Typst math into nodes.
+fn parse(math: &str) -> Vec<Node> {
+    let mut s = Scanner::new(math);
+    parse_seq(&mut s, None)
+}
+
+/// Parse nodes until the closing delimiter or the end.
+fn parse_seq(s: &mut Scanner, close: Option<char>) -> Vec<Node> {
+    let mut nodes: Vec<Node> = vec![];
+    while let Some(c) = s.peek() {
+        if Some(c) == close {
+            break;
+        }
+
+        if c == '^' || c == '_' {
+            s.eat();
+            let arg = Some(Box::new(parse_script_arg(s)));
+            if nodes.last() == Some(&Node::Space) {
+                nodes.pop();
+            }
+
+            // A script attaches to the previous node unless that already has
+            // a script of the same kind.
+            let free = |sub: &Option<_>, sup: &Option<_>| {
+                if c == '^' {
+                    sup.is_none()
+                } else {
+                    sub.is_none()
+                }
+            };
+            let (base, mut sub, mut sup) = match nodes.pop() {
+                Some(Node::Script { base, sub, sup }) if free(&sub, &sup) => {
+                    (base, sub, sup)
+                }
+                Some(node) => (Box::new(node), None, None),
+                None => (Box::new(Node::Group(vec![])), None, None),
+            };
+            if c == '^' {
+                sup = arg;
+            } else {
+                sub = arg;
+            }
+            nodes.push(Node::Script { base, sub, sup });
+            continue;
+        }
+
+        if let Some(node) = parse_atom(s) {
+            if node == Node::Space && matches!(nodes.last(), Some(Node::Space) | None) {
+                continue;
+            }
+            nodes.push(node);
+        }
+    }
+
+    if nodes.last() == Some(&Node::Space) {
+        nodes.pop();
+    }
+    nodes
+}
+
+/// Parse the argument of a subscript or superscript. Braces and parentheses
+/// around the argument are removed.
+fn parse_script_arg(s: &mut Scanner) -> Node {
+    s.eat_whitespace();
+    if s.eat_if('{') {
+        let nodes = parse_seq(s, Some('}'));
+        s.eat_if('}');
+        return group(nodes);
+    }
+    if s.eat_if('(') {
+        let nodes = parse_seq(s, Some(')'));
+        s.eat_if(')');
+        return group(nodes);
+    }
+    parse_atom(s).unwrap_or(Node::Group(vec![]))
+}
+
+/// Parse a single node.
+fn parse_atom(s: &mut Scanner) -> Option<Node> {
+    let c = s.peek()?;
+    if c.is_whitespace() {
+        s.eat_whitespace();
+        return Some(Node::Space);
+    }
+
+    if let Some(&(short, op)) =
+        SHORTHANDS.iter().find(|(short, _)| s.after().starts_with(short))
+    {
+        s.jump(s.cursor() + short.len());
+        return Some(Node::Op(op.into()));
+    }
+
+    match c {
+        '\\' => {
+            s.eat();
+            Some(parse_command(s))
+        }
+        '{' => {
+            s.eat();
+            let nodes = parse_seq(s, Some('}'));
+            s.eat_if('}');
+            Some(group(nodes))
+        }
+        '"' => {
+            s.eat();
+            let text = s.eat_until('"');
+            s.eat_if('"');
+            Some(Node::Text(text.into()))
+        }
+        c if c.is_ascii_digit() => {
+            let start = s.cursor();
+            s.eat_while(|c: char| c.is_ascii_digit());
+            if s.after().starts_with('.')
+                && s.after()[1..].starts_with(|c: char| c.is_ascii_digit())
+            {
+                s.eat();
+                s.eat_while(|c: char| c.is_ascii_digit());
+            }
+            Some(Node::Number(s.from(start).into()))
+        }
+        c if c.is_alphabetic() => Some(parse_word(s)),
+        '-' => {
+            s.eat();
+            Some(Node::Op("−".into()))
+        }
+        '*' => {
+            s.eat();
+            Some(Node::Op("∗".into()))
+        }
+        _ => {
+            s.eat();
+            Some(Node::Op(c.into()))
+        }
+    }
+}
+
+/// Parse a LaTeX command after the backslash.
+fn parse_command(s: &mut Scanner) -> Node {
+    let name = s.eat_while(char::is_alphabetic);
+    if name.is_empty() {
+        return match s.eat() {
+            Some(',' | ';' | ':' | ' ' | '!') => Node::Space,
+            Some(c) => Node::Op(c.into()),
+            None => Node::Op("\\".into()),
+        };
+    }
+
+    match name {
+        "frac" | "dfrac" | "tfrac" => {
+            let num = parse_script_arg(s);
+            let den = parse_script_arg(s);
+            Node::Frac(Box::new(num), Box::new(den))
+        }
+        "sqrt" => Node::Sqrt(Box::new(parse_script_arg(s))),
+        "text" | "mathrm" | "textrm" | "operatorname" | "mathup" => {
+            Node::Text(plain_arg(s))
+        }
+        "mathbf" | "textbf" | "boldsymbol" => match parse_script_arg(s) {
+            Node::Group(nodes) => Node::Bold(nodes),
+            node => Node::Bold(vec![node]),
+        },
+        "mathit" | "textit" => parse_script_arg(s),
+        "left" | "right" | "big" | "Big" | "bigl" | "bigr" | "displaystyle" => {
+            Node::Group(vec![])
+        }
+        _ => symbol(name).unwrap_or_else(|| Node::Text(name.into())),
+    }
+}
+
+/// Parse an identifier, a Typst symbol, or a Typst function call.
+fn parse_word(s: &mut Scanner) -> Node {
+    let start = s.cursor();
+    let word = s.eat_while(char::is_alphabetic);
+
+    // Typst symbols can have modifiers separated by dots.
+    let mut name = word.to_string();
+    let mut end = s.cursor();
+    let mut found = symbol(word).map(|_| (name.clone(), end));
+    while s.after().starts_with('.')
+        && s.after()[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+    {
+        s.eat();
+        name.push('.');
+        name.push_str(s.eat_while(|c: char| c.is_ascii_alphabetic()));
+        end = s.cursor();
+        if symbol(&name).is_some() {
+            found = Some((name.clone(), end));
+        }
+    }
+
+    if let Some((name, end)) = found {
+        s.jump(end);
+        return symbol(&name).unwrap();
+    }
+    s.jump(start + word.len());
+
+    if s.peek() == Some('(') {
+        match word {
+            "sqrt" | "frac" | "upright" | "bold" | "italic" => {
+                s.eat();
+                let mut args = group(parse_seq(s, Some(')'))).split_comma();
+                s.eat_if(')');
+                return match (word, args.len()) {
+                    ("frac", 2) => {
+                        let den = args.pop().unwrap();
+                        let num = args.pop().unwrap();
+                        Node::Frac(Box::new(num), Box::new(den))
+                    }
+                    ("sqrt", _) => Node::Sqrt(Box::new(group(args))),
+                    ("bold", _) => Node::Bold(args),
+                    ("upright", _) => Node::Text(group(args).to_unicode()),
+                    _ => group(args),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    if word.chars().count() == 1 {
+        Node::Ident(word.into())
+    } else {
+        // Several letters are a product of variables in LaTeX and a text in
+        // Typst functions like `sin`. Both are set upright here unless they
+        // are single letters.
+        Node::Text(word.into())
+    }
+}
+
+/// Read the argument of a command as plain text.
+fn plain_arg(s: &mut Scanner) -> String {
+    s.eat_whitespace();
+    if !s.eat_if('{') {
+        return s.eat().map(String::from).unwrap_or_default();
+    }
+
+    let start = s.cursor();
+    let mut depth = 1;
+    while let Some(c) = s.eat() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return s.from(start)[..s.cursor() - start - 1].into();
+                }
+            }
+            _ => {}
+        }
+    }
+    s.from(start).into()
+}
+
+/// Turn a list of nodes into one node.
+fn group(mut nodes: Vec<Node>) -> Node {
+    if nodes.len() == 1 {
+        nodes.pop().unwrap()
+    } else {
+        Node::Group(nodes)
+    }
+}
+
+impl Node {
+    /// Split a group at top-level commas, for the arguments of a Typst
+    /// function.
+    fn split_comma(self) -> Vec<Node> {
+        let nodes = match self {
+            Node::Group(nodes) => nodes,
+            node => return vec![node],
+        };
+
+        let mut args = vec![];
+        let mut current = vec![];
+        for node in nodes {
+            if node == Node::Op(",".into()) {
+                args.push(group(trim_space(std::mem::take(&mut current))));
+            } else {
+                current.push(node);
+            }
+        }
+        args.push(group(trim_space(current)));
+        args
+    }
+
+    /// Render the node as Unicode text.
+    fn to_unicode(&self) -> String {
+        let mut buf = String::new();
+        self.write_unicode(&mut buf);
+        buf
+    }
+
+    /// Write the node as Unicode text.
+    fn write_unicode(&self, buf: &mut String) {
+        match self {
+            Node::Ident(s) | Node::Number(s) | Node::Op(s) | Node::Text(s) => {
+                buf.push_str(s)
+            }
+            Node::Space => buf.push(' '),
+            Node::Bold(nodes) | Node::Group(nodes) => {
+                nodes.iter().for_each(|n| n.write_unicode(buf))
+            }
+            Node::Script { base, sub, sup } => {
+                base.write_unicode(buf);
+                if let Some(sub) = sub {
+                    write_script(&sub.to_unicode(), '_', subscript, buf);
+                }
+                if let Some(sup) = sup {
+                    write_script(&sup.to_unicode(), '^', superscript, buf);
+                }
+            }
+            Node::Frac(num, den) => {
+                buf.push_str(&parenthesize(num.to_unicode()));
+                buf.push('/');
+                buf.push_str(&parenthesize(den.to_unicode()));
+            }
+            Node::Sqrt(arg) => {
+                buf.push('√');
+                buf.push_str(&parenthesize(arg.to_unicode()));
+            }
+        }
+    }
+
+    /// Write the node as MathML.
+    fn write_mathml(&self, buf: &mut String) {
+        match self {
+            Node::Ident(s) => {
+                write!(buf, "<mi>{}</mi>", escape(s)).unwrap();
+            }
+            Node::Number(s) => write!(buf, "<mn>{}</mn>", escape(s)).unwrap(),
+            Node::Op(s) => write!(buf, "<mo>{}</mo>", escape(s)).unwrap(),
+            Node::Text(s) => write!(buf, "<mtext>{}</mtext>", escape(s)).unwrap(),
+            Node::Space => {}
+            Node::Bold(nodes) => {
+                buf.push_str("<mstyle mathvariant=\"bold\">");
+                write_mathml_row(nodes, buf);
+                buf.push_str("</mstyle>");
+            }
+            Node::Group(nodes) => write_mathml_row(nodes, buf),
+            Node::Script { base, sub, sup } => {
+                let tag = match (sub, sup) {
+                    (Some(_), Some(_)) => "msubsup",
+                    (Some(_), None) => "msub",
+                    _ => "msup",
+                };
+                write!(buf, "<{tag}>").unwrap();
+                base.write_mathml_single(buf);
+                for script in [sub, sup].into_iter().flatten() {
+                    script.write_mathml_single(buf);
+                }
+                write!(buf, "</{tag}>").unwrap();
+            }
+            Node::Frac(num, den) => {
+                buf.push_str("<mfrac>");
+                num.write_mathml_single(buf);
+                den.write_mathml_single(buf);
+                buf.push_str("</mfrac>");
+            }
+            Node::Sqrt(arg) => {
+                buf.push_str("<msqrt>");
+                arg.write_mathml_single(buf);
+                buf.push_str("</msqrt>");
+            }
+        }
+    }
+
+    /// Write the node as a single MathML element, as required for the
+    /// arguments of scripts and fractions.
+    fn write_mathml_single(&self, buf: &mut String) {
+        match self {
+            Node::Group(nodes) => {
+                buf.push_str("<mrow>");
+                nodes.iter().for_each(|n| n.write_mathml(buf));
+                buf.push_str("</mrow>");
+            }
+            node => node.write_mathml(buf),
+        }
+    }
+}
+
+/// Write nodes as a MathML row.
+fn write_mathml_row(nodes: &[Node], buf: &mut String) {
+    if nodes.len() == 1 {
+        nodes[0].write_mathml(buf);
+    } else {
+        buf.push_str("<mrow>");
+        nodes.iter().for_each(|n| n.write_mathml(buf));
+        buf.push_str("</mrow>");
+    }
+}
+
+/// Remove leading and trailing spaces.
+fn trim_space(mut nodes: Vec<Node>) -> Vec<Node> {
+    while nodes.last() == Some(&Node::Space) {
+        nodes.pop();
+    }
+    while nodes.first() == Some(&Node::Space) {
+        nodes.remove(0);
+    }
+    nodes
+}
+
+/// Write a script with Unicode subscript or superscript characters, or with a
+/// caret or underscore if not all characters are available.
+fn write_script(
+    script: &str,
+    marker: char,
+    map: fn(char) -> Option<char>,
+    buf: &mut String,
+) {
+    match script.chars().map(map).collect::<Option<String>>() {
+        Some(mapped) => buf.push_str(&mapped),
+        None if script.chars().count() > 1 => {
+            buf.push(marker);
+            buf.push('(');
+            buf.push_str(script);
+            buf.push(')');
+        }
+        None => {
+            buf.push(marker);
+            buf.push_str(script);
+        }
+    }
+}
+
+/// Wrap a rendered argument in parentheses if it is longer than one
+/// character and not a plain word or number.
+fn parenthesize(s: String) -> String {
+    if s.chars().count() > 1 && !s.chars().all(char::is_alphanumeric) {
+        format!("({s})")
+    } else {
+        s
+    }
+}
+
+/// The superscript version of a character.
+fn superscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '−' | '-' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'n' => 'ⁿ',
+        'i' => 'ⁱ',
+        '′' => '′',
+        '∗' => '*',
+        _ => return None,
+    })
+}
+
+/// The subscript version of a character.
+fn subscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '−' | '-' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'o' => 'ₒ',
+        'x' => 'ₓ',
+        'h' => 'ₕ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'p' => 'ₚ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'r' => 'ᵣ',
+        'u' => 'ᵤ',
+        'v' => 'ᵥ',
+        _ => return None,
+    })
+}
+
+/// Escape text for MathML.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unicode() {
+        assert_eq!(math_to_unicode(r"\alpha"), "α");
+        assert_eq!(math_to_unicode("alpha"), "α");
+        assert_eq!(math_to_unicode(r"x^2 + y_{10}"), "x² + y₁₀");
+        assert_eq!(math_to_unicode("x^(n+1) -> oo"), "xⁿ⁺¹ → ∞");
+        assert_eq!(math_to_unicode(r"\mathrm{CO}_2"), "CO₂");
+        assert_eq!(math_to_unicode(r"e^{i\pi} = -1"), "e^(iπ) = −1");
+        assert_eq!(math_to_unicode(r"\frac{a+b}{2}"), "(a+b)/2");
+        assert_eq!(math_to_unicode("sqrt(2) plus.minus 1"), "√2 ± 1");
+        assert_eq!(math_to_unicode(r"10^{-3}\,\text{mol}"), "10⁻³ mol");
+    }
+
+    #[test]
+    fn mathml() {
+        assert_eq!(math_to_mathml(r"\alpha"), "<math><mi>α</mi></math>");
+        assert_eq!(
+            math_to_mathml("x_i^2 < 1"),
+            "<math><mrow><msubsup><mi>x</mi><mi>i</mi><mn>2</mn></msubsup>\
+             <mo>&lt;</mo><mn>1</mn></mrow></math>"
+        );
+        assert_eq!(
+            math_to_mathml(r"\frac{1}{\sqrt{n}}"),
+            "<math><mfrac><mn>1</mn><msqrt><mi>n</mi></msqrt></mfrac></math>"
+        );
+        assert_eq!(
+            math_to_mathml("frac(a, b)"),
+            "<math><mfrac><mi>a</mi><mi>b</mi></mfrac></math>"
+        );
+    }
+}
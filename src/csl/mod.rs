@@ -5,17 +5,18 @@ use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Write};
 use std::hash::Hash;
 use std::num::{NonZeroI16, NonZeroUsize};
-use std::{mem, vec};
+use std::sync::Arc;
+use std::{mem, ptr, vec};
 
 use citationberg::taxonomy::{
     DateVariable, Locator, NameVariable, NumberVariable, OtherTerm, StandardVariable,
     Term, Variable,
 };
 use citationberg::{
-    taxonomy as csl_taxonomy, Affixes, BaseLanguage, Citation, CitationFormat, Collapse,
-    CslMacro, Display, GrammarGender, IndependentStyle, InheritableNameOptions, Layout,
-    LayoutRenderingElement, Locale, LocaleCode, Names, SecondFieldAlign, StyleCategory,
-    StyleClass, TermForm, ToFormatting,
+    taxonomy as csl_taxonomy, Affixes, Citation, CitationFormat, Collapse, CslMacro,
+    DisambiguationRule, Display, GrammarGender, IndependentStyle, InheritableNameOptions,
+    Layout, LayoutRenderingElement, Locale, LocaleCode, Names, SecondFieldAlign,
+    StyleCategory, StyleClass, TermForm, ToFormatting,
 };
 use citationberg::{DateForm, LongShortForm, OrdinalLookup, TextCase};
 use indexmap::IndexSet;
@@ -23,19 +24,46 @@ use indexmap::IndexSet;
 use crate::csl::elem::{simplify_children, NonEmptyStack};
 use crate::csl::rendering::names::NameDisambiguationProperties;
 use crate::csl::rendering::RenderCsl;
+use crate::lang::collation::{Collator, Collators};
 use crate::lang::CaseFolder;
-use crate::types::{ChunkKind, ChunkedString, Date, MaybeTyped, Person};
+use crate::types::{ChunkKind, ChunkedString, Date, LanguageVariant, MaybeTyped, Person};
 
-use self::elem::last_text_mut_child;
+pub use self::abbreviations::Abbreviations;
+pub use self::compiled::CompiledStyle;
+#[cfg(feature = "archive")]
+pub use self::dependent::resolve_archived_style;
+pub use self::dependent::{apply_dependent, resolve_style};
+use self::elem::{last_text_child, last_text_mut_child};
 pub use self::elem::{
     BufWriteFormat, Elem, ElemChild, ElemChildren, ElemMeta, Formatted, Formatting,
 };
-use self::taxonomy::{EntryLike, NumberVariableResult};
+pub use self::intext::InText;
+pub use self::lint::{
+    lint_style, lint_style_with_locales, LintKind, LintSeverity, StyleLint,
+};
+pub use self::locale::{LocaleChain, LocaleResolver};
+pub use self::locator::{parse_locator, LocatorParser, ParsedLocator};
+pub use self::math::{
+    math_to_mathml, math_to_unicode, BuiltinMathRenderer, MathRenderer, RawMathRenderer,
+};
+pub use self::registry::{RegistryError, StyleEntry, StyleOrigin, StyleRegistry};
+use self::rendering::trace::Tracer;
+pub use self::rendering::trace::{RenderTrace, SuppressionReason, TraceEvent, TraceKind};
+use self::taxonomy::{letter, EntryLike, NumberVariableResult};
 
+mod abbreviations;
 #[cfg(feature = "archive")]
 pub mod archive;
 mod citation_label;
+mod compiled;
+mod dependent;
 mod elem;
+mod intext;
+mod lint;
+mod locale;
+mod locator;
+mod math;
+mod registry;
 mod rendering;
 mod sort;
 mod taxonomy;
@@ -45,11 +73,23 @@ mod taxonomy;
 pub struct BibliographyDriver<'a, T: EntryLike> {
     /// The citations we have seen so far.
     citations: Vec<CitationRequest<'a, T>>,
+    /// Entries that appear in the bibliography without being cited.
+    uncited: IndexSet<&'a T>,
+    /// The collators used for sorting, per locale.
+    collators: Collators,
+    /// The resolvers for the locale files of the requests. They cache the
+    /// locale chains across requests.
+    resolvers: Vec<LocaleResolver<'a>>,
 }
 
 impl<T: EntryLike> Default for BibliographyDriver<'_, T> {
     fn default() -> Self {
-        Self { citations: Vec::new() }
+        Self {
+            citations: Vec::new(),
+            uncited: IndexSet::new(),
+            collators: Collators::default(),
+            resolvers: Vec::new(),
+        }
     }
 }
 
@@ -67,6 +107,15 @@ struct SpeculativeItemRender<'a, T: EntryLike> {
     locale: Option<LocaleCode>,
     purpose: Option<CitePurpose>,
     collapse_verdict: Option<CollapseVerdict>,
+    prefix: Option<ElemChildren>,
+    suffix: Option<ElemChildren>,
+}
+
+impl<T: EntryLike> SpeculativeItemRender<'_, T> {
+    /// Whether the item has a prefix or a suffix.
+    fn has_affixes(&self) -> bool {
+        self.prefix.is_some() || self.suffix.is_some()
+    }
 }
 
 #[derive(Debug, Hash, PartialEq, Eq)]
@@ -81,9 +130,27 @@ impl<'a, T: EntryLike> BibliographyDriver<'a, T> {
         Self::default()
     }
 
+    /// Resolve the locale chains of requests with the resolver's locale files
+    /// with the given resolver, e.g. one that falls back to the archived
+    /// locales. By default, the driver creates a resolver for each set of
+    /// locale files it encounters.
+    pub fn with_resolver(mut self, resolver: LocaleResolver<'a>) -> Self {
+        self.resolvers
+            .retain(|r| !ptr::eq(r.locale_files(), resolver.locale_files()));
+        self.resolvers.push(resolver);
+        self
+    }
+
     /// Create a new citation with the given items.
     pub fn citation(&mut self, mut req: CitationRequest<'a, T>) {
-        let style = req.style();
+        if req.compiled.is_none() {
+            let chain = self
+                .resolver(req.locale_files)
+                .resolve(req.style, req.locale.as_ref());
+            req.chain = Some(chain);
+        }
+
+        let style = req.style().with_collators(&self.collators);
 
         for (i, item) in req.items.iter_mut().enumerate() {
             item.initial_idx = i;
@@ -91,6 +158,40 @@ impl<'a, T: EntryLike> BibliographyDriver<'a, T> {
         style.sort(&mut req.items, style.csl.citation.sort.as_ref(), req.locale.as_ref());
         self.citations.push(req);
     }
+
+    /// The resolver for the given locale files. It is created on first use.
+    fn resolver(&mut self, files: &'a [Locale]) -> &LocaleResolver<'a> {
+        let idx =
+            match self.resolvers.iter().position(|r| ptr::eq(r.locale_files(), files)) {
+                Some(idx) => idx,
+                None => {
+                    self.resolvers.push(LocaleResolver::new(files));
+                    self.resolvers.len() - 1
+                }
+            };
+        &self.resolvers[idx]
+    }
+}
+
+impl<'a, T: EntryLike + Hash + Eq> BibliographyDriver<'a, T> {
+    /// Add an entry to the bibliography without citing it.
+    ///
+    /// The entry is sorted, numbered, and disambiguated along with the cited
+    /// entries. If the bibliography is not sorted, uncited entries are placed
+    /// after all cited entries in the order they were added.
+    pub fn nocite(&mut self, entry: &'a T) {
+        self.uncited.insert(entry);
+    }
+
+    /// Add all given entries to the bibliography without citing them.
+    ///
+    /// Pass a [`Library`](crate::Library) to include every entry in it.
+    pub fn nocite_all<I>(&mut self, entries: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.uncited.extend(entries);
+    }
 }
 
 /// Implementations for finishing the bibliography.
@@ -99,7 +200,19 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
     pub fn finish(self, request: BibliographyRequest<'_>) -> Rendered {
         // 1.  Assign citation numbers by bibliography ordering or by citation
         //     order and render them a first time without their locators.
-        let bib_style = request.style();
+        let local_resolver;
+        let resolver = match self
+            .resolvers
+            .iter()
+            .find(|r| ptr::eq(r.locale_files(), request.locale_files))
+        {
+            Some(resolver) => resolver,
+            None => {
+                local_resolver = LocaleResolver::new(request.locale_files);
+                &local_resolver
+            }
+        };
+        let bib_style = request.style(resolver).with_collators(&self.collators);
 
         // Only remember each entry once, even if it is cited multiple times.
         let mut entry_set = IndexSet::new();
@@ -109,6 +222,36 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
             }
         }
 
+        // Uncited entries take part in the first render and in disambiguation
+        // through a hidden citation that is never output.
+        let uncited_items: Vec<_> = self
+            .uncited
+            .iter()
+            .filter(|&&entry| entry_set.insert(entry))
+            .map(|&entry| {
+                let mut item = CitationItem::with_entry(entry);
+                item.hidden = true;
+                item
+            })
+            .collect();
+        let uncited_request = (!uncited_items.is_empty()).then(|| {
+            let mut uncited = CitationRequest::new(
+                uncited_items,
+                request.style,
+                request.locale.clone(),
+                request.locale_files,
+                None,
+            )
+            .with_language_variants(request.language_variants.clone());
+            uncited.abbreviations = request.abbreviations;
+            uncited.compiled = request.compiled;
+            if uncited.compiled.is_none() {
+                uncited.chain =
+                    Some(resolver.resolve(request.style, request.locale.as_ref()));
+            }
+            uncited
+        });
+
         let mut entries: Vec<_> =
             entry_set.into_iter().map(CitationItem::with_entry).collect();
         bib_style.sort(
@@ -116,15 +259,16 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
             bib_style.csl.bibliography.as_ref().and_then(|b| b.sort.as_ref()),
             request.locale.as_ref(),
         );
-        let citation_number = |item: &T| {
-            entries.iter().position(|e| e.entry == item).expect("entry not found")
-        };
+        let citation_numbers: HashMap<&T, usize> =
+            entries.iter().enumerate().map(|(i, e)| (e.entry, i)).collect();
+        let citation_number =
+            |item: &T| *citation_numbers.get(item).expect("entry not found");
 
         let mut seen: HashSet<*const T> = HashSet::new();
         let mut res: Vec<SpeculativeCiteRender<T>> = Vec::new();
         let mut last_cite: Option<&CitationItem<T>> = None;
 
-        for citation in &self.citations {
+        for citation in self.citations.iter().chain(uncited_request.as_ref()) {
             let items = &citation.items;
             let style = citation.style();
 
@@ -168,12 +312,15 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                     ),
                 };
 
+                // Disambiguation works on the regular form of the cite so
+                // that items cited with a purpose are disambiguated like any
+                // other item.
                 let ctx = style.do_citation(
                     *entry,
                     cite_props.clone(),
                     item.locale.as_ref(),
                     citation.locale.as_ref(),
-                    item.purpose,
+                    None,
                     None,
                 );
 
@@ -195,6 +342,8 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                     locale: item.locale.clone(),
                     purpose: item.purpose,
                     collapse_verdict: None,
+                    prefix: item.prefix.clone(),
+                    suffix: item.suffix.clone(),
                 });
 
                 last_cite = Some(item);
@@ -205,6 +354,10 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
 
         // 2.  Disambiguate the citations.
         //
+        // Unless the rule is `by-cite`, ambiguous names are expanded in all
+        // cites first, whether the cite is ambiguous or not.
+        disambiguate_given_names(&mut res);
+
         // If we have set the disambiguation state for an item, we need to set
         // the same state for all entries referencing that item.
         for _ in 0..6 {
@@ -265,6 +418,10 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
 
         // 3. Group adjacent citations.
         for cite in res.iter_mut() {
+            if !cite.request.shall_affix() {
+                continue;
+            }
+
             // This map contains the last index of each entry with this names
             // elem.
             let mut map: HashMap<String, usize> = HashMap::new();
@@ -375,15 +532,21 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
 
         // Rerender.
         let mut final_citations: Vec<RenderedCitation> = Vec::new();
-        for cite in res.iter_mut() {
-            let style_ctx = cite.request.style();
+        for cite in res.iter_mut().take(self.citations.len()) {
+            let style_ctx = cite.request.style().with_trace(request.trace);
             // 5. Collapse grouped citations.
             if cite.request.items.iter().all(|c| c.purpose.is_none()) {
                 collapse_items(cite);
             }
 
+            let mut traces = Vec::new();
             for item in cite.items.iter_mut() {
-                item.rendered = last_purpose_render(&style_ctx, item, cite.request);
+                let (rendered, trace) =
+                    last_purpose_render(&style_ctx, item, cite.request);
+                item.rendered = rendered;
+                if !item.hidden {
+                    traces.extend(trace);
+                }
             }
 
             // 6. Add affixes.
@@ -392,6 +555,7 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
 
             final_citations.push(RenderedCitation {
                 note_number: cite.request.note_number,
+                traces,
                 citation: if cite.items.iter().all(|i| i.hidden) {
                     ElemChildren::new()
                 } else {
@@ -404,6 +568,7 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                         }));
                     }
 
+                    let mut after_suffix = false;
                     for (i, item) in cite.items.iter().enumerate() {
                         if item.hidden {
                             continue;
@@ -422,20 +587,29 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                                     .delimiter
                                     .as_deref())
                             {
-                                elem_children.push(ElemChild::Text(Formatted {
-                                    text: delim.to_string(),
+                                push_delimiter(
+                                    &mut elem_children,
+                                    delim,
                                     formatting,
-                                }));
+                                    after_suffix,
+                                );
                             }
                         }
 
-                        elem_children.push(ElemChild::Elem(Elem {
-                            children: item.rendered.clone(),
-                            display: None,
-                            meta: Some(ElemMeta::Entry(
-                                item.cite_props.certain.initial_idx,
-                            )),
-                        }));
+                        push_affixed_item(
+                            &style_ctx,
+                            &mut elem_children,
+                            Elem {
+                                children: item.rendered.clone(),
+                                display: None,
+                                meta: Some(ElemMeta::Entry(
+                                    item.cite_props.certain.initial_idx,
+                                )),
+                            },
+                            item.prefix.as_ref(),
+                            item.suffix.as_ref(),
+                        );
+                        after_suffix = item.suffix.is_some();
                     }
 
                     if let Some(suffix) = cite.request.suffix() {
@@ -454,32 +628,36 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
         }
 
         let bib_render = if let Some(bibliography) = &request.style.bibliography {
+            // The first cite of each entry provides its properties.
+            let mut cited_items = HashMap::new();
+            for item in res.iter().flat_map(|cite| cite.items.iter()) {
+                cited_items.entry(item.entry).or_insert(item);
+            }
+
+            let bib_style = bib_style.with_trace(request.trace);
             let mut items = Vec::new();
-            for entry in entries.into_iter() {
-                let cited_item = res
-                    .iter()
-                    .flat_map(|cite| cite.items.iter())
-                    .find(|item| item.entry == entry.entry)
-                    .unwrap();
+            for entry in entries.iter() {
+                let cited_item = cited_items[entry.entry];
+                let (rendered, trace) = bib_style
+                    .do_bibliography(
+                        entry.entry,
+                        CiteProperties {
+                            certain: cited_item.cite_props.certain,
+                            speculative: cited_item
+                                .cite_props
+                                .speculative
+                                .for_bibliography(),
+                        },
+                        cited_item.locale.as_ref(),
+                        request.locale.as_ref(),
+                    )
+                    .unwrap()
+                    .flush_traced();
 
                 items.push((
-                    simplify_children(
-                        bib_style
-                            .bibliography(
-                                entry.entry,
-                                CiteProperties {
-                                    certain: cited_item.cite_props.certain,
-                                    speculative: cited_item
-                                        .cite_props
-                                        .speculative
-                                        .for_bibliography(),
-                                },
-                                cited_item.locale.as_ref(),
-                                request.locale.as_ref(),
-                            )
-                            .unwrap(),
-                    ),
+                    simplify_children(rendered),
                     entry.entry.key().to_string(),
+                    trace,
                 ))
             }
 
@@ -490,11 +668,11 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                 entry_spacing: bibliography.entry_spacing,
                 items: items
                     .into_iter()
-                    .map(|(mut i, key)| {
+                    .map(|(mut i, key, trace)| {
                         if bibliography.second_field_align.is_some() {
-                            BibliographyItem::new(key, i.remove_any_meta(), i)
+                            BibliographyItem::new(key, i.remove_any_meta(), i, trace)
                         } else {
-                            BibliographyItem::new(key, None, i)
+                            BibliographyItem::new(key, None, i, trace)
                         }
                     })
                     .collect(),
@@ -517,7 +695,7 @@ pub fn standalone_citation<T: EntryLike>(
 ) -> ElemChildren {
     let style = req.style();
     style.sort(&mut req.items, style.csl.citation.sort.as_ref(), req.locale.as_ref());
-    let mut res = vec![];
+    let mut non_empty = vec![];
     let mut all_hidden = true;
     for item in req.items {
         if item.hidden {
@@ -526,7 +704,7 @@ pub fn standalone_citation<T: EntryLike>(
             all_hidden = false;
         }
 
-        res.push(if let Some(CitePurpose::Year) = item.purpose {
+        let rendered = if let Some(CitePurpose::Year) = item.purpose {
             date_replacement(
                 &style,
                 item.entry,
@@ -543,10 +721,22 @@ pub fn standalone_citation<T: EntryLike>(
                 item.purpose,
                 None,
             )
-        });
-    }
+        };
+
+        if !rendered.has_content() {
+            continue;
+        }
 
-    let non_empty: Vec<_> = res.into_iter().filter(|c| c.has_content()).collect();
+        let mut affixed = Vec::new();
+        push_affixed_item(
+            &style,
+            &mut affixed,
+            Elem { children: rendered, display: None, meta: None },
+            item.prefix.as_ref(),
+            item.suffix.as_ref(),
+        );
+        non_empty.push((ElemChildren(affixed), item.suffix.is_some()));
+    }
 
     let formatting =
         Formatting::default().apply(style.csl.citation.layout.to_formatting());
@@ -558,26 +748,26 @@ pub fn standalone_citation<T: EntryLike>(
             ElemChildren::new()
         };
 
-        for (i, elem_children) in non_empty.into_iter().enumerate() {
+        let mut after_suffix = false;
+        for (i, (elem_children, has_suffix)) in non_empty.into_iter().enumerate() {
             let first = i == 0;
             if !first {
-                res.0.push(
-                    Formatted {
-                        text: style
-                            .csl
-                            .citation
-                            .layout
-                            .delimiter
-                            .as_deref()
-                            .unwrap_or(Citation::DEFAULT_CITE_GROUP_DELIMITER)
-                            .to_string(),
-                        formatting,
-                    }
-                    .into(),
+                push_delimiter(
+                    &mut res.0,
+                    style
+                        .csl
+                        .citation
+                        .layout
+                        .delimiter
+                        .as_deref()
+                        .unwrap_or(Citation::DEFAULT_CITE_GROUP_DELIMITER),
+                    formatting,
+                    after_suffix,
                 );
             }
 
-            res.0.extend(elem_children.0)
+            res.0.extend(elem_children.0);
+            after_suffix = has_suffix;
         }
 
         if let Some(suffix) = style.csl.citation.layout.suffix.as_ref() {
@@ -593,6 +783,98 @@ pub fn standalone_citation<T: EntryLike>(
     }
 }
 
+/// Add a delimiter between two cites. If the preceding cite had a suffix,
+/// punctuation that the suffix already ends with is not repeated.
+fn push_delimiter(
+    children: &mut Vec<ElemChild>,
+    delim: &str,
+    formatting: Formatting,
+    after_suffix: bool,
+) {
+    let trimmed = delim.trim();
+    let text = match last_text_child(children) {
+        Some(last)
+            if after_suffix
+                && !trimmed.is_empty()
+                && last.text.trim_end().ends_with(trimmed) =>
+        {
+            let start = delim.find(trimmed).unwrap_or_default();
+            &delim[start + trimmed.len()..]
+        }
+        _ => delim,
+    };
+
+    if !text.is_empty() {
+        children.push(Formatted { text: text.to_string(), formatting }.into());
+    }
+}
+
+/// Add a rendered citation item surrounded by its prefix and suffix.
+///
+/// A space is inserted between the affixes and the item unless the affixes
+/// already provide separation. Punctuation at the start of the suffix is
+/// deduplicated and pulled into a preceding closing quote if the locale
+/// demands it.
+fn push_affixed_item(
+    ctx: &StyleContext<'_>,
+    children: &mut Vec<ElemChild>,
+    item: Elem,
+    prefix: Option<&ElemChildren>,
+    suffix: Option<&ElemChildren>,
+) {
+    if let Some(prefix) = prefix {
+        let mut prefix = prefix.clone();
+        let needs_space = match last_text_child(&prefix.0) {
+            Some(last) => matches!(
+                last.text.chars().next_back(),
+                Some(c) if !c.is_whitespace()
+                    && !matches!(c, '(' | '[' | '{' | '/' | '“' | '‘')
+            ),
+            None => prefix.has_content(),
+        };
+
+        if needs_space {
+            prefix.0.push(Formatting::default().add_text(" ".into()).into());
+        }
+
+        children.extend(prefix.0);
+    }
+
+    children.push(item.into());
+
+    let Some(suffix) = suffix else { return };
+    let mut suffix = suffix.clone();
+    let mut needs_space = false;
+    if let Some(ElemChild::Text(first)) = suffix.0.first_mut() {
+        match first.text.chars().next() {
+            Some(c @ ('.' | ',' | ';' | ':' | '!' | '?')) => {
+                if let Some(last) = last_text_mut_child(children) {
+                    if last.text.ends_with(c) {
+                        first.text.remove(0);
+                    } else if ctx.punctuation_in_quotes() {
+                        for quote in ctx.close_quotes().into_iter().flatten() {
+                            if let Some(head) = last.text.strip_suffix(quote) {
+                                last.text = format!("{head}{c}{quote}");
+                                first.text.remove(0);
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            Some(c) if c.is_alphanumeric() => needs_space = true,
+            _ => {}
+        }
+    }
+
+    if needs_space {
+        children.push(Formatting::default().add_text(" ".into()).into());
+    }
+
+    children.extend(suffix.0);
+}
+
+/// Render the regular form of an item during disambiguation.
 fn do_rerender<T: EntryLike>(
     ctx: &StyleContext<'_>,
     item: &SpeculativeItemRender<T>,
@@ -603,7 +885,7 @@ fn do_rerender<T: EntryLike>(
         item.cite_props.clone(),
         item.locale.as_ref(),
         request.locale.as_ref(),
-        item.purpose,
+        None,
         item.collapse_verdict,
     )
 }
@@ -621,29 +903,35 @@ fn date_replacement<T: EntryLike>(
         .or_else(|| entry.resolve_date_variable(DateVariable::Submitted))
         .or_else(|| entry.resolve_date_variable(DateVariable::OriginalDate));
 
-    ElemChildren(vec![ElemChild::Text(Formatted {
-        text: if let Some(date) = date {
-            format!(
-                "{}{}",
-                if date.year > 0 { date.year } else { date.year.abs() + 1 },
-                if date.year < 1000 {
-                    if date.year < 0 {
-                        "BC"
-                    } else {
-                        "AD"
-                    }
+    let mut text = if let Some(date) = date {
+        format!(
+            "{}{}",
+            if date.year > 0 { date.year } else { date.year.abs() + 1 },
+            if date.year < 1000 {
+                if date.year < 0 {
+                    "BC"
                 } else {
-                    ""
+                    "AD"
                 }
-            )
-        } else if let Some(no_date) = ctx
-            .ctx(entry, cite_props.clone(), locale, term_locale, false)
-            .term(Term::Other(OtherTerm::NoDate), TermForm::default(), false)
-        {
-            no_date.to_string()
-        } else {
-            "n.d.".to_string()
-        },
+            } else {
+                ""
+            }
+        )
+    } else if let Some(no_date) = ctx
+        .ctx(entry, cite_props.clone(), locale, term_locale, false)
+        .term(Term::Other(OtherTerm::NoDate), TermForm::default(), false)
+    {
+        no_date.to_string()
+    } else {
+        "n.d.".to_string()
+    };
+
+    if let DisambiguateState::YearSuffix(suffix) = cite_props.speculative.disambiguation {
+        text.push_str(&letter(suffix));
+    }
+
+    ElemChildren(vec![ElemChild::Text(Formatted {
+        text,
         formatting: Formatting::default(),
     })])
 }
@@ -652,17 +940,31 @@ fn last_purpose_render<T: EntryLike + Debug>(
     ctx: &StyleContext<'_>,
     item: &SpeculativeItemRender<T>,
     request: &CitationRequest<'_, T>,
-) -> ElemChildren {
+) -> (ElemChildren, Option<RenderTrace>) {
     if let Some(CitePurpose::Year) = item.purpose {
-        date_replacement(
+        // The year is not rendered with the style.
+        let trace = ctx.trace.then(|| RenderTrace {
+            key: item.entry.key().to_string(),
+            events: Vec::new(),
+        });
+        let rendered = date_replacement(
             ctx,
             item.entry,
             &item.cite_props,
             request.locale.as_ref(),
             item.locale.as_ref(),
-        )
+        );
+        (rendered, trace)
     } else {
-        do_rerender(ctx, item, request)
+        ctx.do_citation(
+            item.entry,
+            item.cite_props.clone(),
+            item.locale.as_ref(),
+            request.locale.as_ref(),
+            item.purpose,
+            item.collapse_verdict,
+        )
+        .flush_traced()
     }
 }
 
@@ -706,6 +1008,97 @@ fn disambiguate_names<F, T>(
     }
 }
 
+/// Expand names that are shared by different persons across all cites as
+/// required by the `givenname-disambiguation-rule`.
+fn disambiguate_given_names<T: EntryLike>(
+    renders: &mut [SpeculativeCiteRender<'_, '_, T>],
+) {
+    // Collect all names that are subject to global name disambiguation.
+    let mut names = Vec::new();
+    for (cite_idx, cite) in renders.iter().enumerate() {
+        let citation = &cite.request.style.citation;
+        let rule = citation.givenname_disambiguation_rule;
+        if !citation.disambiguate_add_givenname || rule == DisambiguationRule::ByCite {
+            continue;
+        }
+
+        for (item_idx, item) in cite.items.iter().enumerate() {
+            let Some(props) = &item.first_name else { continue };
+            for (variable, idx, form) in
+                props.rendered_forms(!rule.allows_multiple_names())
+            {
+                let Some(person) =
+                    item.entry.resolve_name_variable(variable).get(idx).cloned()
+                else {
+                    continue;
+                };
+
+                names.push((
+                    cite_idx,
+                    item_idx,
+                    variable,
+                    idx,
+                    form,
+                    person.into_owned(),
+                ));
+            }
+        }
+    }
+
+    let persons = || names.iter().map(|n| &n.5);
+    let mut targets: HashMap<(usize, usize), Vec<_>> = HashMap::new();
+    for (cite_idx, item_idx, variable, idx, form, person) in names.iter() {
+        let rule = renders[*cite_idx]
+            .request
+            .style
+            .citation
+            .givenname_disambiguation_rule;
+        let target = form.distinguish(person, persons(), rule.allows_full_first_names());
+        if target != *form {
+            targets
+                .entry((*cite_idx, *item_idx))
+                .or_default()
+                .push((*variable, *idx, target));
+        }
+    }
+
+    for ((cite_idx, item_idx), targets) in targets {
+        let cite = &mut renders[cite_idx];
+        let allow_full_first_name = cite
+            .request
+            .style
+            .citation
+            .givenname_disambiguation_rule
+            .allows_full_first_names();
+        let item = &mut cite.items[item_idx];
+        let Some(mut props) = item.first_name.clone() else { continue };
+
+        // Step each name until it has reached its target form.
+        loop {
+            let mut change = false;
+            for &(variable, _, _) in targets.iter() {
+                let idxs: Vec<_> = targets
+                    .iter()
+                    .filter(|&&(v, idx, target)| {
+                        v == variable
+                            && props.form(v, idx).is_some_and(|form| form < target)
+                    })
+                    .map(|&(_, idx, _)| idx)
+                    .collect();
+                change |= props.disambiguate_list(variable, &idxs, allow_full_first_name);
+            }
+
+            if !change {
+                break;
+            }
+        }
+
+        item.cite_props.speculative.disambiguation =
+            DisambiguateState::NameDisambiguation(props);
+        item.rendered = do_rerender(&cite.request.style(), item, cite.request);
+    }
+}
+
 /// Mark qualifying entries for disambiguation with `cs:choose`.
 fn disambiguate_with_choose<F, T>(
     renders: &[SpeculativeCiteRender<'_, '_, T>],
@@ -879,8 +1272,11 @@ fn collapse_items<'a, T: EntryLike>(cite: &mut SpeculativeCiteRender<'a, '_, T>)
                     // cannot be mutably borrowed below otherwise.
                     let item = &cite.items[i];
                     if item.hidden
+                        || item.has_affixes()
                         || item.rendered.get_meta(ElemMeta::CitationNumber).is_none()
                     {
+                        // Items with affixes must stay visible and cannot be
+                        // part of a range.
                         end_range(&mut cite.items, &mut range_start);
                         continue;
                     }
@@ -912,20 +1308,41 @@ fn collapse_items<'a, T: EntryLike>(cite: &mut SpeculativeCiteRender<'a, '_, T>)
 
             end_range(&mut cite.items, &mut range_start);
         }
-        Some(Collapse::Year | Collapse::YearSuffix | Collapse::YearSuffixRanged) => {
+        Some(
+            collapse @ (Collapse::Year
+            | Collapse::YearSuffix
+            | Collapse::YearSuffixRanged),
+        ) => {
             let after_collapse_delim =
                 after_collapse_delim.or(style.citation.layout.delimiter.as_deref());
+            let year_suffix_delim = style.citation.get_year_suffix_delimiter();
 
             // Index of where the current group started and the group we are
             // currently in.
             let mut group_idx: Option<(usize, usize)> = None;
             for i in 0..cite.items.len() {
+                // A prefix on this item or a suffix on the previous one
+                // separates the item from its group.
+                let affix_break = cite.items[i].prefix.is_some()
+                    || (i > 0 && cite.items[i - 1].suffix.is_some());
+
                 match group_idx {
                     // This is our group.
-                    Some((_, idx)) if Some(idx) == cite.items[i].group_idx => {
+                    Some((_, idx))
+                        if Some(idx) == cite.items[i].group_idx && !affix_break =>
+                    {
                         // FIXME: Retains delimiter in names.
                         cite.items[i].delim_override = group_delimiter;
                         cite.items[i].collapse_verdict = Some(CollapseVerdict::First);
+
+                        // Items with the same year only print their suffix.
+                        if collapse != Collapse::Year
+                            && same_year_with_suffix(&cite.items[i - 1], &cite.items[i])
+                        {
+                            cite.items[i].delim_override = Some(year_suffix_delim);
+                            cite.items[i].collapse_verdict =
+                                Some(CollapseVerdict::YearSuffix);
+                        }
                     }
                     // This is a different group.
                     Some((start, _)) if start + 1 < i => {
@@ -939,19 +1356,77 @@ fn collapse_items<'a, T: EntryLike>(cite: &mut SpeculativeCiteRender<'a, '_, T>)
                 }
             }
 
-            // TODO: Year Suffix and Year Suffix ranged.
+            if collapse == Collapse::YearSuffixRanged {
+                collapse_year_suffix_ranges(&mut cite.items);
+            }
         }
         None => {}
     }
 }
 
+/// The year suffix that was assigned to an item during disambiguation.
+fn year_suffix<T: EntryLike>(item: &SpeculativeItemRender<'_, T>) -> Option<u8> {
+    match item.cite_props.speculative.disambiguation {
+        DisambiguateState::YearSuffix(suffix) => Some(suffix),
+        _ => None,
+    }
+}
+
+/// Whether both items have a year suffix and render the same date otherwise.
+fn same_year_with_suffix<T: EntryLike>(
+    prev: &SpeculativeItemRender<'_, T>,
+    item: &SpeculativeItemRender<'_, T>,
+) -> bool {
+    let date_without_suffix = |item: &SpeculativeItemRender<'_, T>| {
+        let suffix = letter(year_suffix(item)?);
+        let date = format!("{:#}", item.rendered.get_meta(ElemMeta::Date)?);
+        Some(
+            date.strip_suffix(suffix.as_str())
+                .map(ToString::to_string)
+                .unwrap_or(date),
+        )
+    };
+
+    match (date_without_suffix(prev), date_without_suffix(item)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Collapse runs of at least three consecutive year suffixes into a range.
+fn collapse_year_suffix_ranges<T: EntryLike>(items: &mut [SpeculativeItemRender<'_, T>]) {
+    let mut start = 0;
+    while start < items.len() {
+        let mut end = start;
+        while end + 1 < items.len()
+            && items[end + 1].collapse_verdict == Some(CollapseVerdict::YearSuffix)
+            && year_suffix(&items[end + 1]).is_some()
+            && year_suffix(&items[end + 1]) == year_suffix(&items[end]).map(|s| s + 1)
+            && items[end].locator.is_none()
+            && items[end + 1].locator.is_none()
+            && !items[end].hidden
+            && !items[end + 1].hidden
+        {
+            end += 1;
+        }
+
+        if start + 1 < end {
+            items[end].delim_override = Some("–");
+            for item in &mut items[start + 1..end] {
+                item.hidden = true;
+            }
+        }
+
+        start = end + 1;
+    }
+}
+
 /// What we have decided for rerendering this item.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum CollapseVerdict {
     /// Only the first date should be printed.
     First,
     /// Only the year suffix should be printed.
-    #[allow(dead_code)]
     YearSuffix,
 }
 
@@ -987,11 +1462,23 @@ pub struct BibliographyItem {
     pub key: String,
     pub first_field: Option<ElemChild>,
     pub content: ElemChildren,
+    /// The trace of the render if [`BibliographyRequest::trace`] was set.
+    pub(crate) trace: Option<RenderTrace>,
 }
 
 impl BibliographyItem {
-    fn new(key: String, first_field: Option<ElemChild>, content: ElemChildren) -> Self {
-        Self { key, first_field, content }
+    fn new(
+        key: String,
+        first_field: Option<ElemChild>,
+        content: ElemChildren,
+        trace: Option<RenderTrace>,
+    ) -> Self {
+        Self { key, first_field, content, trace }
+    }
+
+    /// The trace of the render if [`BibliographyRequest::trace`] was set.
+    pub fn trace(&self) -> Option<&RenderTrace> {
+        self.trace.as_ref()
     }
 }
 
@@ -1002,6 +1489,17 @@ pub struct RenderedCitation {
     pub note_number: Option<usize>,
     /// The citation.
     pub citation: ElemChildren,
+    /// The traces of the rendered items if [`BibliographyRequest::trace`] was
+    /// set.
+    pub(crate) traces: Vec<RenderTrace>,
+}
+
+impl RenderedCitation {
+    /// The traces of the rendered items if [`BibliographyRequest::trace`] was
+    /// set.
+    pub fn traces(&self) -> &[RenderTrace] {
+        &self.traces
+    }
 }
 
 /// A context that contains all information related to rendering a single entry.
@@ -1057,15 +1555,27 @@ impl<'a, T: EntryLike> InstanceContext<'a, T> {
 }
 
 /// A context that contains information about the style we are using to render.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub(crate) struct StyleContext<'a> {
     // Settings from the style.
     /// The settings of the style.
     pub csl: &'a IndependentStyle,
-    /// A list of locales defined in their respective locale file.
-    locale_files: &'a [Locale],
+    /// The locales to consult for terms and options, most specific first.
+    locales: LocaleChain<'a>,
     /// Which locale we're using.
     locale_override: Option<LocaleCode>,
+    /// Which language variants of multilingual fields to render.
+    language_variants: LanguageVariants,
+    /// The layout for narrative citations.
+    intext: Option<&'a InText>,
+    /// Abbreviations for short forms that the entries do not define.
+    abbreviations: Option<&'a Abbreviations>,
+    /// The compiled style with cached macros, if any.
+    compiled: Option<&'a CompiledStyle<'a>>,
+    /// The collators for sorting, if they are cached.
+    collators: Option<&'a Collators>,
+    /// Whether to record traces of renders.
+    trace: bool,
 }
 
 impl<'a> StyleContext<'a> {
@@ -1074,7 +1584,69 @@ impl<'a> StyleContext<'a> {
         locale: Option<LocaleCode>,
         locale_files: &'a [Locale],
     ) -> Self {
-        Self { csl: style, locale_files, locale_override: locale }
+        let locales = LocaleResolver::new(locale_files).resolve(style, locale.as_ref());
+        Self::with_locales(style, locale, locales)
+    }
+
+    /// Create a context with an already resolved locale chain.
+    fn with_locales(
+        style: &'a IndependentStyle,
+        locale: Option<LocaleCode>,
+        locales: LocaleChain<'a>,
+    ) -> Self {
+        Self {
+            csl: style,
+            locales,
+            locale_override: locale,
+            language_variants: LanguageVariants::default(),
+            intext: None,
+            abbreviations: None,
+            compiled: None,
+            collators: None,
+            trace: false,
+        }
+    }
+
+    /// Create a context for a compiled style.
+    fn from_compiled(
+        compiled: &'a CompiledStyle<'a>,
+        locale: Option<LocaleCode>,
+    ) -> Self {
+        Self {
+            csl: compiled.style(),
+            locales: compiled.chain(locale.as_ref()),
+            locale_override: locale,
+            language_variants: LanguageVariants::default(),
+            intext: compiled.intext(),
+            abbreviations: None,
+            compiled: Some(compiled),
+            collators: Some(compiled.collators()),
+            trace: false,
+        }
+    }
+
+    /// Render the given language variants of multilingual fields.
+    fn with_language_variants(mut self, variants: &LanguageVariants) -> Self {
+        self.language_variants = variants.clone();
+        self
+    }
+
+    /// Use abbreviations for short forms.
+    fn with_abbreviations(mut self, abbreviations: Option<&'a Abbreviations>) -> Self {
+        self.abbreviations = abbreviations;
+        self
+    }
+
+    /// Sort with cached collators unless the compiled style provides them.
+    fn with_collators(mut self, collators: &'a Collators) -> Self {
+        self.collators.get_or_insert(collators);
+        self
+    }
+
+    /// Record traces of the renders.
+    fn with_trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
     }
 
     fn ctx<'b, T: EntryLike>(
@@ -1095,7 +1667,10 @@ impl<'a> StyleContext<'a> {
                 None,
             ),
             style: self,
-            writing: WritingContext::new(self.csl.settings.options.clone()),
+            writing: WritingContext {
+                trace: self.trace.then(RefCell::default),
+                ..WritingContext::new(self.csl.settings.options.clone())
+            },
             bibliography,
         }
     }
@@ -1138,18 +1713,6 @@ impl<'a> StyleContext<'a> {
         ctx.flush()
     }
 
-    /// Render the given item within a bibliography.
-    fn bibliography<T: EntryLike>(
-        &self,
-        entry: &T,
-        props: CiteProperties<'a>,
-        locale: Option<&LocaleCode>,
-        term_locale: Option<&LocaleCode>,
-    ) -> Option<ElemChildren> {
-        self.do_bibliography(entry, props, locale, term_locale)
-            .map(|ctx| ctx.flush())
-    }
-
     /// Render the given item within a citation.
     fn do_citation<'b, T: EntryLike>(
         &'b self,
@@ -1273,6 +1836,18 @@ impl<'a> StyleContext<'a> {
                     }
                 }
             }
+            Some(CitePurpose::Prose) if self.intext.is_some() => {
+                let layout = &self.intext.unwrap().layout;
+                if let Some(prefix) = layout.prefix.as_ref() {
+                    ctx.push_str(prefix);
+                }
+                ctx.writing.push_name_options(&self.csl.citation.name_options);
+                layout.render(&mut ctx);
+                ctx.writing.pop_name_options();
+                if let Some(suffix) = layout.suffix.as_ref() {
+                    ctx.push_str(suffix);
+                }
+            }
             Some(CitePurpose::Prose) => {
                 do_author(&mut ctx);
                 if !self.csl.citation.layout.prefix.as_ref().map_or(false, |f| {
@@ -1362,6 +1937,15 @@ pub struct CitationRequest<'a, T: EntryLike> {
     /// `near-note` will always test false if this is none for the referenced
     /// note.
     note_number: Option<usize>,
+    /// Which language variants of multilingual fields to render.
+    language_variants: LanguageVariants,
+    /// Abbreviations for the short forms of variables.
+    abbreviations: Option<&'a Abbreviations>,
+    /// The compiled style, if the request was created for one.
+    compiled: Option<&'a CompiledStyle<'a>>,
+    /// The locale chain, resolved by the driver if the request was not
+    /// created for a compiled style.
+    chain: Option<LocaleChain<'a>>,
 }
 
 impl<'a, T: EntryLike> CitationRequest<'a, T> {
@@ -1379,9 +1963,38 @@ impl<'a, T: EntryLike> CitationRequest<'a, T> {
             locale,
             locale_files,
             note_number: note_number.filter(|_| style.settings.class == StyleClass::Note),
+            language_variants: LanguageVariants::default(),
+            abbreviations: None,
+            compiled: None,
+            chain: None,
         }
     }
 
+    /// Create a new citation request for a compiled style.
+    pub fn with_compiled(
+        items: Vec<CitationItem<'a, T>>,
+        style: &'a CompiledStyle<'a>,
+        locale: Option<LocaleCode>,
+        note_number: Option<usize>,
+    ) -> Self {
+        let mut req =
+            Self::new(items, style.style(), locale, style.locale_files(), note_number);
+        req.compiled = Some(style);
+        req
+    }
+
+    /// Set which language variants of multilingual fields to render.
+    pub fn with_language_variants(mut self, variants: LanguageVariants) -> Self {
+        self.language_variants = variants;
+        self
+    }
+
+    /// Set the abbreviations for the short forms of variables.
+    pub fn with_abbreviations(mut self, abbreviations: &'a Abbreviations) -> Self {
+        self.abbreviations = Some(abbreviations);
+        self
+    }
+
     /// Create a new citation request without a note number.
     pub fn from_items(
         items: Vec<CitationItem<'a, T>>,
@@ -1392,7 +2005,21 @@ impl<'a, T: EntryLike> CitationRequest<'a, T> {
     }
 
     fn style(&self) -> StyleContext<'a> {
-        StyleContext::new(self.style, self.locale.clone(), self.locale_files)
+        match self.compiled {
+            Some(compiled) => StyleContext::from_compiled(compiled, self.locale.clone()),
+            None => match &self.chain {
+                Some(chain) => StyleContext::with_locales(
+                    self.style,
+                    self.locale.clone(),
+                    chain.clone(),
+                ),
+                None => {
+                    StyleContext::new(self.style, self.locale.clone(), self.locale_files)
+                }
+            },
+        }
+        .with_language_variants(&self.language_variants)
+        .with_abbreviations(self.abbreviations)
     }
 
     fn shall_affix(&self) -> bool {
@@ -1433,6 +2060,14 @@ pub struct BibliographyRequest<'a> {
     /// The files used to retrieve locale settings and terms if the style does
     /// not define all neccessary items.
     pub locale_files: &'a [Locale],
+    /// Which language variants of multilingual fields to render.
+    language_variants: LanguageVariants,
+    /// Abbreviations for the short forms of variables.
+    abbreviations: Option<&'a Abbreviations>,
+    /// The compiled style, if the request was created for one.
+    compiled: Option<&'a CompiledStyle<'a>>,
+    /// Whether to record a [`RenderTrace`] for each rendered item.
+    trace: bool,
 }
 
 impl<'a> BibliographyRequest<'a> {
@@ -1442,11 +2077,99 @@ impl<'a> BibliographyRequest<'a> {
         locale: Option<LocaleCode>,
         locale_files: &'a [Locale],
     ) -> Self {
-        Self { style, locale, locale_files }
+        Self {
+            style,
+            locale,
+            locale_files,
+            language_variants: LanguageVariants::default(),
+            abbreviations: None,
+            compiled: None,
+            trace: false,
+        }
     }
 
-    fn style(&self) -> StyleContext<'a> {
-        StyleContext::new(self.style, self.locale.clone(), self.locale_files)
+    /// Create a new bibliography request for a compiled style.
+    pub fn with_compiled(
+        style: &'a CompiledStyle<'a>,
+        locale: Option<LocaleCode>,
+    ) -> Self {
+        let mut req = Self::new(style.style(), locale, style.locale_files());
+        req.compiled = Some(style);
+        req
+    }
+
+    /// Set which language variants of multilingual fields to render.
+    pub fn with_language_variants(mut self, variants: LanguageVariants) -> Self {
+        self.language_variants = variants;
+        self
+    }
+
+    /// Set the abbreviations for the short forms of variables.
+    pub fn with_abbreviations(mut self, abbreviations: &'a Abbreviations) -> Self {
+        self.abbreviations = Some(abbreviations);
+        self
+    }
+
+    /// Set whether to record a trace of the decisions made while rendering
+    /// each citation item and bibliography entry. Traces explain which
+    /// branches of `cs:choose` were taken, which variables were consulted,
+    /// and why output was suppressed.
+    pub fn trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    fn style<'s>(&self, resolver: &LocaleResolver<'s>) -> StyleContext<'s>
+    where
+        'a: 's,
+    {
+        match self.compiled {
+            Some(compiled) => StyleContext::from_compiled(compiled, self.locale.clone()),
+            None => StyleContext::with_locales(
+                self.style,
+                self.locale.clone(),
+                resolver.resolve(self.style, self.locale.as_ref()),
+            ),
+        }
+        .with_language_variants(&self.language_variants)
+        .with_abbreviations(self.abbreviations)
+    }
+}
+
+/// Selects which language variants of multilingual fields are rendered,
+/// following the conventions of CSL-M.
+///
+/// By default, only the original values are rendered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LanguageVariants {
+    /// The variants to render for titles and other text fields. The first
+    /// available variant is printed and the others follow, e.g.
+    /// "Voina i mir [War and Peace]". If none is available, the original is
+    /// printed.
+    pub fields: Vec<LanguageVariant>,
+    /// The variants to render for names. The first available variant is
+    /// printed. If none is available, the original is printed.
+    pub names: Vec<LanguageVariant>,
+    /// The text before the variants that follow the first one of a field.
+    /// Defaults to `" ["`.
+    pub prefix: String,
+    /// The text between the variants that follow the first one of a field.
+    /// Defaults to `", "`.
+    pub delimiter: String,
+    /// The text after the variants that follow the first one of a field.
+    /// Defaults to `"]"`.
+    pub suffix: String,
+}
+
+impl Default for LanguageVariants {
+    fn default() -> Self {
+        Self {
+            fields: Vec::new(),
+            names: Vec::new(),
+            prefix: " [".into(),
+            delimiter: ", ".into(),
+            suffix: "]".into(),
+        }
     }
 }
 
@@ -1465,6 +2188,10 @@ pub struct CitationItem<'a, T: EntryLike> {
     pub hidden: bool,
     /// Format the item in a special way.
     pub purpose: Option<CitePurpose>,
+    /// Text to print before the item, e.g. "see".
+    pub prefix: Option<ElemChildren>,
+    /// Text to print after the item, e.g. ", for a critique".
+    pub suffix: Option<ElemChildren>,
     /// The initial index of this item in the list of items.
     initial_idx: usize,
 }
@@ -1478,6 +2205,8 @@ impl<'a, T: EntryLike> CitationItem<'a, T> {
             locale: None,
             hidden: false,
             purpose: None,
+            prefix: None,
+            suffix: None,
             initial_idx: 0,
         }
     }
@@ -1490,6 +2219,8 @@ impl<'a, T: EntryLike> CitationItem<'a, T> {
             locale: None,
             hidden: false,
             purpose: None,
+            prefix: None,
+            suffix: None,
             initial_idx: 0,
         }
     }
@@ -1508,6 +2239,8 @@ impl<'a, T: EntryLike> CitationItem<'a, T> {
             locale,
             hidden,
             purpose,
+            prefix: None,
+            suffix: None,
             initial_idx: 0,
         }
     }
@@ -1517,63 +2250,49 @@ impl<'a, T: EntryLike> CitationItem<'a, T> {
         self.purpose = Some(purpose);
         self
     }
+
+    /// Set the text that is printed before this item. Strings are
+    /// used verbatim; pass a [`ChunkedString`](crate::types::ChunkedString)
+    /// to use rich-text markup like `<i>` or `<b>`.
+    pub fn prefix(mut self, prefix: impl Into<ElemChildren>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Set the text that is printed after this item. Strings are
+    /// used verbatim; pass a [`ChunkedString`](crate::types::ChunkedString)
+    /// to use rich-text markup like `<i>` or `<b>`.
+    pub fn suffix(mut self, suffix: impl Into<ElemChildren>) -> Self {
+        self.suffix = Some(suffix.into());
+        self
+    }
 }
 
 impl<'a> StyleContext<'a> {
     /// Retrieve a macro.
     fn get_macro(&self, name: &str) -> Option<&'a CslMacro> {
-        self.csl.macros.iter().find(|m| m.name == name)
+        match self.compiled {
+            Some(compiled) => compiled.get_macro(name),
+            None => self.csl.macros.iter().find(|m| m.name == name),
+        }
+    }
+
+    /// Retrieve the collator for sorting in the given locale or the locale of
+    /// the style.
+    fn collator(&self, locale: Option<&LocaleCode>) -> Arc<Collator> {
+        let locale = locale.cloned().unwrap_or_else(|| self.locale());
+        match self.collators {
+            Some(collators) => collators.get(&locale),
+            None => Arc::new(Collator::new(&locale)),
+        }
     }
 
-    /// Get the locale for the given language in the style.
-    fn lookup_locale<F, R>(&self, mut f: F) -> Option<R>
+    /// Find the first value provided by the locale chain of the style.
+    fn lookup_locale<F, R>(&self, f: F) -> Option<R>
     where
         F: FnMut(&'a Locale) -> Option<R>,
     {
-        let mut lookup = |file: &'a [Locale], lang: Option<&LocaleCode>| {
-            #[allow(clippy::redundant_closure)]
-            file.iter().find(|l| l.lang.as_ref() == lang).and_then(|l| f(l))
-        };
-
-        let locale = self.locale();
-        let en_us = LocaleCode::en_us();
-
-        for (i, resource) in [self.csl.locale.as_slice(), self.locale_files]
-            .into_iter()
-            .enumerate()
-        {
-            let fallback = if i == 0 {
-                locale.parse_base().and_then(|base| match base {
-                    BaseLanguage::Iso639_1(lang) => {
-                        Some(LocaleCode(String::from_utf8(lang.to_vec()).unwrap()))
-                    }
-                    BaseLanguage::Iana(lang) => Some(LocaleCode(lang)),
-                    _ => None,
-                })
-            } else {
-                locale.fallback()
-            };
-
-            if let Some(output) = lookup(resource, Some(&locale)) {
-                return Some(output);
-            }
-
-            if fallback.is_some() {
-                if let Some(output) = lookup(resource, fallback.as_ref()) {
-                    return Some(output);
-                }
-            }
-
-            if i == 0 {
-                if let Some(output) = lookup(resource, None) {
-                    return Some(output);
-                }
-            } else if let Some(output) = lookup(resource, Some(&en_us)) {
-                return Some(output);
-            }
-        }
-
-        None
+        self.locales.iter().find_map(f)
     }
 
     /// Check whether to do punctuation in quotes.
@@ -1581,6 +2300,16 @@ impl<'a> StyleContext<'a> {
         self.lookup_locale(|f| f.style_options?.punctuation_in_quote)
             .unwrap_or_default()
     }
+
+    /// Retrieve the closing outer and inner quotation marks.
+    fn close_quotes(&self) -> [Option<&'a str>; 2] {
+        [OtherTerm::CloseQuote, OtherTerm::CloseInnerQuote].map(|term| {
+            self.lookup_locale(|l| {
+                l.term(term.into(), TermForm::default()).map(|t| t.single())
+            })
+            .flatten()
+        })
+    }
 }
 
 /// This struct contains all information needed to render a single entry. It
@@ -1606,6 +2335,8 @@ pub(crate) struct WritingContext {
     /// The disambiguation-relevant properties of the first `cs:name` element.
     /// This is `None` if no `cs:name` elements were rendered.
     first_name: Option<NameDisambiguationProperties>,
+    /// The trace of this render, if tracing is enabled.
+    trace: Option<RefCell<Tracer>>,
 
     // Inheritable settings.
     /// A stack of formatting. Always contains the format of the root layout
@@ -1638,6 +2369,7 @@ impl Default for WritingContext {
             checked_disambiguate: false,
             first_date: true,
             first_name: None,
+            trace: None,
             format_stack: NonEmptyStack::default(),
             cases: NonEmptyStack::default(),
             name_options: NonEmptyStack::default(),
@@ -1690,6 +2422,24 @@ impl WritingContext {
             return;
         }
 
+        let text = mem::take(&mut self.buf).finish();
+        self.save_text_to_block(text);
+    }
+
+    /// Saves the current buffer to the last element like
+    /// [`Self::save_to_block`], but lets case folding continue with the next
+    /// text. This is used when the formatting changes within a string.
+    fn save_to_block_continued(&mut self) {
+        if self.buf.is_empty() {
+            return;
+        }
+
+        let text = self.buf.take_continued();
+        self.save_text_to_block(text);
+    }
+
+    /// Appends text with the current formatting to the last element.
+    fn save_text_to_block(&mut self, text: String) {
         let format = *self.formatting();
 
         // Append to last child if formats match.
@@ -1701,12 +2451,12 @@ impl WritingContext {
             }
         }) {
             if format == child.formatting {
-                child.text.push_str(&mem::take(&mut self.buf).finish());
+                child.text.push_str(&text);
                 return;
             }
         }
 
-        let formatted = format.add_text(mem::take(&mut self.buf).finish());
+        let formatted = format.add_text(text);
         self.elem_stack.last_mut().0.push(ElemChild::Text(formatted))
     }
 
@@ -1888,6 +2638,11 @@ impl WritingContext {
     {
         let general: Variable = variable.into();
         if self.suppressed_variables.borrow().contains(&general) {
+            if let Some(tracer) = &self.trace {
+                tracer.borrow_mut().record(TraceKind::Suppressed(
+                    SuppressionReason::Substituted(general.to_string()),
+                ));
+            }
             return None;
         }
 
@@ -2366,6 +3121,12 @@ impl<'a, T: EntryLike> Context<'a, T> {
     /// Push a chunked string to the buffer.
     pub fn push_chunked(&mut self, chunked: &ChunkedString) {
         for chunk in &chunked.0 {
+            let format = self.writing.formatting().chunk_format(chunk.format);
+            let idx = (!format.is_empty()).then(|| {
+                self.writing.save_to_block_continued();
+                self.writing.push_format(format)
+            });
+
             match chunk.kind {
                 ChunkKind::Normal => self.push_str(&chunk.value),
                 ChunkKind::Verbatim => {
@@ -2384,6 +3145,11 @@ impl<'a, T: EntryLike> Context<'a, T> {
                     self.writing.buf.prevent_trimming();
                 }
             }
+
+            if let Some(idx) = idx {
+                self.writing.save_to_block_continued();
+                self.writing.pop_format(idx);
+            }
         }
     }
 
@@ -2414,6 +3180,15 @@ impl<'a, T: EntryLike> Context<'a, T> {
         self.writing.flush()
     }
 
+    /// Like [`Context::flush`], but also returns the trace of the render if
+    /// tracing is enabled.
+    fn flush_traced(mut self) -> (ElemChildren, Option<RenderTrace>) {
+        let trace = self.writing.trace.take().map(|tracer| {
+            tracer.into_inner().finish(self.instance.entry.key().to_string())
+        });
+        (self.flush(), trace)
+    }
+
     /// Get a term from the style.
     fn term(&self, mut term: Term, form: TermForm, plural: bool) -> Option<&'a str> {
         if term == Term::NumberVariable(csl_taxonomy::NumberVariable::Locator) {
@@ -2536,6 +3311,7 @@ impl<'a, T: EntryLike> Context<'a, T> {
 
         self.writing.prepare_variable_query(variable)?;
         let res = self.instance.resolve_number_variable(variable);
+        self.trace_variable(variable, res.is_some());
         res
     }
 
@@ -2573,8 +3349,13 @@ impl<'a, T: EntryLike> Context<'a, T> {
         }
 
         self.writing.prepare_variable_query(variable)?;
-        let res = self.instance.resolve_standard_variable(form, variable);
-
+        let res = self.instance.resolve_standard_variable_abbreviated(
+            form,
+            variable,
+            &self.style.language_variants,
+            self.style.abbreviations,
+        );
+        self.trace_variable(variable, res.is_some());
         res
     }
 
@@ -2587,7 +3368,7 @@ impl<'a, T: EntryLike> Context<'a, T> {
     ) -> Option<Cow<'a, Date>> {
         self.writing.prepare_variable_query(variable)?;
         let res = self.instance.entry.resolve_date_variable(variable);
-
+        self.trace_variable(variable, res.is_some());
         res
     }
 
@@ -2602,7 +3383,28 @@ impl<'a, T: EntryLike> Context<'a, T> {
             return Vec::new();
         }
 
-        let res = self.instance.entry.resolve_name_variable(variable);
+        let mut res = self.instance.entry.resolve_name_variable(variable);
+        let variants = &self.style.language_variants.names;
+        if !variants.is_empty() {
+            for person in res.iter_mut() {
+                let variant = |p: &'a Person| {
+                    variants.iter().find_map(|v| p.select_variant(v)).unwrap_or(p)
+                };
+
+                *person = match person {
+                    Cow::Borrowed(p) => Cow::Borrowed(variant(p)),
+                    Cow::Owned(p) => Cow::Owned(
+                        variants
+                            .iter()
+                            .find_map(|v| p.select_variant(v))
+                            .unwrap_or(p)
+                            .clone(),
+                    ),
+                };
+            }
+        }
+
+        self.trace_variable(variable, !res.is_empty());
         res
     }
 
@@ -2764,7 +3566,9 @@ pub enum CitePurpose {
     Year,
     /// The citation will equate to the bibliography entry of the item.
     Full,
-    /// The citation will be well-suited for inclusion in prose.
+    /// The citation will be well-suited for inclusion in prose. It uses the
+    /// `cs:intext` layout if the request's
+    /// [`CompiledStyle`] was created [from XML](CompiledStyle::from_xml).
     Prose,
 }
 
@@ -2832,11 +3636,7 @@ mod tests {
                 ));
             }
 
-            driver.finish(BibliographyRequest {
-                style: &style,
-                locale: None,
-                locale_files: &en_locale,
-            });
+            driver.finish(BibliographyRequest::new(&style, None, &en_locale));
 
             // for cite in finished.citations {
             //     println!("{}", cite.citation.to_string(BufWriteFormat::Plain))
@@ -0,0 +1,516 @@
+//! A registry of the styles and locales available to a program.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use citationberg::{IndependentStyle, Locale, LocaleFile, Style, StyleInfo, XmlError};
+use thiserror::Error;
+
+#[cfg(feature = "archive")]
+use super::archive::ArchivedStyle;
+use super::dependent::apply_dependent;
+use crate::types::IdentifierKind;
+
+/// A registry of styles and locales from multiple sources.
+///
+/// Styles and locales are added in layers: the archive (if the `archive`
+/// feature is enabled), directories of `.csl` and `locales-*.xml` files, and
+/// styles and locales held in memory. Layers that were added later take
+/// precedence over earlier ones. A style with the same CSL ID as a style of an
+/// earlier layer overrides it, and lookups by name, title, or ISSN return the
+/// style of the latest layer that matches.
+#[derive(Debug, Clone)]
+pub struct StyleRegistry {
+    /// The registered styles, from the lowest to the highest precedence.
+    styles: Vec<StyleEntry>,
+    /// The registered locales, from the highest to the lowest precedence.
+    locales: Vec<Locale>,
+}
+
+/// A style in a [`StyleRegistry`].
+#[derive(Debug, Clone)]
+pub struct StyleEntry {
+    id: String,
+    title: String,
+    title_short: Option<String>,
+    names: Vec<String>,
+    issn: Vec<String>,
+    parent: Option<String>,
+    origin: StyleOrigin,
+    data: StyleData,
+}
+
+/// Where a style of a [`StyleRegistry`] comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StyleOrigin {
+    /// The style is archived in Hayagriva.
+    Archive,
+    /// The style was read from a file.
+    File(PathBuf),
+    /// The style was added from memory.
+    Memory,
+}
+
+/// The data of a registered style.
+#[derive(Debug, Clone)]
+enum StyleData {
+    #[cfg(feature = "archive")]
+    Archived(ArchivedStyle),
+    Parsed(Box<Style>),
+}
+
+/// An error while adding styles or locales to a [`StyleRegistry`].
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    /// A file or directory could not be read.
+    #[error("could not read {}: {err}", .path.display())]
+    Io {
+        /// The path that could not be read.
+        path: PathBuf,
+        /// The underlying error.
+        #[source]
+        err: io::Error,
+    },
+    /// A style or locale file is malformed.
+    #[error("malformed file {}: {err}", .path.display())]
+    Malformed {
+        /// The path of the malformed file.
+        path: PathBuf,
+        /// The underlying error.
+        #[source]
+        err: XmlError,
+    },
+}
+
+impl StyleRegistry {
+    /// Create a registry with the archived styles and locales, if the
+    /// `archive` feature is enabled.
+    pub fn new() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = Self::empty();
+
+        #[cfg(feature = "archive")]
+        {
+            registry.styles.extend(ArchivedStyle::all().iter().map(|&style| {
+                StyleEntry {
+                    id: style.csl_id().into(),
+                    title: style.display_name().into(),
+                    title_short: None,
+                    names: style.names().iter().map(ToString::to_string).collect(),
+                    issn: vec![],
+                    parent: None,
+                    origin: StyleOrigin::Archive,
+                    data: StyleData::Archived(style),
+                }
+            }));
+            registry.locales.extend(crate::archive::locales());
+        }
+
+        registry
+    }
+
+    /// Create a registry without any styles or locales.
+    pub fn empty() -> Self {
+        Self { styles: vec![], locales: vec![] }
+    }
+
+    /// Add the styles and locales of a directory.
+    ///
+    /// Files with the extension `.csl` are read as styles and files named
+    /// `locales-*.xml` as locale files. Other files and subdirectories are
+    /// ignored. Returns an error if a file cannot be read or parsed, in which
+    /// case the registry is left unchanged.
+    pub fn add_dir(&mut self, dir: impl AsRef<Path>) -> Result<(), RegistryError> {
+        let dir = dir.as_ref();
+        let io_err = |path: &Path| {
+            let path = path.to_path_buf();
+            move |err| RegistryError::Io { path, err }
+        };
+
+        let mut paths = vec![];
+        for entry in fs::read_dir(dir).map_err(io_err(dir))? {
+            let path = entry.map_err(io_err(dir))?.path();
+            if path.is_file() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut styles = vec![];
+        let mut locales = vec![];
+        for path in paths {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            let is_style = path.extension().is_some_and(|ext| ext == "csl");
+            let is_locale = name.starts_with("locales-") && name.ends_with(".xml");
+            if !is_style && !is_locale {
+                continue;
+            }
+
+            let src = fs::read_to_string(&path).map_err(io_err(&path))?;
+            let malformed = |err| RegistryError::Malformed { path: path.clone(), err };
+            if is_style {
+                let style = Style::from_xml(&src).map_err(malformed)?;
+                let stem = path.file_stem().and_then(|s| s.to_str()).map(Into::into);
+                styles.push(StyleEntry::parsed(
+                    style,
+                    stem,
+                    StyleOrigin::File(path.clone()),
+                ));
+            } else {
+                locales.push(LocaleFile::from_xml(&src).map_err(malformed)?.into());
+            }
+        }
+
+        self.styles.extend(styles);
+        self.locales.splice(0..0, locales);
+        Ok(())
+    }
+
+    /// Add a style from memory.
+    pub fn add_style(&mut self, style: Style) {
+        self.styles.push(StyleEntry::parsed(style, None, StyleOrigin::Memory));
+    }
+
+    /// Add a locale from memory.
+    pub fn add_locale(&mut self, locale: Locale) {
+        self.locales.insert(0, locale);
+    }
+
+    /// The registered locales, from the highest to the lowest precedence.
+    /// Pass them to a [`LocaleResolver`](super::LocaleResolver) or a request
+    /// to use them for rendering. Since the resolver consults all locale files
+    /// for a language in order, a registered locale that only defines some
+    /// terms overrides the archived locale for these terms.
+    pub fn locales(&self) -> &[Locale] {
+        &self.locales
+    }
+
+    /// The styles that are not overridden by a style with the same CSL ID,
+    /// in the order in which they were added.
+    pub fn styles(&self) -> impl Iterator<Item = &StyleEntry> + '_ {
+        let last = self.last_indices(|entry| std::iter::once(entry.id.as_str()));
+        self.styles
+            .iter()
+            .enumerate()
+            .filter(move |(i, entry)| last[entry.id.as_str()] == *i)
+            .map(|(_, entry)| entry)
+    }
+
+    /// Pairs of styles where the first style is overridden by the second
+    /// because they have the same CSL ID or share a name.
+    pub fn overrides(&self) -> Vec<(&StyleEntry, &StyleEntry)> {
+        let by_id = self.last_indices(|entry| std::iter::once(entry.id.as_str()));
+        let by_name = self.last_indices(|entry| entry.names.iter().map(String::as_str));
+        let mut res = vec![];
+        for (i, entry) in self.styles.iter().enumerate() {
+            let by = entry
+                .names
+                .iter()
+                .map(|n| by_name[n.as_str()])
+                .chain([by_id[entry.id.as_str()]])
+                .max()
+                .filter(|&j| j > i);
+            if let Some(j) = by {
+                res.push((entry, &self.styles[j]));
+            }
+        }
+        res
+    }
+
+    /// Map each key of the registered styles to the index of the last style
+    /// with that key.
+    fn last_indices<'s, I>(
+        &'s self,
+        keys: impl Fn(&'s StyleEntry) -> I,
+    ) -> HashMap<&'s str, usize>
+    where
+        I: Iterator<Item = &'s str>,
+    {
+        let mut map = HashMap::new();
+        for (i, entry) in self.styles.iter().enumerate() {
+            for key in keys(entry) {
+                map.insert(key, i);
+            }
+        }
+        map
+    }
+
+    /// Find a style by CSL ID, name, title, or ISSN, in this order.
+    pub fn get(&self, query: &str) -> Option<&StyleEntry> {
+        self.by_id(query)
+            .or_else(|| self.by_name(query))
+            .or_else(|| self.by_title(query))
+            .or_else(|| self.by_issn(query))
+    }
+
+    /// Find a style by its CSL ID.
+    pub fn by_id(&self, id: &str) -> Option<&StyleEntry> {
+        self.find(|entry| entry.id == id)
+    }
+
+    /// Find a style by one of its names, e.g. `apa`.
+    pub fn by_name(&self, name: &str) -> Option<&StyleEntry> {
+        self.find(|entry| entry.names.iter().any(|n| n == name))
+    }
+
+    /// Find a style by its full or short title. Case is ignored.
+    pub fn by_title(&self, title: &str) -> Option<&StyleEntry> {
+        let title = title.trim().to_lowercase();
+        self.find(|entry| {
+            entry.title.to_lowercase() == title
+                || entry.title_short.as_ref().is_some_and(|t| t.to_lowercase() == title)
+        })
+    }
+
+    /// Find the style of a journal by its ISSN or eISSN. The hyphen is
+    /// optional.
+    pub fn by_issn(&self, issn: &str) -> Option<&StyleEntry> {
+        let issn = normalize_issn(issn);
+        self.find(|entry| entry.issn.contains(&issn))
+    }
+
+    /// Find the styles whose CSL ID, names, titles, or ISSNs contain the
+    /// query. Case is ignored, and complete ISSNs also match without their
+    /// hyphen.
+    pub fn search(&self, query: &str) -> Vec<&StyleEntry> {
+        let query = normalize_issn(query).to_lowercase();
+        self.styles()
+            .filter(|entry| {
+                entry
+                    .names
+                    .iter()
+                    .chain([&entry.id, &entry.title])
+                    .chain(&entry.title_short)
+                    .chain(&entry.issn)
+                    .any(|s| s.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    /// Find a style like [`get`](Self::get) and resolve it to the independent
+    /// style that renders it.
+    pub fn independent(&self, query: &str) -> Option<IndependentStyle> {
+        self.resolve(self.get(query)?)
+    }
+
+    /// Resolve a registered style to the independent style that renders it.
+    /// The parents of dependent styles are looked up by CSL ID in the
+    /// registry. Returns `None` if the parent is not registered.
+    pub fn resolve(&self, entry: &StyleEntry) -> Option<IndependentStyle> {
+        match &entry.data {
+            #[cfg(feature = "archive")]
+            StyleData::Archived(style) => match style.get() {
+                Style::Independent(style) => Some(style),
+                Style::Dependent(_) => None,
+            },
+            StyleData::Parsed(style) => match style.as_ref() {
+                Style::Independent(style) => Some(style.clone()),
+                Style::Dependent(dependent) => {
+                    Some(apply_dependent(dependent, self.parent(entry)?))
+                }
+            },
+        }
+    }
+
+    /// Resolve a style that may not be registered, looking up the parent of a
+    /// dependent style in the registry.
+    pub fn resolve_style(&self, style: Style) -> Option<IndependentStyle> {
+        super::dependent::resolve_style(style, |id| self.parent_by_id(id))
+    }
+
+    /// Resolve the independent parent of a dependent style.
+    fn parent(&self, entry: &StyleEntry) -> Option<IndependentStyle> {
+        self.parent_by_id(entry.parent.as_deref()?)
+    }
+
+    /// Resolve a parent style by CSL ID. Parents that are dependent
+    /// themselves are not followed.
+    fn parent_by_id(&self, id: &str) -> Option<IndependentStyle> {
+        let parent = self.by_id(id)?;
+        parent.parent.is_none().then(|| self.resolve(parent)).flatten()
+    }
+
+    /// The style of the highest precedence that matches.
+    fn find(&self, f: impl Fn(&StyleEntry) -> bool) -> Option<&StyleEntry> {
+        self.styles.iter().rev().find(|entry| f(entry))
+    }
+}
+
+impl Default for StyleRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StyleEntry {
+    /// Create an entry for a parsed style. The name of a style is the last
+    /// segment of its CSL ID and, for files, the file stem.
+    fn parsed(style: Style, stem: Option<String>, origin: StyleOrigin) -> Self {
+        let info = style.info();
+        let mut names: Vec<String> = stem.into_iter().collect();
+        let last = info.id.rsplit('/').next().unwrap_or(&info.id);
+        if !last.is_empty() && !names.iter().any(|n| n == last) {
+            names.push(last.into());
+        }
+
+        let parent = match &style {
+            Style::Dependent(dependent) => Some(dependent.parent_link.href.clone()),
+            Style::Independent(_) => None,
+        };
+
+        let mut seen = HashSet::new();
+        let issn = info
+            .issn
+            .iter()
+            .chain(&info.eissn)
+            .chain(&info.issnl)
+            .map(|i| normalize_issn(i))
+            .filter(|i| seen.insert(i.clone()))
+            .collect();
+
+        Self {
+            id: info.id.clone(),
+            title: info.title.value.clone(),
+            title_short: info.title_short.as_ref().map(|t| t.value.clone()),
+            names,
+            issn,
+            parent,
+            origin,
+            data: StyleData::Parsed(Box::new(style)),
+        }
+    }
+
+    /// The CSL ID of the style.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The title of the style.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The short title of the style, if any.
+    pub fn title_short(&self) -> Option<&str> {
+        self.title_short.as_deref()
+    }
+
+    /// The names by which the style can be looked up, e.g. `apa`.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The ISSNs of the journal the style is for.
+    pub fn issn(&self) -> &[String] {
+        &self.issn
+    }
+
+    /// The CSL ID of the independent parent, if this is a dependent style.
+    pub fn parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    /// Where the style comes from.
+    pub fn origin(&self) -> &StyleOrigin {
+        &self.origin
+    }
+
+    /// The metadata of the style.
+    pub fn info(&self) -> Cow<'_, StyleInfo> {
+        match &self.data {
+            #[cfg(feature = "archive")]
+            StyleData::Archived(style) => Cow::Owned(style.get().info().clone()),
+            StyleData::Parsed(style) => Cow::Borrowed(style.info()),
+        }
+    }
+}
+
+/// Bring an ISSN into its canonical form for comparison. Malformed ISSNs are
+/// kept as they are.
+fn normalize_issn(issn: &str) -> String {
+    IdentifierKind::Issn.normalize(issn).unwrap_or_else(|_| issn.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use citationberg::LocaleCode;
+
+    use super::*;
+
+    fn style(id: &str, title: &str, parent: Option<&str>) -> Style {
+        let body = match parent {
+            Some(parent) => {
+                format!(r#"<link href="{parent}" rel="independent-parent"/>"#)
+            }
+            None => String::new(),
+        };
+        let layout = match parent {
+            Some(_) => "",
+            None => r#"<citation><layout><text variable="title"/></layout></citation>"#,
+        };
+        let class = if parent.is_none() { r#"class="in-text""# } else { "" };
+        Style::from_xml(&format!(
+            r#"<style xmlns="http://purl.org/net/xbiblio/csl" version="1.0" {class} default-locale="de-DE">
+  <info>
+    <title>{title}</title>
+    <id>{id}</id>
+    {body}
+    <issn>0317-8471</issn>
+    <updated>2024-01-01T00:00:00+00:00</updated>
+  </info>
+  {layout}
+</style>"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn layers() {
+        let mut registry = StyleRegistry::empty();
+        registry.add_style(style("http://example.org/styles/house", "House", None));
+        registry.add_style(style(
+            "http://example.org/styles/journal",
+            "Journal of Houses",
+            Some("http://example.org/styles/house"),
+        ));
+
+        let journal = registry.get("Journal of houses").unwrap();
+        assert_eq!(journal.names(), ["journal"]);
+        assert_eq!(journal.parent(), Some("http://example.org/styles/house"));
+        assert_eq!(registry.get("03178471").unwrap().id(), journal.id());
+        let resolved = registry.resolve(journal).unwrap();
+        assert_eq!(resolved.info.title.value, "House");
+        assert_eq!(resolved.default_locale, Some(LocaleCode("de-DE".into())));
+
+        // A later style with the same ID overrides the earlier one.
+        registry.add_style(style("http://example.org/styles/house", "New House", None));
+        assert_eq!(registry.get("house").unwrap().title(), "New House");
+        assert_eq!(registry.styles().count(), 2);
+        assert_eq!(registry.overrides().len(), 1);
+        assert_eq!(
+            registry.independent("journal").unwrap().info.title.value,
+            "New House"
+        );
+        assert_eq!(registry.search("HOUSE").len(), 2);
+        assert_eq!(registry.search("0317-84").len(), 2);
+        assert_eq!(registry.search("03178471").len(), 2);
+        assert!(registry.search("8765").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "archive")]
+    fn archive_layer() {
+        let mut registry = StyleRegistry::new();
+        assert!(registry.get("apa").is_some());
+        assert!(registry.get("http://www.zotero.org/styles/apa").is_some());
+
+        registry.add_style(style("http://example.org/styles/apa", "My APA", None));
+        assert_eq!(registry.get("apa").unwrap().origin(), &StyleOrigin::Memory);
+        let overrides = registry.overrides();
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].0.origin(), &StyleOrigin::Archive);
+    }
+}
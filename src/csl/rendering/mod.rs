@@ -8,18 +8,21 @@ use citationberg::taxonomy::{
 use citationberg::{
     ChooseBranch, CslMacro, DateDayForm, DateMonthForm, DatePartName, DateParts,
     DateStrongAnyForm, GrammarGender, LabelPluralize, LayoutRenderingElement,
-    LongShortForm, NumberForm, TestPosition, TextCase, ToAffixes, ToFormatting,
+    LongShortForm, NumberForm, PageRangeFormat, TestPosition, TextCase, ToAffixes,
+    ToFormatting,
 };
 use citationberg::{TermForm, TextTarget};
 
 use crate::csl::taxonomy::NumberVariableResult;
 use crate::lang::{Case, SentenceCase, TitleCase};
-use crate::types::{ChunkedString, Date, MaybeTyped, Numeric};
+use crate::types::{ChunkedString, Date, MaybeTyped, NumberNotation, Numeric};
 
 use super::taxonomy::EntryLike;
 use super::{Context, ElemMeta, IbidState, SpecialForm, UsageInfo};
+use trace::{describe_branch, describe_element, SuppressionReason, TraceKind};
 
 pub mod names;
+pub mod trace;
 
 /// All rendering elements implement this trait. It allows you to format an
 /// [`Entry`] with them.
@@ -351,7 +354,7 @@ fn render_typed_num<T: EntryLike>(
 ) {
     let normal_num = if form == NumberForm::Numeric && variable == NumberVariable::Page {
         if let Some(range) = num.range() {
-            render_page_range(range, ctx);
+            render_page_range(num, range, ctx);
             false
         } else {
             true
@@ -366,21 +369,35 @@ fn render_typed_num<T: EntryLike>(
 }
 
 fn render_page_range<T: EntryLike>(
+    num: &Numeric,
     range: std::ops::RangeInclusive<i32>,
     ctx: &mut Context<T>,
 ) {
-    ctx.style
-        .csl
-        .settings
-        .page_range_format
-        .unwrap_or_default()
-        .format(
-            range,
-            ctx,
-            ctx.term(OtherTerm::PageRangeDelimiter.into(), TermForm::default(), false)
-                .or(Some("–")),
-        )
-        .unwrap();
+    let format = ctx.style.csl.settings.page_range_format.unwrap_or_default();
+    let delim = ctx
+        .term(OtherTerm::PageRangeDelimiter.into(), TermForm::default(), false)
+        .unwrap_or("–");
+
+    num.fmt_repeated_prefix(ctx).unwrap();
+    if range.start() == range.end() {
+        num.fmt_number(*range.start(), ctx).unwrap();
+        return;
+    }
+
+    match num.notation {
+        // Only Arabic numerals can be abbreviated. If each number carries a
+        // prefix, it is only repeated in the expanded form.
+        NumberNotation::Arabic => format.format(range, ctx, Some(delim)).unwrap(),
+        NumberNotation::PrefixedArabic if format != PageRangeFormat::Expanded => {
+            format.format(range, ctx, Some(delim)).unwrap()
+        }
+        _ => {
+            num.fmt_number(*range.start(), ctx).unwrap();
+            ctx.push_str(delim);
+            num.fmt_repeated_prefix(ctx).unwrap();
+            num.fmt_number(*range.end(), ctx).unwrap();
+        }
+    }
 }
 
 fn label_pluralization(
@@ -771,9 +788,12 @@ fn render_year_suffix_implicitly<T: EntryLike>(ctx: &mut Context<T>) {
     }
 }
 
+/// Select the branch of a `cs:choose` element and call `f` with its children.
+/// If `trace` is set, the tested branches are recorded in the trace.
 fn choose_children<F, R, T: EntryLike>(
     choose: &citationberg::Choose,
     ctx: &mut Context<T>,
+    trace: bool,
     mut f: F,
 ) -> Option<R>
 where
@@ -781,33 +801,54 @@ where
 {
     let supressed = ctx.writing.suppress_queried_variables;
     ctx.writing.stop_suppressing_queried_variables();
-    let branch = choose
-        .branches()
-        .find(|branch| branch.match_.test(BranchConditionIter::from_branch(branch, ctx)));
+    let branch = choose.branches().enumerate().find(|(i, branch)| {
+        let matched = branch.match_.test(BranchConditionIter::from_branch(branch, ctx));
+        if trace {
+            let element = if *i == 0 { "if" } else { "else-if" };
+            ctx.trace(|| TraceKind::Branch {
+                test: describe_branch(element, branch),
+                matched,
+            });
+        }
+        matched
+    });
     ctx.writing.suppress_queried_variables = supressed;
 
-    branch
-        .map(|b| b.children.as_slice())
-        .or_else(|| choose.otherwise.as_ref().map(|f| f.children.as_slice()))
-        .map(|children| f(children, ctx))
+    let (element, children) = match branch {
+        Some((0, branch)) => ("if", branch.children.as_slice()),
+        Some((_, branch)) => ("else-if", branch.children.as_slice()),
+        None => ("else", choose.otherwise.as_ref()?.children.as_slice()),
+    };
+
+    if trace {
+        if element == "else" {
+            ctx.trace(|| TraceKind::Branch { test: "else".into(), matched: true });
+        }
+        ctx.trace_enter(|| element.into());
+    }
+    let res = f(children, ctx);
+    if trace {
+        ctx.trace_exit();
+    }
+    Some(res)
 }
 
 impl RenderCsl for citationberg::Choose {
     fn render<T: EntryLike>(&self, ctx: &mut Context<T>) {
-        choose_children(self, ctx, |children, ctx| {
+        choose_children(self, ctx, true, |children, ctx| {
             render_with_delimiter(children, self.delimiter.as_deref(), ctx);
         });
     }
 
     fn will_render<T: EntryLike>(&self, ctx: &mut Context<T>, var: Variable) -> bool {
-        choose_children(self, ctx, |children, ctx| {
+        choose_children(self, ctx, false, |children, ctx| {
             children.iter().any(|c| c.will_render(ctx, var))
         })
         .unwrap_or_default()
     }
 
     fn will_have_info<T: EntryLike>(&self, ctx: &mut Context<T>) -> (bool, UsageInfo) {
-        choose_children(self, ctx, |children, ctx| {
+        choose_children(self, ctx, false, |children, ctx| {
             let mut info = UsageInfo::default();
             let mut will_print = false;
 
@@ -833,7 +874,18 @@ fn render_with_delimiter<T: EntryLike>(
 
     for child in children {
         // Do not render the child if it will not print anything.
-        if !child.will_have_info(ctx).0 {
+        let (print, info) = child.will_have_info(ctx);
+        if !print {
+            ctx.trace_enter(|| describe_element(child));
+            ctx.trace(|| {
+                TraceKind::Suppressed(match child {
+                    LayoutRenderingElement::Group(_) if !info.should_render_group() => {
+                        SuppressionReason::EmptyGroup
+                    }
+                    _ => SuppressionReason::NoOutput,
+                })
+            });
+            ctx.trace_exit();
             continue;
         }
 
@@ -851,17 +903,7 @@ fn render_with_delimiter<T: EntryLike>(
         }
 
         let pos = ctx.push_elem(citationberg::Formatting::default());
-
-        match child {
-            LayoutRenderingElement::Text(text) => text.render(ctx),
-            LayoutRenderingElement::Number(num) => num.render(ctx),
-            LayoutRenderingElement::Label(label) => label.render(ctx),
-            LayoutRenderingElement::Date(date) => date.render(ctx),
-            LayoutRenderingElement::Names(names) => names.render(ctx),
-            LayoutRenderingElement::Choose(choose) => choose.render(ctx),
-            LayoutRenderingElement::Group(_group) => _group.render(ctx),
-        }
-
+        child.render(ctx);
         first = false;
         ctx.commit_elem(pos, None, None);
     }
@@ -1115,6 +1157,7 @@ impl RenderCsl for citationberg::Group {
         if info.should_render_group() {
             ctx.commit_elem(idx, self.display, None);
         } else {
+            ctx.trace(|| TraceKind::Suppressed(SuppressionReason::EmptyGroup));
             ctx.discard_elem(idx);
         }
     }
@@ -1148,6 +1191,7 @@ impl RenderCsl for citationberg::Group {
 
 impl RenderCsl for citationberg::LayoutRenderingElement {
     fn render<T: EntryLike>(&self, ctx: &mut Context<T>) {
+        ctx.trace_enter(|| describe_element(self));
         match self {
             citationberg::LayoutRenderingElement::Text(text) => text.render(ctx),
             citationberg::LayoutRenderingElement::Number(num) => num.render(ctx),
@@ -1157,10 +1201,11 @@ impl RenderCsl for citationberg::LayoutRenderingElement {
             citationberg::LayoutRenderingElement::Choose(choose) => choose.render(ctx),
             citationberg::LayoutRenderingElement::Group(group) => group.render(ctx),
         }
+        ctx.trace_exit();
     }
 
     fn will_render<T: EntryLike>(&self, ctx: &mut Context<T>, var: Variable) -> bool {
-        match self {
+        ctx.probe(|ctx| match self {
             citationberg::LayoutRenderingElement::Text(text) => {
                 text.will_render(ctx, var)
             }
@@ -1182,11 +1227,11 @@ impl RenderCsl for citationberg::LayoutRenderingElement {
             citationberg::LayoutRenderingElement::Group(group) => {
                 group.will_render(ctx, var)
             }
-        }
+        })
     }
 
     fn will_have_info<T: EntryLike>(&self, ctx: &mut Context<T>) -> (bool, UsageInfo) {
-        match self {
+        ctx.probe(|ctx| match self {
             citationberg::LayoutRenderingElement::Text(text) => text.will_have_info(ctx),
             citationberg::LayoutRenderingElement::Number(num) => num.will_have_info(ctx),
             citationberg::LayoutRenderingElement::Label(label) => {
@@ -1202,17 +1247,19 @@ impl RenderCsl for citationberg::LayoutRenderingElement {
             citationberg::LayoutRenderingElement::Group(group) => {
                 group.will_have_info(ctx)
             }
-        }
+        })
     }
 }
 
 impl RenderCsl for citationberg::Layout {
     fn render<T: EntryLike>(&self, ctx: &mut Context<T>) {
+        ctx.trace_enter(|| "layout".into());
         let fidx = ctx.push_format(self.to_formatting());
         for e in &self.elements {
             e.render(ctx);
         }
         ctx.pop_format(fidx);
+        ctx.trace_exit();
     }
 
     fn will_render<T: EntryLike>(&self, ctx: &mut Context<T>, var: Variable) -> bool {
@@ -14,6 +14,7 @@ use crate::csl::taxonomy::EntryLike;
 use crate::csl::{Context, DisambiguateState, ElemMeta, SpecialForm, UsageInfo};
 use crate::types::Person;
 
+use super::trace::describe_element;
 use super::{render_label_with_var, RenderCsl};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -68,6 +69,52 @@ impl DisambiguatedNameForm {
     pub fn is_long(self) -> bool {
         matches!(self, Self::LongFull | Self::LongInitialized)
     }
+
+    /// Expand the form until the name of `person` cannot be confused with
+    /// the names of any of the `others` anymore. Names that cannot be told
+    /// apart are expanded as far as possible.
+    pub fn distinguish<'p>(
+        self,
+        person: &Person,
+        others: impl Iterator<Item = &'p Person> + Clone,
+        allow_full_first_name: bool,
+    ) -> Self {
+        if person.given_name.is_none() {
+            return self;
+        }
+
+        let mut form = self;
+        loop {
+            let key = form.name_key(person);
+            if !others.clone().any(|o| o != person && form.name_key(o) == key) {
+                return form;
+            }
+
+            match form.disambiguate(allow_full_first_name) {
+                Some(next) => form = next,
+                None => return form,
+            }
+        }
+    }
+
+    /// A string that is identical for two persons if and only if their names
+    /// look the same in this form.
+    fn name_key(self, person: &Person) -> String {
+        let mut key = String::new();
+        match self {
+            Self::LongInitialized => {
+                person.initials(&mut key, Some("."), false).unwrap();
+            }
+            Self::LongFull => {
+                key.push_str(person.given_name.as_deref().unwrap_or_default())
+            }
+            Self::Count | Self::ShortInitialized | Self::ShortFull => {}
+        }
+
+        key.push(' ');
+        key.push_str(&person.name_with_particle());
+        key
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -126,11 +173,14 @@ impl NameDisambiguationProperties {
         false
     }
 
-    /// Disambuiguate a list of identical names.
-    ///
-    /// TODO: Use for disambiguation of identical names.
-    #[allow(dead_code)]
-    pub fn disambiguate_list(&mut self, variable: NameVariable, items: &[usize]) -> bool {
+    /// Disambiguate a list of identical names by expanding the names at the
+    /// given indices of the variable by one step.
+    pub fn disambiguate_list(
+        &mut self,
+        variable: NameVariable,
+        items: &[usize],
+        allow_full_first_name: bool,
+    ) -> bool {
         let mut change = false;
         for &idx in items {
             let Some(outer) = self.variables.iter().position(|v| v == &variable) else {
@@ -139,7 +189,7 @@ impl NameDisambiguationProperties {
 
             let form = &mut self.name_forms[outer][idx];
             if let Some(form) = form {
-                if let Some(new_form) = form.disambiguate(true) {
+                if let Some(new_form) = form.disambiguate(allow_full_first_name) {
                     *form = new_form;
                     change = true;
                 }
@@ -152,6 +202,35 @@ impl NameDisambiguationProperties {
         change
     }
 
+    /// The forms of all rendered names with their variable and index. If
+    /// `primary_only` is set, only the first name is returned.
+    pub fn rendered_forms(
+        &self,
+        primary_only: bool,
+    ) -> impl Iterator<Item = (NameVariable, usize, DisambiguatedNameForm)> + '_ {
+        self.variables
+            .iter()
+            .zip(self.name_forms.iter())
+            .flat_map(|(&variable, forms)| {
+                forms
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(i, form)| Some((variable, i, (*form)?)))
+            })
+            .filter(|&(_, _, form)| form != DisambiguatedNameForm::Count)
+            .take(if primary_only { 1 } else { usize::MAX })
+    }
+
+    /// The current form of a name.
+    pub fn form(
+        &self,
+        variable: NameVariable,
+        idx: usize,
+    ) -> Option<DisambiguatedNameForm> {
+        let outer = self.variables.iter().position(|v| v == &variable)?;
+        self.name_forms.get(outer)?.get(idx).copied().flatten()
+    }
+
     /// Return the more disambiguated form of the name.
     pub fn max(self, other: Self) -> Self {
         let count_some = |x: &Self| {
@@ -260,11 +339,14 @@ impl RenderCsl for Names {
         if is_empty {
             if let Some(substitute) = &self.substitute() {
                 ctx.writing.start_suppressing_queried_variables();
+                ctx.trace_enter(|| "substitute".into());
 
                 for child in &substitute.children {
                     let len = ctx.writing.len();
                     if let LayoutRenderingElement::Names(names_child) = child {
-                        self.from_names_substitute(names_child).render(ctx)
+                        ctx.trace_enter(|| describe_element(child));
+                        self.from_names_substitute(names_child).render(ctx);
+                        ctx.trace_exit();
                     } else {
                         child.render(ctx);
                     }
@@ -273,6 +355,7 @@ impl RenderCsl for Names {
                     }
                 }
 
+                ctx.trace_exit();
                 ctx.writing.stop_suppressing_queried_variables();
             }
 
@@ -371,10 +454,6 @@ impl RenderCsl for Names {
             do_label(NameLabelPosition::AfterName, ctx);
         }
 
-        // TODO Compare each elem with a name meta and run
-        // [`NameDisambiguationProperties::disambiguate_list`] on the identical
-        // pairs. Rerender if necessary.
-
         ctx.apply_suffix(&self.to_affixes(), affix_loc);
         ctx.commit_elem(depth, self.display, Some(ElemMeta::Names));
         ctx.writing.pop_name_options();
@@ -627,7 +706,7 @@ fn write_name<T: EntryLike>(
         if let Some(prefix) = family_affixes[0] {
             ctx.push_str(prefix);
         }
-        ctx.push_str(&name.name);
+        ctx.push_str(&name.name_with_particle());
         ctx.pop_case(cidx);
         ctx.pop_format(idx);
         if let Some(suffix) = family_affixes[1] {
@@ -643,7 +722,7 @@ fn write_name<T: EntryLike>(
             ctx.push_str(prefix);
         }
 
-        ctx.push_str(&name.name);
+        ctx.push_str(&name.name_with_particle());
 
         ctx.pop_case(cidx);
         ctx.pop_format(idx);
@@ -810,7 +889,7 @@ fn write_name<T: EntryLike>(
                 ctx.push_str(prefix);
             }
 
-            ctx.push_str(&name.name);
+            ctx.push_str(&name.name_with_particle());
 
             ctx.pop_case(cidx);
             ctx.pop_format(idx);
@@ -0,0 +1,293 @@
+//! Traces that explain how a style rendered an item.
+
+use std::fmt;
+
+use citationberg::taxonomy::Variable;
+use citationberg::{ChooseBranch, ChooseMatch, LayoutRenderingElement, TextTarget};
+use serde::Serialize;
+
+use crate::csl::taxonomy::EntryLike;
+use crate::csl::Context;
+
+/// A record of the decisions made while rendering an item with a style.
+///
+/// Traces are only recorded if requested with
+/// [`BibliographyRequest::trace`](crate::BibliographyRequest::trace). Their
+/// [`Display`](fmt::Display) implementation prints the evaluated elements as
+/// an indented tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RenderTrace {
+    /// The key of the rendered entry.
+    pub key: String,
+    /// The events in the order in which they occurred.
+    pub events: Vec<TraceEvent>,
+}
+
+/// A single event of a [`RenderTrace`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraceEvent {
+    /// The path of CSL elements that were being evaluated, starting at the
+    /// layout, e.g. `["layout", "group", "text macro=\"author\""]`.
+    pub path: Vec<String>,
+    /// What happened.
+    pub kind: TraceKind,
+}
+
+/// What happened in a [`TraceEvent`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TraceKind {
+    /// The last element of the path was rendered.
+    Render,
+    /// A branch of a `cs:choose` element was tested.
+    Branch {
+        /// The branch element and its conditions, e.g. `if type="book"`.
+        test: String,
+        /// Whether the branch was chosen.
+        matched: bool,
+    },
+    /// A variable was consulted.
+    Variable {
+        /// The name of the variable.
+        variable: String,
+        /// Whether the variable had a value.
+        present: bool,
+    },
+    /// Output was suppressed.
+    Suppressed(SuppressionReason),
+}
+
+/// Why output was suppressed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SuppressionReason {
+    /// A group was suppressed because it consulted variables and all of them
+    /// were empty.
+    EmptyGroup,
+    /// An element was skipped because it would not print anything.
+    NoOutput,
+    /// A variable was suppressed because it was already rendered in place of
+    /// a name variable by `cs:substitute`.
+    Substituted(String),
+}
+
+impl fmt::Display for RenderTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.key)?;
+        for event in self.events.iter() {
+            let depth = event.path.len();
+            match &event.kind {
+                TraceKind::Render => {
+                    let element = event.path.last().map_or("", String::as_str);
+                    writeln!(f, "{:indent$}{element}", "", indent = depth * 2)?;
+                }
+                kind => {
+                    writeln!(f, "{:indent$}- {kind}", "", indent = depth * 2 + 2)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TraceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Render => f.write_str("rendered"),
+            Self::Branch { test, matched: true } => write!(f, "{test}: chosen"),
+            Self::Branch { test, matched: false } => write!(f, "{test}: not matched"),
+            Self::Variable { variable, present: true } => {
+                write!(f, "variable {variable}: present")
+            }
+            Self::Variable { variable, present: false } => {
+                write!(f, "variable {variable}: empty")
+            }
+            Self::Suppressed(reason) => write!(f, "suppressed: {reason}"),
+        }
+    }
+}
+
+impl fmt::Display for SuppressionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyGroup => f.write_str("all variables of the group are empty"),
+            Self::NoOutput => f.write_str("the element prints nothing"),
+            Self::Substituted(variable) => {
+                write!(f, "{variable} was already rendered by cs:substitute")
+            }
+        }
+    }
+}
+
+/// Records a trace while rendering.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Tracer {
+    /// The path of the element that is currently being rendered.
+    path: Vec<String>,
+    /// The recorded events.
+    events: Vec<TraceEvent>,
+    /// How many probes of elements are in progress. Probes check whether
+    /// elements will print something without rendering them and are not
+    /// recorded.
+    probes: usize,
+}
+
+impl Tracer {
+    /// Enter an element.
+    pub fn enter(&mut self, element: String) {
+        if self.probes > 0 {
+            return;
+        }
+        self.path.push(element);
+        self.events
+            .push(TraceEvent { path: self.path.clone(), kind: TraceKind::Render });
+    }
+
+    /// Leave the current element.
+    pub fn exit(&mut self) {
+        if self.probes == 0 {
+            self.path.pop();
+        }
+    }
+
+    /// Record an event for the current element. Variable lookups and
+    /// suppressions are only recorded once per element because elements are
+    /// often probed before they are rendered.
+    pub fn record(&mut self, kind: TraceKind) {
+        if self.probes > 0 {
+            return;
+        }
+
+        let event = TraceEvent { path: self.path.clone(), kind };
+        if matches!(event.kind, TraceKind::Variable { .. } | TraceKind::Suppressed(_))
+            && self.events.contains(&event)
+        {
+            return;
+        }
+
+        self.events.push(event);
+    }
+
+    /// Finish the trace for an entry.
+    pub fn finish(self, key: String) -> RenderTrace {
+        RenderTrace { key, events: self.events }
+    }
+}
+
+impl<T: EntryLike> Context<'_, T> {
+    /// Probe elements without recording the lookups in the trace.
+    pub(super) fn probe<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        if let Some(tracer) = &self.writing.trace {
+            tracer.borrow_mut().probes += 1;
+        }
+        let res = f(self);
+        if let Some(tracer) = &self.writing.trace {
+            tracer.borrow_mut().probes -= 1;
+        }
+        res
+    }
+
+    /// Enter an element in the trace, if tracing.
+    pub(super) fn trace_enter(&self, element: impl FnOnce() -> String) {
+        if let Some(tracer) = &self.writing.trace {
+            tracer.borrow_mut().enter(element());
+        }
+    }
+
+    /// Leave the current element in the trace, if tracing.
+    pub(super) fn trace_exit(&self) {
+        if let Some(tracer) = &self.writing.trace {
+            tracer.borrow_mut().exit();
+        }
+    }
+
+    /// Record an event in the trace, if tracing.
+    pub(in crate::csl) fn trace(&self, kind: impl FnOnce() -> TraceKind) {
+        if let Some(tracer) = &self.writing.trace {
+            tracer.borrow_mut().record(kind());
+        }
+    }
+
+    /// Record the lookup of a variable in the trace, if tracing.
+    pub(in crate::csl) fn trace_variable(
+        &self,
+        variable: impl Into<Variable>,
+        present: bool,
+    ) {
+        self.trace(|| TraceKind::Variable {
+            variable: variable.into().to_string(),
+            present,
+        });
+    }
+}
+
+/// Describe an element for the trace path.
+pub(in crate::csl) fn describe_element(element: &LayoutRenderingElement) -> String {
+    match element {
+        LayoutRenderingElement::Text(text) => match &text.target {
+            TextTarget::Variable { var, .. } => format!("text variable=\"{var}\""),
+            TextTarget::Macro { name } => format!("text macro=\"{name}\""),
+            TextTarget::Term { term, .. } => format!("text term=\"{}\"", kebab(term)),
+            TextTarget::Value { val } => format!("text value=\"{val}\""),
+        },
+        LayoutRenderingElement::Number(num) => {
+            format!("number variable=\"{}\"", num.variable)
+        }
+        LayoutRenderingElement::Label(label) => {
+            format!("label variable=\"{}\"", label.variable)
+        }
+        LayoutRenderingElement::Date(date) => match date.variable {
+            Some(var) => format!("date variable=\"{var}\""),
+            None => "date".into(),
+        },
+        LayoutRenderingElement::Names(names) => format!(
+            "names variable=\"{}\"",
+            names
+                .variable
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+        LayoutRenderingElement::Choose(_) => "choose".into(),
+        LayoutRenderingElement::Group(_) => "group".into(),
+    }
+}
+
+/// Describe a branch of a `cs:choose` element and its conditions.
+pub(in crate::csl) fn describe_branch(element: &str, branch: &ChooseBranch) -> String {
+    fn list<V>(name: &str, values: &Option<Vec<V>>, f: impl Fn(&V) -> String) -> String {
+        match values {
+            Some(values) => format!(
+                " {name}=\"{}\"",
+                values.iter().map(f).collect::<Vec<_>>().join(" ")
+            ),
+            None => String::new(),
+        }
+    }
+
+    let mut res = element.to_string();
+    if let Some(disambiguate) = branch.disambiguate {
+        res.push_str(&format!(" disambiguate=\"{disambiguate}\""));
+    }
+    res.push_str(&list("is-numeric", &branch.is_numeric, ToString::to_string));
+    res.push_str(&list(
+        "is-uncertain-date",
+        &branch.is_uncertain_date,
+        ToString::to_string,
+    ));
+    res.push_str(&list("locator", &branch.locator, kebab));
+    res.push_str(&list("position", &branch.position, kebab));
+    res.push_str(&list("type", &branch.type_, kebab));
+    res.push_str(&list("variable", &branch.variable, ToString::to_string));
+    if branch.match_ != ChooseMatch::All {
+        res.push_str(&format!(" match=\"{}\"", kebab(&branch.match_)));
+    }
+    res
+}
+
+/// The CSL name of a value that is serialized as a string.
+pub(in crate::csl) fn kebab<V: Serialize>(value: &V) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => "?".into(),
+    }
+}
@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 
-use citationberg::taxonomy::Variable;
+use citationberg::taxonomy::{StandardVariable, Variable};
 use citationberg::{
     DemoteNonDroppingParticle, InheritableNameOptions, LocaleCode, LongShortForm, Sort,
     SortDirection, SortKey,
@@ -8,35 +8,96 @@ use citationberg::{
 
 use crate::csl::rendering::RenderCsl;
 use crate::csl::BufWriteFormat;
+use crate::lang::collation::Collator;
 
 use super::taxonomy::EntryLike;
 use super::{CitationItem, InstanceContext, StyleContext};
 
+/// An item to sort together with its precomputed text keys.
+struct SortItem<'b, 'c, T: EntryLike> {
+    item: &'b CitationItem<'c, T>,
+    /// The position of the item before sorting.
+    pos: usize,
+    idx: usize,
+    /// For each sort key, the text that is compared for variable and macro
+    /// keys. Text keys are computed once per item rather than once per
+    /// comparison.
+    texts: Vec<Option<String>>,
+}
+
 impl<'a> StyleContext<'a> {
-    /// Retrieve the ordering of two entries according to the given sort key.
-    fn cmp_entries<T: EntryLike>(
+    /// Compute the text that is compared for a sort key, if the key compares
+    /// text.
+    fn sort_text<T: EntryLike>(
         &self,
-        a: &CitationItem<T>,
-        a_idx: usize,
-        b: &CitationItem<T>,
-        b_idx: usize,
+        item: &CitationItem<T>,
+        idx: usize,
         key: &SortKey,
         term_locale: Option<&LocaleCode>,
-    ) -> Ordering {
-        let ordering = match key {
+        collator: &Collator,
+    ) -> Option<String> {
+        match key {
             SortKey::Variable { variable: Variable::Standard(s), .. } => {
-                let a = InstanceContext::sort_instance(a, a_idx)
-                    .resolve_standard_variable(LongShortForm::default(), *s)
-                    .map(|s| s.to_string());
-                let b = InstanceContext::sort_instance(b, b_idx)
-                    .resolve_standard_variable(LongShortForm::default(), *s)
-                    .map(|s| s.to_string());
-
-                a.cmp(&b)
+                let value = InstanceContext::sort_instance(item, idx)
+                    .resolve_standard_variable(LongShortForm::default(), *s)?
+                    .to_string();
+
+                // Leading articles of titles are ignored.
+                Some(if is_title(*s) {
+                    collator.strip_article(&value).to_string()
+                } else {
+                    value
+                })
             }
+            SortKey::MacroName {
+                name,
+                names_min,
+                names_use_first,
+                names_use_last,
+                ..
+            } => {
+                let mut ctx =
+                    self.sorting_ctx(item, idx, item.locale.as_ref(), term_locale, false);
+                ctx.writing.name_options.push(InheritableNameOptions {
+                    et_al_min: *names_min,
+                    et_al_subsequent_min: *names_min,
+                    et_al_use_first: *names_use_first,
+                    et_al_subsequent_use_first: *names_use_first,
+                    et_al_use_last: *names_use_last,
+                    ..Default::default()
+                });
+
+                self.get_macro(name).map(|m| {
+                    for child in &m.children {
+                        child.render(&mut ctx)
+                    }
+                    ctx.flush().0.into_iter().fold(String::new(), |mut s, f| {
+                        f.write_buf(&mut s, BufWriteFormat::Plain).unwrap();
+                        s
+                    })
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Retrieve the ordering of two entries according to the sort key with
+    /// the given index.
+    fn cmp_entries<T: EntryLike>(
+        &self,
+        a: &SortItem<T>,
+        b: &SortItem<T>,
+        key_idx: usize,
+        key: &SortKey,
+        collator: &Collator,
+    ) -> Ordering {
+        let ordering = match key {
+            SortKey::Variable { variable: Variable::Standard(_), .. }
+            | SortKey::MacroName { .. } => collator
+                .compare_opt(a.texts[key_idx].as_deref(), b.texts[key_idx].as_deref()),
             SortKey::Variable { variable: Variable::Date(d), .. } => {
-                let a = a.entry.resolve_date_variable(*d);
-                let b = b.entry.resolve_date_variable(*d);
+                let a = a.item.entry.resolve_date_variable(*d);
+                let b = b.item.entry.resolve_date_variable(*d);
 
                 match (a, b) {
                     (Some(a), Some(b)) => a.csl_cmp(&b),
@@ -46,8 +107,8 @@ impl<'a> StyleContext<'a> {
                 }
             }
             SortKey::Variable { variable: Variable::Name(n), .. } => {
-                let a = a.entry.resolve_name_variable(*n);
-                let b = b.entry.resolve_name_variable(*n);
+                let a = a.item.entry.resolve_name_variable(*n);
+                let b = b.item.entry.resolve_name_variable(*n);
 
                 for (a_pers, b_pers) in a.iter().zip(b.iter()) {
                     let ord = a_pers.csl_cmp(
@@ -55,6 +116,7 @@ impl<'a> StyleContext<'a> {
                         LongShortForm::Long,
                         self.csl.settings.demote_non_dropping_particle
                             != DemoteNonDroppingParticle::Never,
+                        collator,
                     );
                     if ord != Ordering::Equal {
                         return ord;
@@ -70,10 +132,10 @@ impl<'a> StyleContext<'a> {
                 }
             }
             SortKey::Variable { variable: Variable::Number(n), .. } => {
-                let a =
-                    InstanceContext::sort_instance(a, a_idx).resolve_number_variable(*n);
-                let b =
-                    InstanceContext::sort_instance(b, b_idx).resolve_number_variable(*n);
+                let a = InstanceContext::sort_instance(a.item, a.idx)
+                    .resolve_number_variable(*n);
+                let b = InstanceContext::sort_instance(b.item, b.idx)
+                    .resolve_number_variable(*n);
 
                 match (a, b) {
                     (Some(a), Some(b)) => a.csl_cmp(&b),
@@ -82,46 +144,6 @@ impl<'a> StyleContext<'a> {
                     (None, None) => Ordering::Equal,
                 }
             }
-            SortKey::MacroName {
-                name,
-                names_min,
-                names_use_first,
-                names_use_last,
-                ..
-            } => {
-                let render = |entry: &CitationItem<T>, idx: usize| {
-                    let mut ctx = self.sorting_ctx(
-                        entry,
-                        idx,
-                        entry.locale.as_ref(),
-                        term_locale,
-                        false,
-                    );
-                    ctx.writing.name_options.push(InheritableNameOptions {
-                        et_al_min: *names_min,
-                        et_al_subsequent_min: *names_min,
-                        et_al_use_first: *names_use_first,
-                        et_al_subsequent_use_first: *names_use_first,
-                        et_al_use_last: *names_use_last,
-                        ..Default::default()
-                    });
-
-                    self.get_macro(name).map(|m| {
-                        for child in &m.children {
-                            child.render(&mut ctx)
-                        }
-                        ctx.flush().0.into_iter().fold(String::new(), |mut s, f| {
-                            f.write_buf(&mut s, BufWriteFormat::Plain).unwrap();
-                            s.to_lowercase()
-                        })
-                    })
-                };
-
-                let a_rendered = render(a, a_idx);
-                let b_rendered = render(b, b_idx);
-
-                a_rendered.cmp(&b_rendered)
-            }
         };
 
         if key.sort_direction() == SortDirection::Descending {
@@ -138,17 +160,68 @@ impl<'a> StyleContext<'a> {
         sort: Option<&Sort>,
         term_locale: Option<&LocaleCode>,
     ) {
-        if let Some(sort) = sort {
-            cites.sort_by(|a, b| {
-                let mut ordering = Ordering::Equal;
-                for key in &sort.keys {
-                    ordering = self.cmp_entries(a, 0, b, 0, key, term_locale);
-                    if ordering != Ordering::Equal {
-                        break;
-                    }
+        let Some(sort) = sort else { return };
+        if cites.len() < 2 {
+            return;
+        }
+
+        let collator = self.collator(term_locale);
+        let mut items: Vec<SortItem<T>> = cites
+            .iter()
+            .enumerate()
+            .map(|(pos, item)| SortItem {
+                item,
+                pos,
+                idx: 0,
+                texts: sort
+                    .keys
+                    .iter()
+                    .map(|key| self.sort_text(item, 0, key, term_locale, &collator))
+                    .collect(),
+            })
+            .collect();
+
+        items.sort_by(|a, b| {
+            let mut ordering = Ordering::Equal;
+            for (i, key) in sort.keys.iter().enumerate() {
+                ordering = self.cmp_entries(a, b, i, key, &collator);
+                if ordering != Ordering::Equal {
+                    break;
                 }
-                ordering
-            });
+            }
+            ordering
+        });
+
+        let order: Vec<_> = items.into_iter().map(|i| i.pos).collect();
+        permute(cites, order);
+    }
+}
+
+/// Reorder a slice in place such that the element at index `i` is the one
+/// previously at `order[i]`.
+fn permute<T>(slice: &mut [T], mut order: Vec<usize>) {
+    for i in 0..slice.len() {
+        let mut j = i;
+        while order[j] != i {
+            let k = order[j];
+            slice.swap(j, k);
+            order[j] = j;
+            j = k;
         }
+        order[j] = j;
     }
 }
+
+/// Whether the variable is a title whose leading article is ignored when
+/// sorting.
+fn is_title(variable: StandardVariable) -> bool {
+    matches!(
+        variable,
+        StandardVariable::Title
+            | StandardVariable::ContainerTitle
+            | StandardVariable::CollectionTitle
+            | StandardVariable::OriginalTitle
+            | StandardVariable::ReviewedTitle
+            | StandardVariable::VolumeTitle
+    )
+}
@@ -3,20 +3,29 @@ use std::cmp;
 use std::str::FromStr;
 
 use crate::types::{
-    ChunkedString, Date, EntryType, MaybeTyped, Numeric, Person, PersonRole, StringChunk,
+    ChunkKind, ChunkedString, Date, DurationRange, EntryType, FormatString,
+    LanguageVariant, MaybeTyped, Numeric, Person, PersonRole, StringChunk,
 };
 use crate::Entry;
 use citationberg::taxonomy::{
-    DateVariable, Kind, NameVariable, NumberVariable, StandardVariable,
+    DateVariable, Kind, Locator, NameVariable, NumberVariable, StandardVariable,
 };
 use citationberg::{taxonomy, LongShortForm};
 use unic_langid::LanguageIdentifier;
 
 #[cfg(feature = "csl-json")]
 use citationberg::json as csl_json;
+#[cfg(feature = "csl-json")]
+use url::Url;
+
+#[cfg(feature = "csl-json")]
+use crate::types::archive_name;
 
 use super::citation_label::Alphanumerical;
-use super::{DisambiguateState, InstanceContext, LocatorPayload};
+use super::{
+    Abbreviations, DisambiguateState, InstanceContext, LanguageVariants, LocatorPayload,
+    SpecificLocator,
+};
 
 pub trait EntryLike {
     fn resolve_number_variable(
@@ -28,6 +37,19 @@ pub trait EntryLike {
         form: LongShortForm,
         variable: StandardVariable,
     ) -> Option<Cow<'_, ChunkedString>>;
+    /// Resolve a standard variable in a language variant. Returns `None` if
+    /// the entry does not have this variant.
+    fn resolve_standard_variable_variant(
+        &self,
+        form: LongShortForm,
+        variable: StandardVariable,
+        variant: &LanguageVariant,
+    ) -> Option<Cow<'_, ChunkedString>> {
+        match variant {
+            LanguageVariant::Original => self.resolve_standard_variable(form, variable),
+            _ => None,
+        }
+    }
     fn resolve_name_variable(&self, variable: NameVariable) -> Vec<Cow<'_, Person>>;
     fn resolve_date_variable(&self, variable: DateVariable) -> Option<Cow<'_, Date>>;
     fn matches_entry_type(&self, kind: taxonomy::Kind) -> bool;
@@ -53,16 +75,45 @@ impl<'a, T: EntryLike> InstanceContext<'a, T> {
                     )))
                 })
             }
-            NumberVariable::Locator => match self.cite_props.speculative.locator?.1 {
-                LocatorPayload::Str(l) => Some(NumberVariableResult::from_regular(
-                    Numeric::from_str(l)
-                        .map(|n| MaybeTyped::Typed(Cow::Owned(n)))
-                        .unwrap_or_else(|_| MaybeTyped::String(l.to_owned())),
-                )),
-                LocatorPayload::Transparent => Some(NumberVariableResult::Transparent(
-                    self.cite_props.certain.initial_idx,
-                )),
-            },
+            NumberVariable::Locator => {
+                let SpecificLocator(locator, payload) =
+                    self.cite_props.speculative.locator?;
+                match payload {
+                    LocatorPayload::Str(l) => {
+                        // Timestamps are normalized and their ranges use an en
+                        // dash.
+                        let timestamp = (locator == Locator::Timestamp)
+                            .then(|| DurationRange::from_str(l).ok())
+                            .flatten();
+                        Some(NumberVariableResult::from_regular(match timestamp {
+                            Some(range) if range.start == range.end => {
+                                MaybeTyped::String(range.start.to_string())
+                            }
+                            Some(range) => MaybeTyped::String(format!(
+                                "{}–{}",
+                                range.start, range.end
+                            )),
+                            None if matches!(
+                                locator,
+                                Locator::Page | Locator::Folio | Locator::Volume
+                            ) =>
+                            {
+                                Numeric::from_str_with_roman(l)
+                                    .map(|n| MaybeTyped::Typed(Cow::Owned(n)))
+                                    .unwrap_or_else(|_| MaybeTyped::String(l.to_owned()))
+                            }
+                            None => Numeric::from_str(l)
+                                .map(|n| MaybeTyped::Typed(Cow::Owned(n)))
+                                .unwrap_or_else(|_| MaybeTyped::String(l.to_owned())),
+                        }))
+                    }
+                    LocatorPayload::Transparent => {
+                        Some(NumberVariableResult::Transparent(
+                            self.cite_props.certain.initial_idx,
+                        ))
+                    }
+                }
+            }
             _ => self
                 .entry
                 .resolve_number_variable(variable)
@@ -89,6 +140,96 @@ impl<'a, T: EntryLike> InstanceContext<'a, T> {
             _ => self.entry.resolve_standard_variable(form, variable),
         }
     }
+
+    /// Resolve a standard variable in the given language variants like
+    /// [`Self::resolve_standard_variable_variants`]. If the short form is
+    /// requested and the entry does not define its own, it is looked up in
+    /// the abbreviations.
+    pub(super) fn resolve_standard_variable_abbreviated(
+        &self,
+        form: LongShortForm,
+        variable: StandardVariable,
+        variants: &LanguageVariants,
+        abbreviations: Option<&Abbreviations>,
+    ) -> Option<Cow<'a, ChunkedString>> {
+        let res = self.resolve_standard_variable_variants(form, variable, variants);
+        let Some(abbreviations) = abbreviations.filter(|_| {
+            form == LongShortForm::Short
+                && matches!(
+                    variable,
+                    StandardVariable::ContainerTitle
+                        | StandardVariable::Publisher
+                        | StandardVariable::Authority
+                )
+        }) else {
+            return res;
+        };
+
+        // Entries fall back to the long form if they have no short form.
+        let long = self.resolve_standard_variable_variants(
+            LongShortForm::Long,
+            variable,
+            variants,
+        );
+        if long.is_none() || res != long {
+            return res;
+        }
+
+        match abbreviations.abbreviate(variable, &long?.to_str()) {
+            Some(abbr) => {
+                let mut short = ChunkedString::new();
+                short.push_str(&abbr, ChunkKind::Verbatim);
+                Some(Cow::Owned(short))
+            }
+            None => res,
+        }
+    }
+
+    /// Resolve a standard variable in the first available of the given
+    /// language variants. The other available variants follow with the
+    /// affixes and delimiter of the [`LanguageVariants`], e.g.
+    /// "Voina i mir [War and Peace]".
+    pub(super) fn resolve_standard_variable_variants(
+        &self,
+        form: LongShortForm,
+        variable: StandardVariable,
+        variants: &LanguageVariants,
+    ) -> Option<Cow<'a, ChunkedString>> {
+        if variants.fields.is_empty() || variable == StandardVariable::YearSuffix {
+            return self.resolve_standard_variable(form, variable);
+        }
+
+        let mut values: Vec<Cow<'a, ChunkedString>> = Vec::new();
+        for variant in &variants.fields {
+            if let Some(value) =
+                self.entry.resolve_standard_variable_variant(form, variable, variant)
+            {
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+        }
+
+        let mut values = values.into_iter();
+        let Some(first) = values.next() else {
+            // Fall back to the original if none of the variants exist.
+            return self.resolve_standard_variable(form, variable);
+        };
+
+        let mut rest = values.peekable();
+        if rest.peek().is_none() {
+            return Some(first);
+        }
+
+        let mut res = first.into_owned();
+        for (i, value) in rest.enumerate() {
+            let sep = if i == 0 { &variants.prefix } else { &variants.delimiter };
+            res.push_str(sep, ChunkKind::Normal);
+            res.0.extend(value.0.iter().cloned());
+        }
+        res.push_str(&variants.suffix, ChunkKind::Normal);
+        Some(Cow::Owned(res))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -185,7 +326,7 @@ impl EntryLike for Entry {
             NumberVariable::Section => None,
             NumberVariable::SupplementNumber => None,
             NumberVariable::Version => self
-                .bound_select(&select!(("e":Repository)), "e")
+                .bound_select(&select!(("e":(Repository | Software | Dataset))), "e")
                 .and_then(Entry::serial_number)
                 .and_then(|s| s.0.get("version"))
                 .map(|s| {
@@ -207,155 +348,21 @@ impl EntryLike for Entry {
         form: LongShortForm,
         variable: StandardVariable,
     ) -> Option<Cow<'_, ChunkedString>> {
-        let entry = self;
-        match variable {
-            StandardVariable::Abstract => entry
-                .map(|e| e.abstract_())
-                .map(|f| f.select(form))
-                .map(Cow::Borrowed),
-            StandardVariable::Annote => {
-                entry.map(|e| e.annote()).map(|f| f.select(form)).map(Cow::Borrowed)
-            }
-            StandardVariable::Archive => {
-                entry.map(|e| e.archive()).map(|f| f.select(form)).map(Cow::Borrowed)
-            }
-            StandardVariable::ArchiveCollection => None,
-            StandardVariable::ArchiveLocation => {
-                entry.archive_location().map(|f| f.select(form)).map(Cow::Borrowed)
-            }
-            StandardVariable::ArchivePlace => None,
-            StandardVariable::Authority => {
-                entry.organization().map(|f| f.select(form)).map(Cow::Borrowed)
-            }
-            StandardVariable::CallNumber => {
-                entry.call_number().map(|f| f.select(form)).map(Cow::Borrowed)
-            }
-            StandardVariable::CitationKey => {
-                Some(Cow::Owned(StringChunk::verbatim(&entry.key).into()))
-            }
-            StandardVariable::CitationLabel => {
-                Some(Cow::Owned(Alphanumerical::default().citation(entry).into()))
-            }
-            // Get third-order title first, then second-order title.
-            StandardVariable::CollectionTitle => entry
-                .get_collection()
-                .and_then(|e| e.title())
-                .map(|f| f.select(form))
-                .map(Cow::Borrowed),
-            StandardVariable::ContainerTitle => entry
-                .get_container()
-                .and_then(|e| e.title())
-                .map(|f| f.select(form))
-                .map(Cow::Borrowed),
-            StandardVariable::ContainerTitleShort => entry
-                .get_container()
-                .and_then(|e| e.title())
-                .map(|f| f.select(LongShortForm::Short))
-                .map(Cow::Borrowed),
-            StandardVariable::Dimensions => entry
-                .runtime()
-                .map(|r| Cow::Owned(StringChunk::normal(r.to_string()).into())),
-            StandardVariable::Division => None,
-            StandardVariable::DOI => {
-                entry.doi().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
-            }
-            StandardVariable::Event | StandardVariable::EventTitle => entry
-                .bound_select(&select!(* > ("p":(Exhibition | Conference | Misc))), "p")
-                .and_then(Entry::title)
-                .map(|f| f.select(form))
-                .map(Cow::Borrowed),
-            StandardVariable::EventPlace => entry
-                .bound_select(&select!(* > ("p":(Exhibition | Conference | Misc))), "p")
-                .and_then(Entry::location)
-                .map(|f| f.select(form))
-                .map(Cow::Borrowed),
-            StandardVariable::Genre => {
-                entry.map(|e| e.genre()).map(|f| f.select(form)).map(Cow::Borrowed)
-            }
-            StandardVariable::ISBN => {
-                entry.isbn().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
-            }
-            StandardVariable::ISSN => {
-                entry.issn().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
-            }
-            StandardVariable::Jurisdiction => None,
-            StandardVariable::Keyword => None,
-            StandardVariable::Language => entry
-                .map(|e| e.language())
-                .map(|l| Cow::Owned(StringChunk::normal(csl_language(l)).into())),
-            StandardVariable::License => None,
-            StandardVariable::Medium => None,
-            StandardVariable::Note => {
-                entry.note().map(|f| f.select(form)).map(Cow::Borrowed)
-            }
-            StandardVariable::OriginalPublisher => entry
-                .get_original()
-                .and_then(|e| e.publisher())
-                .map(|f| f.select(form))
-                .map(Cow::Borrowed),
-            StandardVariable::OriginalPublisherPlace => entry
-                .get_original()
-                .and_then(|e| e.publisher().and_then(|_| e.location()))
-                .map(|f| f.select(form))
-                .map(Cow::Borrowed),
-            StandardVariable::OriginalTitle => entry
-                .get_original()
-                .and_then(|e| e.title())
-                .map(|f| f.select(form))
-                .map(Cow::Borrowed),
-            StandardVariable::PartTitle => None,
-            StandardVariable::PMCID => {
-                entry.pmcid().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
-            }
-            StandardVariable::PMID => {
-                entry.pmid().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
-            }
-            StandardVariable::Publisher => entry
-                .map(|e| e.publisher())
-                .map(|f| f.select(form))
-                .map(Cow::Borrowed),
-            StandardVariable::PublisherPlace => entry
-                .map(|e| if e.publisher().is_some() { Some(e) } else { None })
-                .and_then(|e| e.location())
-                .map(|f| f.select(form))
-                .map(Cow::Borrowed),
-            StandardVariable::References => None,
-            StandardVariable::ReviewedGenre => None,
-            StandardVariable::ReviewedTitle => None,
-            StandardVariable::Scale => None,
-            StandardVariable::Source => entry
-                .bound_select(&select!(* > ("p":Repository)), "p")
-                .and_then(Entry::title)
-                .map(|f| f.select(form))
-                .map(Cow::Borrowed),
-            StandardVariable::Status => None,
-            StandardVariable::Title => entry
-                .title()
-                .map(|f| f.select(LongShortForm::Short))
-                .map(Cow::Borrowed),
-            StandardVariable::TitleShort => {
-                entry.title().map(|f| f.select(form)).map(Cow::Borrowed)
-            }
-            StandardVariable::URL => entry
-                .map(|e| e.url())
-                .map(|d| Cow::Owned(StringChunk::verbatim(d.to_string()).into())),
-            StandardVariable::VolumeTitle => {
-                let selector = select!(
-                    (Anthos > ("p":Anthology)) |
-                    (Entry  > ("p":*)) |
-                    (* > ("p":Reference)) |
-                    (Article > ("p":Proceedings))
-                );
-
-                self.bound_select(&selector, "p")
-                    .and_then(Entry::title)
-                    .map(|f| f.select(form))
-                    .map(Cow::Borrowed)
-            }
-            StandardVariable::YearSuffix => panic!("processor must resolve this"),
-        }
+        self.resolve_standard_variable_with(form, variable, |f, form| {
+            Some(f.select(form))
+        })
     }
 
+    fn resolve_standard_variable_variant(
+        &self,
+        form: LongShortForm,
+        variable: StandardVariable,
+        variant: &LanguageVariant,
+    ) -> Option<Cow<'_, ChunkedString>> {
+        self.resolve_standard_variable_with(form, variable, |f, form| {
+            f.select_variant(form, variant)
+        })
+    }
     fn resolve_date_variable(&self, variable: DateVariable) -> Option<Cow<'_, Date>> {
         Some(Cow::Borrowed(match variable {
             DateVariable::Accessed => self.url_any().and_then(|u| u.visit_date.as_ref()),
@@ -442,7 +449,9 @@ impl EntryLike for Entry {
             NameVariable::Illustrator => {
                 self.map(|e| Some(e.affiliated_with_role(PersonRole::Illustrator)))
             }
-            NameVariable::Interviewer => None,
+            NameVariable::Interviewer => {
+                self.map(|e| Some(e.affiliated_with_role(PersonRole::Interviewer)))
+            }
             NameVariable::Narrator => {
                 self.map(|e| Some(e.affiliated_with_role(PersonRole::Narrator)))
             }
@@ -460,7 +469,10 @@ impl EntryLike for Entry {
                 self.map(|e| Some(e.affiliated_with_role(PersonRole::Producer)))
             }
             NameVariable::Recipient => None,
-            NameVariable::ReviewedAuthor => None,
+            NameVariable::ReviewedAuthor => self
+                .get_reviewed()
+                .and_then(|e| e.authors())
+                .map(|a| a.iter().collect()),
             NameVariable::ScriptWriter => {
                 self.map(|e| Some(e.affiliated_with_role(PersonRole::Writer)))
             }
@@ -567,10 +579,9 @@ impl EntryLike for Entry {
                 select!(Chapter > (Book | Anthology | Proceedings)).matches(self)
             }
             Kind::Entry | Kind::EntryDictionary | Kind::EntryEncyclopedia => {
+                let is_dictionary = select!(* > Dictionary).matches(self);
                 if kind == Kind::EntryDictionary {
-                    // TODO: We do not differentiate between dictionaries and other
-                    // references.
-                    return false;
+                    return is_dictionary;
                 }
 
                 let is_encyclopedia = select!(* > Reference).matches(self);
@@ -578,10 +589,13 @@ impl EntryLike for Entry {
                     return is_encyclopedia;
                 }
 
-                self.entry_type() == &EntryType::Entry && !is_encyclopedia
+                self.entry_type() == &EntryType::Entry
+                    && !(is_dictionary || is_encyclopedia)
             }
             Kind::Event => self.entry_type() == &EntryType::Exhibition,
-            Kind::Hearing | Kind::Interview | Kind::Performance | Kind::Speech => false,
+            Kind::Hearing | Kind::Performance => false,
+            Kind::Interview => self.entry_type() == &EntryType::Interview,
+            Kind::Speech => self.entry_type() == &EntryType::Speech,
             Kind::Broadcast | Kind::MotionPicture | Kind::MusicalScore | Kind::Song => {
                 let is_music_score =
                     select!(Audio > (Book | Periodical | Reference | Misc | Blog | Web))
@@ -606,6 +620,11 @@ impl EntryLike for Entry {
                     && !(is_music_score || is_motion_picture || is_song)
             }
             Kind::Legislation | Kind::Bill => {
+                if self.entry_type() == &EntryType::Bill {
+                    return kind == Kind::Bill;
+                }
+
+                // Legislation without a publisher has not been enacted.
                 if self.entry_type() != &EntryType::Legislation {
                     return false;
                 }
@@ -618,7 +637,8 @@ impl EntryLike for Entry {
                 is_published
             }
             Kind::LegalCase => self.entry_type() == &EntryType::Case,
-            Kind::Regulation | Kind::Standard | Kind::Treaty => false,
+            Kind::Regulation | Kind::Treaty => false,
+            Kind::Standard => self.entry_type() == &EntryType::Standard,
             Kind::Patent => self.entry_type() == &EntryType::Patent,
             Kind::Webpage | Kind::PostWeblog | Kind::Post => {
                 let is_blogpost = select!(* > Blog).matches(self);
@@ -635,23 +655,34 @@ impl EntryLike for Entry {
                     .matches(self)
                     && !(is_blogpost || is_post)
             }
-            Kind::Dataset => false,
+            Kind::Dataset => self.entry_type() == &EntryType::Dataset,
             Kind::Figure | Kind::Graphic | Kind::Map => {
                 let is_figure = select!(Artwork > Article).matches(self);
                 if kind == Kind::Figure {
                     return is_figure;
                 }
 
+                let is_map = self.entry_type() == &EntryType::Map;
                 if kind == Kind::Map {
-                    return false;
+                    return is_map;
                 }
 
                 self.entry_type() == &EntryType::Artwork && !is_figure
             }
             Kind::Pamphlet => false,
             Kind::PersonalCommunication => false,
-            Kind::Review | Kind::ReviewBook => false,
-            Kind::Software => self.entry_type() == &EntryType::Repository,
+            Kind::Review | Kind::ReviewBook => {
+                if self.entry_type() != &EntryType::Review {
+                    return false;
+                }
+
+                let is_book_review =
+                    self.get_reviewed().is_some_and(|e| e.entry_type == EntryType::Book);
+                (kind == Kind::ReviewBook) == is_book_review
+            }
+            Kind::Software => {
+                matches!(self.entry_type(), EntryType::Software | EntryType::Repository)
+            }
             Kind::Document => self.entry_type() == &EntryType::Misc,
         }
     }
@@ -661,16 +692,222 @@ impl EntryLike for Entry {
     }
 }
 
+impl Entry {
+    /// Resolve a standard variable, using `pick` to choose the string from
+    /// the entry's fields.
+    fn resolve_standard_variable_with<'s>(
+        &'s self,
+        form: LongShortForm,
+        variable: StandardVariable,
+        pick: impl Fn(&'s FormatString, LongShortForm) -> Option<&'s ChunkedString>,
+    ) -> Option<Cow<'s, ChunkedString>> {
+        let entry = self;
+        match variable {
+            StandardVariable::Abstract => entry
+                .map(|e| e.abstract_())
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::Annote => entry
+                .map(|e| e.annote())
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::Archive => entry
+                .map(|e| e.archive())
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed)
+                .or_else(|| {
+                    let name = entry.url_any()?.archive_name()?;
+                    Some(Cow::Owned(StringChunk::normal(name).into()))
+                }),
+            StandardVariable::ArchiveCollection => None,
+            StandardVariable::ArchiveLocation => entry
+                .archive_location()
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed)
+                .or_else(|| {
+                    let url = entry.url_any()?.archive_url.as_ref()?;
+                    Some(Cow::Owned(StringChunk::verbatim(url.to_string()).into()))
+                }),
+            StandardVariable::ArchivePlace => None,
+            StandardVariable::Authority => {
+                entry.organization().and_then(|f| pick(f, form)).map(Cow::Borrowed)
+            }
+            StandardVariable::CallNumber => {
+                entry.call_number().and_then(|f| pick(f, form)).map(Cow::Borrowed)
+            }
+            StandardVariable::CitationKey => {
+                Some(Cow::Owned(StringChunk::verbatim(&entry.key).into()))
+            }
+            StandardVariable::CitationLabel => {
+                Some(Cow::Owned(Alphanumerical::default().citation(entry).into()))
+            }
+            // Get third-order title first, then second-order title.
+            StandardVariable::CollectionTitle => entry
+                .get_collection()
+                .and_then(|e| e.title())
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::ContainerTitle => entry
+                .get_container()
+                .and_then(|e| e.title())
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::ContainerTitleShort => entry
+                .get_container()
+                .and_then(|e| e.title())
+                .and_then(|f| pick(f, LongShortForm::Short))
+                .map(Cow::Borrowed),
+            StandardVariable::Dimensions => entry
+                .runtime()
+                .map(|r| Cow::Owned(StringChunk::normal(r.to_string()).into())),
+            StandardVariable::Division => None,
+            StandardVariable::DOI => {
+                entry.doi().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
+            }
+            StandardVariable::Event | StandardVariable::EventTitle => entry
+                .bound_select(&select!(* > ("p":(Exhibition | Conference | Misc))), "p")
+                .and_then(Entry::title)
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::EventPlace => entry
+                .bound_select(&select!(* > ("p":(Exhibition | Conference | Misc))), "p")
+                .and_then(Entry::location)
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::Genre => entry
+                .map(|e| e.genre())
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::ISBN => {
+                entry.isbn().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
+            }
+            StandardVariable::ISSN => {
+                entry.issn().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
+            }
+            StandardVariable::Jurisdiction => None,
+            StandardVariable::Keyword => None,
+            StandardVariable::Language => entry
+                .map(|e| e.language())
+                .map(|l| Cow::Owned(StringChunk::normal(csl_language(l)).into())),
+            StandardVariable::License => None,
+            StandardVariable::Medium => None,
+            StandardVariable::Note => {
+                entry.note().and_then(|f| pick(f, form)).map(Cow::Borrowed)
+            }
+            StandardVariable::OriginalPublisher => entry
+                .get_original()
+                .and_then(|e| e.publisher())
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::OriginalPublisherPlace => entry
+                .get_original()
+                .and_then(|e| e.publisher().and_then(|_| e.location()))
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::OriginalTitle => entry
+                .get_original()
+                .and_then(|e| e.title())
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::PartTitle => None,
+            StandardVariable::PMCID => {
+                entry.pmcid().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
+            }
+            StandardVariable::PMID => {
+                entry.pmid().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
+            }
+            StandardVariable::Publisher => entry
+                .map(|e| e.publisher())
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::PublisherPlace => entry
+                .map(|e| if e.publisher().is_some() { Some(e) } else { None })
+                .and_then(|e| e.location())
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::References => None,
+            StandardVariable::ReviewedGenre => entry
+                .get_reviewed()
+                .and_then(Entry::genre)
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::ReviewedTitle => entry
+                .get_reviewed()
+                .and_then(Entry::title)
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::Scale => None,
+            StandardVariable::Source => entry
+                .bound_select(&select!(* > ("p":Repository)), "p")
+                .and_then(Entry::title)
+                .and_then(|f| pick(f, form))
+                .map(Cow::Borrowed),
+            StandardVariable::Status => None,
+            StandardVariable::Title => entry
+                .title()
+                .and_then(|f| pick(f, LongShortForm::Short))
+                .map(Cow::Borrowed),
+            StandardVariable::TitleShort => {
+                entry.title().and_then(|f| pick(f, form)).map(Cow::Borrowed)
+            }
+            StandardVariable::URL => entry
+                .map(|e| e.url())
+                .map(|d| Cow::Owned(StringChunk::verbatim(d.to_string()).into())),
+            StandardVariable::VolumeTitle => {
+                let selector = select!(
+                    (Anthos > ("p":Anthology)) |
+                    (Entry  > ("p":*)) |
+                    (* > ("p":Reference)) |
+                    (Article > ("p":Proceedings))
+                );
+
+                self.bound_select(&selector, "p")
+                    .and_then(Entry::title)
+                    .and_then(|f| pick(f, form))
+                    .map(Cow::Borrowed)
+            }
+            StandardVariable::YearSuffix => panic!("processor must resolve this"),
+        }
+    }
+}
+
 #[cfg(feature = "csl-json")]
 impl EntryLike for citationberg::json::Item {
     fn resolve_standard_variable(
         &self,
-        _: LongShortForm,
+        form: LongShortForm,
         variable: StandardVariable,
     ) -> Option<Cow<'_, ChunkedString>> {
-        match self.0.get(&variable.to_string())? {
+        // Short forms are stored in `*-short` fields like
+        // `container-title-short`. Without one, the long form is used.
+        let short = match form {
+            LongShortForm::Short => self.0.get(&format!("{variable}-short")),
+            LongShortForm::Long => None,
+        };
+
+        // Archived copies are given as a URL in `archive_location`. Without an
+        // explicit `archive`, it is named after the service holding the copy.
+        let archive_url = || match self.0.get("archive_location")? {
+            csl_json::Value::String(s) => Some((s, Url::parse(s).ok()?)),
+            _ => None,
+        };
+        match variable {
+            StandardVariable::Archive if !self.0.contains_key("archive") => {
+                let (_, url) = archive_url()?;
+                let name = archive_name(&url)?;
+                return Some(Cow::Owned(StringChunk::normal(name).into()));
+            }
+            StandardVariable::ArchiveLocation => {
+                if let Some((s, _)) = archive_url() {
+                    return Some(Cow::Owned(StringChunk::verbatim(s).into()));
+                }
+            }
+            _ => {}
+        }
+
+        match short.or_else(|| self.0.get(&variable.to_string()))? {
             csl_json::Value::String(s) => {
-                Some(Cow::Owned(StringChunk::normal(s.clone()).into()))
+                Some(Cow::Owned(ChunkedString::from_rich_text(s)))
             }
             csl_json::Value::Number(n) => {
                 Some(Cow::Owned(StringChunk::normal(n.to_string()).into()))
@@ -685,9 +922,7 @@ impl EntryLike for citationberg::json::Item {
                 let Ok(d) = csl_json::FixedDateRange::try_from(d.clone()) else {
                     return None;
                 };
-                if d.end.is_some() {
-                    panic!("ranges are not supported")
-                }
+                // Date ranges are not supported, so we use the start.
                 let d = d.start;
                 Some(Cow::Owned(Date {
                     year: d.year as i32,
@@ -711,7 +946,7 @@ impl EntryLike for citationberg::json::Item {
                             prefix: None,
                             suffix: None,
                             given_name: None,
-                            alias: None,
+                            ..Default::default()
                         },
                         csl_json::NameValue::Item(csl_json::NameItem {
                             family,
@@ -738,15 +973,12 @@ impl EntryLike for citationberg::json::Item {
                             dropping_particle,
                             suffix,
                         }) => Person {
-                            name: if let Some(non_drop) = non_dropping_particle {
-                                format!("{} {}", non_drop, family)
-                            } else {
-                                family.clone()
-                            },
+                            name: family.clone(),
                             prefix: dropping_particle.clone(),
+                            non_dropping_particle: non_dropping_particle.clone(),
                             suffix: suffix.clone(),
                             given_name: given.clone(),
-                            alias: None,
+                            ..Default::default()
                         },
                     })
                 })
@@ -805,6 +1037,35 @@ impl EntryLike for citationberg::json::Item {
     }
 }
 
+/// Whether a variable is never provided by a Hayagriva [`Entry`].
+pub(super) fn is_unsupported_variable(variable: taxonomy::Variable) -> bool {
+    use taxonomy::Variable;
+    matches!(
+        variable,
+        Variable::Number(NumberVariable::Section | NumberVariable::SupplementNumber)
+            | Variable::Date(DateVariable::AvailableDate | DateVariable::Submitted)
+            | Variable::Name(
+                NameVariable::EditorialDirector
+                    | NameVariable::Guest
+                    | NameVariable::Host
+                    | NameVariable::Recipient
+            )
+            | Variable::Standard(
+                StandardVariable::ArchiveCollection
+                    | StandardVariable::ArchivePlace
+                    | StandardVariable::Division
+                    | StandardVariable::Jurisdiction
+                    | StandardVariable::Keyword
+                    | StandardVariable::License
+                    | StandardVariable::Medium
+                    | StandardVariable::PartTitle
+                    | StandardVariable::References
+                    | StandardVariable::Scale
+                    | StandardVariable::Status
+            )
+    )
+}
+
 pub(super) fn csl_language(lang_id: &LanguageIdentifier) -> String {
     let mut buf = String::with_capacity(if lang_id.region.is_some() { 5 } else { 2 });
     buf.push_str(lang_id.language.as_str());
@@ -816,7 +1077,7 @@ pub(super) fn csl_language(lang_id: &LanguageIdentifier) -> String {
 }
 
 // A function that takes a usize value and returns a String
-fn letter(val: u8) -> String {
+pub(super) fn letter(val: u8) -> String {
     let mut result = String::with_capacity(1);
     let mut current = val;
 
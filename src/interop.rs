@@ -53,7 +53,7 @@ impl From<&tex::Person> for Person {
             given_name: optional(&person.given_name),
             prefix: optional(&person.prefix),
             suffix: optional(&person.suffix),
-            alias: None,
+            ..Default::default()
         }
     }
 }
@@ -84,8 +84,15 @@ impl From<&[Spanned<Chunk>]> for ChunkedString {
         let mut res = Self::new();
         for chunk in chunks {
             match &chunk.v {
-                Chunk::Normal(s) => res.push_str(s, ChunkKind::Normal),
-                Chunk::Verbatim(s) => res.push_str(s, ChunkKind::Verbatim),
+                Chunk::Normal(s) => {
+                    push_commands(&mut res, s, ChunkKind::Normal, ChunkFormat::default())
+                }
+                Chunk::Verbatim(s) => push_commands(
+                    &mut res,
+                    s,
+                    ChunkKind::Verbatim,
+                    ChunkFormat::default(),
+                ),
                 Chunk::Math(s) => res.push_str(s, ChunkKind::Math),
             }
         }
@@ -93,9 +100,79 @@ impl From<&[Spanned<Chunk>]> for ChunkedString {
     }
 }
 
+/// LaTeX commands that format their argument.
+const FORMAT_COMMANDS: &[&str] =
+    &["emph", "textit", "textsl", "textbf", "textsc", "textsuperscript", "textsubscript"];
+
+/// Apply a formatting command to the formatting of its argument.
+fn apply_command(command: &str, format: &mut ChunkFormat) {
+    match command {
+        "emph" => format.italic = !format.italic,
+        "textit" | "textsl" => format.italic = true,
+        "textbf" => format.bold = true,
+        "textsc" => format.small_caps = true,
+        "textsuperscript" => format.superscript = true,
+        "textsubscript" => format.subscript = true,
+        _ => {}
+    }
+}
+
+/// Push text with the given kind and formatting. The `biblatex` crate keeps
+/// commands it does not know, so formatting commands like `\emph{...}` are
+/// turned into formatted chunks here.
+fn push_commands(res: &mut ChunkedString, s: &str, kind: ChunkKind, format: ChunkFormat) {
+    fn push(res: &mut ChunkedString, s: &str, kind: ChunkKind, format: ChunkFormat) {
+        if !s.is_empty() {
+            res.push_formatted(s, kind, format);
+        }
+    }
+
+    let mut rest = s;
+    while let Some(i) = rest.find('\\') {
+        let after = &rest[i + 1..];
+        let command = FORMAT_COMMANDS.iter().find_map(|name| {
+            let arg = after.strip_prefix(name)?.strip_prefix('{')?;
+            let end = matching_brace(arg)?;
+            Some((name, &arg[..end], &arg[end + 1..]))
+        });
+
+        let Some((name, arg, next)) = command else {
+            push(res, &rest[..i + 1], kind, format);
+            rest = after;
+            continue;
+        };
+
+        push(res, &rest[..i], kind, format);
+        let mut inner = format;
+        apply_command(name, &mut inner);
+        push_commands(res, arg, kind, inner);
+        rest = next;
+    }
+
+    push(res, rest, kind, format);
+}
+
+/// Find the brace that closes a group whose opening brace precedes `s`.
+fn matching_brace(s: &str) -> Option<usize> {
+    let mut depth = 1;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 impl From<&[Spanned<Chunk>]> for FormatString {
     fn from(chunks: &[Spanned<Chunk>]) -> Self {
-        Self { value: chunks.into(), short: None }
+        Self { value: chunks.into(), ..Default::default() }
     }
 }
 
@@ -156,6 +233,34 @@ fn map_res<T>(result: Result<T, RetrievalError>) -> Result<Option<T>, TypeError>
     }
 }
 
+/// The entry type for BibLaTeX entry types that are not part of the core
+/// data model but are defined by common styles like `biblatex-chicago`.
+fn unknown_kind(name: &str) -> EntryType {
+    match name {
+        "standard" => EntryType::Standard,
+        "review" => EntryType::Review,
+        "legislation" => EntryType::Legislation,
+        _ => EntryType::Misc,
+    }
+}
+
+/// The URL of an archived copy given as an `eprint` with an archive as its
+/// `eprinttype`. The `eprint` may be a full URL or the archive's identifier.
+fn archived_eprint(kind: &str, eprint: &str) -> Option<Url> {
+    let base = match kind {
+        "wayback" | "webarchive" | "archive.org" | "internet archive" => {
+            "https://web.archive.org/web/"
+        }
+        "perma" | "perma.cc" => "https://perma.cc/",
+        "archive.today" | "archive.ph" | "archive.is" => "https://archive.ph/",
+        _ => return None,
+    };
+
+    Url::parse(eprint)
+        .or_else(|_| Url::parse(&format!("{base}{eprint}")))
+        .ok()
+}
+
 impl TryFrom<&tex::Entry> for Entry {
     type Error = TypeError;
 
@@ -193,14 +298,31 @@ impl TryFrom<&tex::Entry> for Entry {
             { tex::EntryType::MvCollection, EntryType::Anthology, None, false },
             { tex::EntryType::Patent, EntryType::Patent, None, false },
             { tex::EntryType::Online, EntryType::Web, None, false },
-            { tex::EntryType::Software, EntryType::Misc, None, false },
-            { tex::EntryType::Dataset, EntryType::Repository, None, false },
+            { tex::EntryType::Software, EntryType::Software, None, false },
+            { tex::EntryType::Dataset, EntryType::Dataset, None, false },
             { tex::EntryType::Set, EntryType::Misc, None, false },
             { tex::EntryType::XData, EntryType::Misc, None, false },
-            { tex::EntryType::Unknown(_), EntryType::Misc, None, false },
+            { tex::EntryType::Unknown(ref name), unknown_kind(name), None, false },
         ]);
 
-        if let Ok(a) = entry.author().map(|a| a.iter().map(Into::into).collect()) {
+        // With biblatex's `useprefix` option, name prefixes are not dropped.
+        let use_prefix = entry.get("options").is_some_and(|options| {
+            options.format_verbatim().split(',').any(|option| {
+                matches!(
+                    option.split_whitespace().collect::<String>().as_str(),
+                    "useprefix" | "useprefix=true"
+                )
+            })
+        });
+        let person = |person: &tex::Person| {
+            let mut person = Person::from(person);
+            if use_prefix {
+                person.non_dropping_particle = person.prefix.take();
+            }
+            person
+        };
+
+        if let Ok(a) = entry.author().map(|a| a.iter().map(person).collect()) {
             item.set_authors(a);
         }
 
@@ -209,9 +331,9 @@ impl TryFrom<&tex::Entry> for Entry {
         for (editors, role) in entry.editors()? {
             let ptype = ed_role(role);
             match ptype {
-                None => eds.extend(editors.iter().map(Into::into)),
+                None => eds.extend(editors.iter().map(person)),
                 Some(role) => collaborators.push(PersonsWithRoles::new(
-                    editors.iter().map(Into::into).collect(),
+                    editors.iter().map(person).collect(),
                     role,
                 )),
             }
@@ -224,34 +346,33 @@ impl TryFrom<&tex::Entry> for Entry {
             item.set_affiliated(collaborators);
         }
 
-        if let Some(a) =
-            map_res(entry.holder())?.map(|a| a.iter().map(Into::into).collect())
+        if let Some(a) = map_res(entry.holder())?.map(|a| a.iter().map(person).collect())
         {
             item.add_affiliated_persons((a, PersonRole::Holder));
         }
 
         if let Some(parent) = book(&mut item, parent) {
             if let Some(a) =
-                map_res(entry.book_author())?.map(|a| a.iter().map(Into::into).collect())
+                map_res(entry.book_author())?.map(|a| a.iter().map(person).collect())
             {
                 parent.set_authors(a);
             }
         }
 
         if let Some(a) =
-            map_res(entry.annotator())?.map(|a| a.iter().map(Into::into).collect())
+            map_res(entry.annotator())?.map(|a| a.iter().map(person).collect())
         {
             item.add_affiliated_persons((a, PersonRole::Annotator));
         }
 
         if let Some(a) =
-            map_res(entry.commentator())?.map(|a| a.iter().map(Into::into).collect())
+            map_res(entry.commentator())?.map(|a| a.iter().map(person).collect())
         {
             item.add_affiliated_persons((a, PersonRole::Commentator));
         }
 
         if let Some(a) =
-            map_res(entry.translator())?.map(|a| a.iter().map(Into::into).collect())
+            map_res(entry.translator())?.map(|a| a.iter().map(person).collect())
         {
             item.add_affiliated_persons((a, PersonRole::Translator));
         }
@@ -259,19 +380,19 @@ impl TryFrom<&tex::Entry> for Entry {
         // TODO: entry.orig_language into item.language = Some()
 
         if let Some(a) =
-            map_res(entry.afterword())?.map(|a| a.iter().map(Into::into).collect())
+            map_res(entry.afterword())?.map(|a| a.iter().map(person).collect())
         {
             item.add_affiliated_persons((a, PersonRole::Afterword));
         }
 
         if let Some(a) =
-            map_res(entry.foreword())?.map(|a| a.iter().map(Into::into).collect())
+            map_res(entry.foreword())?.map(|a| a.iter().map(person).collect())
         {
             item.add_affiliated_persons((a, PersonRole::Foreword));
         }
 
         if let Some(a) =
-            map_res(entry.introduction())?.map(|a| a.iter().map(Into::into).collect())
+            map_res(entry.introduction())?.map(|a| a.iter().map(person).collect())
         {
             item.add_affiliated_persons((a, PersonRole::Introduction));
         }
@@ -405,12 +526,16 @@ impl TryFrom<&tex::Entry> for Entry {
             item.set_issn(issn.format_verbatim());
         }
 
+        let mut archive_url = None;
         if let Some(eprint) = map_res(entry.eprint())? {
-            if map_res(entry.eprint_type().map(|c| c.format_verbatim().to_lowercase()))?
-                .as_deref()
-                == Some("arxiv")
+            match map_res(
+                entry.eprint_type().map(|c| c.format_verbatim().to_lowercase()),
+            )?
+            .as_deref()
             {
-                item.set_arxiv(eprint);
+                Some("arxiv") => item.set_arxiv(eprint),
+                Some(kind) => archive_url = archived_eprint(kind, &eprint),
+                None => {}
             }
         }
 
@@ -433,7 +558,11 @@ impl TryFrom<&tex::Entry> for Entry {
                     PermissiveType::Chunks(_) => None,
                 })
                 .map(|d| d.into());
-            item.set_url(QualifiedUrl { value: url, visit_date: date });
+            let mut url = QualifiedUrl::new(url, date);
+            if let Some(archive_url) = archive_url {
+                url.set_archive(archive_url, None);
+            }
+            item.set_url(url);
         }
 
         if let Some(location) = map_res(entry.location())?.map(|d| d.into()) {
@@ -505,11 +634,14 @@ impl TryFrom<&tex::Entry> for Entry {
                             value: NumericValue::Set(items),
                             prefix: None,
                             suffix: None,
+                            notation: NumberNotation::Arabic,
                         })
                     }
                 }
                 PermissiveType::Chunks(chunks) => {
-                    MaybeTyped::infallible_from_str(&chunks.format_verbatim())
+                    let pages = chunks.format_verbatim();
+                    Numeric::from_str_with_roman(&pages)
+                        .map_or(MaybeTyped::String(pages), MaybeTyped::Typed)
                 }
             });
         }
@@ -582,5 +714,5 @@ fn comma_list(items: &[Vec<Spanned<Chunk>>]) -> FormatString {
         value.extend(chunked);
     }
 
-    FormatString { value, short: None }
+    FormatString { value, ..Default::default() }
 }
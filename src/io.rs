@@ -3,6 +3,7 @@
 #[cfg(feature = "biblatex")]
 use biblatex::{Bibliography, TypeError};
 
+use crate::types::IdentifierIssue;
 use crate::{Entry, Library};
 
 /// Parse a bibliography from a YAML string.
@@ -23,7 +24,36 @@ use crate::{Entry, Library};
 /// assert_eq!(bib.nth(0).unwrap().date().unwrap().year, 2014);
 /// ```
 pub fn from_yaml_str(s: &str) -> Result<Library, serde_yaml::Error> {
-    serde_yaml::from_str(s)
+    from_yaml_str_with(s, LoadOptions::default()).map(|(library, _)| library)
+}
+
+/// Parse a bibliography from a YAML string with the given options. Also
+/// returns the malformed identifiers found while normalizing them.
+pub fn from_yaml_str_with(
+    s: &str,
+    options: LoadOptions,
+) -> Result<(Library, Vec<IdentifierIssue>), serde_yaml::Error> {
+    serde_yaml::from_str(s).map(|library| options.apply(library))
+}
+
+/// Options for reading bibliographies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct LoadOptions {
+    /// Bring identifiers like DOIs and ISBNs into their canonical form.
+    /// Malformed identifiers are kept as they are and reported as issues.
+    pub normalize_identifiers: bool,
+}
+
+impl LoadOptions {
+    fn apply(self, mut library: Library) -> (Library, Vec<IdentifierIssue>) {
+        let issues = if self.normalize_identifiers {
+            library.normalize_identifiers()
+        } else {
+            vec![]
+        };
+        (library, issues)
+    }
 }
 
 /// Serialize a bibliography to a YAML string.
@@ -54,14 +84,29 @@ impl std::fmt::Display for BibLaTeXError {
 /// Parse a bibliography from a BibLaTeX source string.
 #[cfg(feature = "biblatex")]
 pub fn from_biblatex_str(biblatex: &str) -> Result<Library, Vec<BibLaTeXError>> {
+    from_biblatex_str_with(biblatex, LoadOptions::default()).map(|(library, _)| library)
+}
+
+/// Parse a bibliography from a BibLaTeX source string with the given options.
+/// Also returns the malformed identifiers found while normalizing them.
+#[cfg(feature = "biblatex")]
+pub fn from_biblatex_str_with(
+    biblatex: &str,
+    options: LoadOptions,
+) -> Result<(Library, Vec<IdentifierIssue>), Vec<BibLaTeXError>> {
     let bibliography =
         Bibliography::parse(biblatex).map_err(|e| vec![BibLaTeXError::Parse(e)])?;
 
     from_biblatex(&bibliography)
+        .map(|library| options.apply(library))
         .map_err(|e| e.into_iter().map(BibLaTeXError::Type).collect())
 }
 
 /// Parse a bibliography from a BibLaTeX [`Bibliography`].
+///
+/// Formatting commands like `\emph{...}`, `\textbf{...}`, or
+/// `\textsuperscript{...}` in the fields become
+/// [formatted chunks](crate::types::ChunkFormat).
 #[cfg(feature = "biblatex")]
 pub fn from_biblatex(bibliography: &Bibliography) -> Result<Library, Vec<TypeError>> {
     let res: Vec<Result<Entry, TypeError>> =
@@ -101,4 +146,64 @@ mod tests {
             assert_eq!(match_e, &entry);
         }
     }
+
+    #[test]
+    fn normalize_on_load() {
+        let yaml = r#"
+        a:
+            type: Book
+            serial-number:
+                doi: https://doi.org/10.1000/ABC
+                isbn: 0-306-40615-3
+        "#;
+        let options = LoadOptions { normalize_identifiers: true };
+        let (lib, issues) = from_yaml_str_with(yaml, options).unwrap();
+        let serials = lib.get("a").unwrap().serial_number().unwrap();
+        assert_eq!(serials.0["doi"], "10.1000/abc");
+        assert_eq!(serials.0["isbn"], "0-306-40615-3");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].value, "0-306-40615-3");
+
+        let (_, issues) = from_yaml_str_with(yaml, LoadOptions::default()).unwrap();
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "biblatex")]
+    fn biblatex_markup() {
+        let lib = from_biblatex_str(
+            r#"@article{coli,
+            title = {Growth of \emph{Escherichia \textbf{coli}} at 37\textsuperscript{o}},
+        }"#,
+        )
+        .unwrap();
+        let title = &lib.get("coli").unwrap().title().unwrap().value;
+        assert_eq!(title.to_string(), "Growth of Escherichia coli at 37o");
+        let formats: Vec<_> = title.0.iter().map(|c| c.format).collect();
+        assert!(formats[1].italic && !formats[1].bold);
+        assert!(formats[2].italic && formats[2].bold);
+        assert!(formats[4].superscript);
+    }
+
+    #[test]
+    #[cfg(feature = "biblatex")]
+    fn biblatex_archived_url() {
+        let lib = from_biblatex_str(
+            r#"@online{report,
+            title = {Report},
+            url = {https://example.com/report},
+            urldate = {2022-05-01},
+            eprint = {20210314095126/https://example.com/report},
+            eprinttype = {wayback},
+        }"#,
+        )
+        .unwrap();
+        let url = lib.get("report").unwrap().url().unwrap();
+        assert_eq!(url.visit_date.unwrap().to_string(), "2022-05-01");
+        assert_eq!(
+            url.archive_url.as_ref().unwrap().as_str(),
+            "https://web.archive.org/web/20210314095126/https://example.com/report"
+        );
+        assert_eq!(url.archive_date.unwrap().to_string(), "2021-03-14");
+    }
 }
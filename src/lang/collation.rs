@@ -0,0 +1,165 @@
+//! Locale-aware string comparison.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use citationberg::LocaleCode;
+use icu_collator::CollatorOptions;
+use icu_provider::DataLocale;
+
+/// Compares strings using the Unicode Collation Algorithm with the tailoring
+/// of a locale, e.g. sorting "Å" after "Z" in Swedish.
+pub(crate) struct Collator {
+    collator: icu_collator::Collator,
+    articles: &'static [&'static str],
+}
+
+impl std::fmt::Debug for Collator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Collator")
+            .field("articles", &self.articles)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Collator {
+    /// Create a collator for a locale. Unknown locales use the root collation.
+    pub fn new(locale: &LocaleCode) -> Self {
+        let collator = locale
+            .0
+            .parse::<DataLocale>()
+            .ok()
+            .and_then(|l| {
+                icu_collator::Collator::try_new(&l, CollatorOptions::new()).ok()
+            })
+            .unwrap_or_else(|| {
+                icu_collator::Collator::try_new(
+                    &DataLocale::default(),
+                    CollatorOptions::new(),
+                )
+                .expect("root collation is always available")
+            });
+
+        let language = locale.0.split(['-', '_']).next().unwrap_or_default();
+        Self {
+            collator,
+            articles: articles(&language.to_ascii_lowercase()),
+        }
+    }
+
+    /// Compare two strings.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        self.collator.compare(a, b)
+    }
+
+    /// Compare two optional strings. Missing strings go first.
+    pub fn compare_opt(&self, a: Option<&str>, b: Option<&str>) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) => self.compare(a, b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        }
+    }
+
+    /// Strip a leading article of the locale's language from a string.
+    pub fn strip_article<'s>(&self, s: &'s str) -> &'s str {
+        for article in self.articles {
+            let Some(start) = s.get(..article.len()) else { continue };
+            if !start.eq_ignore_ascii_case(article) {
+                continue;
+            }
+
+            let rest = &s[article.len()..];
+            // Elided articles like "l'" are directly followed by the word.
+            let rest = if article.ends_with('\'') {
+                rest
+            } else if rest.starts_with(' ') {
+                rest.trim_start()
+            } else {
+                continue;
+            };
+
+            if !rest.is_empty() {
+                return rest;
+            }
+        }
+
+        s
+    }
+}
+
+/// Collators for multiple locales. Each collator is created on first use.
+#[derive(Debug, Default)]
+pub(crate) struct Collators(RwLock<HashMap<LocaleCode, Arc<Collator>>>);
+
+impl Collators {
+    /// The collator for a locale.
+    pub fn get(&self, locale: &LocaleCode) -> Arc<Collator> {
+        if let Some(collator) = self.0.read().unwrap().get(locale) {
+            return collator.clone();
+        }
+
+        self.0
+            .write()
+            .unwrap()
+            .entry(locale.clone())
+            .or_insert_with(|| Arc::new(Collator::new(locale)))
+            .clone()
+    }
+}
+
+/// The articles that are ignored when sorting in a language.
+fn articles(language: &str) -> &'static [&'static str] {
+    match language {
+        "en" => &["the", "an", "a"],
+        "de" => &["der", "die", "das", "eine", "ein"],
+        "fr" => &["les", "le", "la", "l'", "une", "un"],
+        "es" => &["los", "las", "el", "la", "una", "un"],
+        "it" => &["gli", "il", "lo", "la", "le", "i", "l'", "uno", "una", "un"],
+        "pt" => &["os", "as", "o", "a", "uma", "um"],
+        "nl" => &["het", "de", "een"],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(locale: &str, words: &[&'static str]) -> Vec<&'static str> {
+        let collator = Collator::new(&LocaleCode(locale.into()));
+        let mut words = words.to_vec();
+        words.sort_by(|a, b| collator.compare(a, b));
+        words
+    }
+
+    #[test]
+    fn tailoring() {
+        assert_eq!(sorted("en-US", &["Zhang", "Ångström"]), ["Ångström", "Zhang"]);
+        assert_eq!(sorted("sv-SE", &["Ångström", "Zhang"]), ["Zhang", "Ångström"]);
+        assert_eq!(
+            sorted("de-DE", &["Muz", "Müller", "Mueller"]),
+            ["Mueller", "Müller", "Muz"]
+        );
+        assert_eq!(
+            sorted("cs-CZ", &["chata", "hrad", "ivan"]),
+            ["hrad", "chata", "ivan"]
+        );
+        assert_eq!(sorted("es-ES", &["ñu", "nube", "oso"]), ["nube", "ñu", "oso"]);
+        assert_eq!(
+            sorted("en-US", &["banana", "Apple", "apple"]),
+            ["apple", "Apple", "banana"]
+        );
+    }
+
+    #[test]
+    fn articles() {
+        let en = Collator::new(&LocaleCode::en_us());
+        assert_eq!(en.strip_article("The Raven"), "Raven");
+        assert_eq!(en.strip_article("Theory"), "Theory");
+        assert_eq!(en.strip_article("A"), "A");
+
+        let fr = Collator::new(&LocaleCode("fr-FR".into()));
+        assert_eq!(fr.strip_article("L'Étranger"), "Étranger");
+    }
+}
@@ -1,5 +1,6 @@
 //! Language-dependant string transformations.
 
+pub(crate) mod collation;
 pub(crate) mod en;
 pub(crate) mod name;
 
@@ -633,6 +634,15 @@ impl CaseFolder {
         self.case = case;
     }
 
+    /// Take the transformed string so far, but keep the state so that the
+    /// next pushed string continues the text, e.g. mid-sentence.
+    pub fn take_continued(&mut self) -> String {
+        self.process_word();
+        self.last_word = None;
+        self.last_reconfig = 0;
+        mem::take(&mut self.buf)
+    }
+
     /// Yield the transformed string.
     pub fn finish(mut self) -> String {
         self.process_word();
@@ -56,11 +56,7 @@ for entry in bib.iter() {
     driver.citation(CitationRequest::from_items(items, &style, &locales));
 }
 
-let result = driver.finish(BibliographyRequest {
-    style: &style,
-    locale: None,
-    locale_files: &locales,
-});
+let result = driver.finish(BibliographyRequest::new(&style, None, &locales));
 
 for cite in result.citations {
     println!("{}", cite.citation.to_string())
@@ -70,6 +66,8 @@ for cite in result.citations {
 To format entries, you need to wrap them in a [`CitationRequest`]. Each of these
 can reference multiple entries in their respective [`CitationItem`]s.
 Use these with a [`BibliographyDriver`] to obtain formatted citations and bibliographies.
+Entries that should appear in the bibliography without being cited can be added
+with [`BibliographyDriver::nocite`] and [`BibliographyDriver::nocite_all`].
 
 If the default features are enabled, Hayagriva supports BibTeX and BibLaTeX
 bibliographies. You can use [`io::from_biblatex_str`] to parse such
@@ -146,6 +144,7 @@ mod interop;
 mod csl;
 pub mod io;
 pub mod lang;
+mod reconcile;
 pub mod types;
 mod util;
 
@@ -153,13 +152,21 @@ use std::collections::BTreeMap;
 
 #[cfg(feature = "archive")]
 pub use crate::csl::archive;
+#[cfg(feature = "archive")]
+pub use crate::csl::resolve_archived_style;
 pub use citationberg;
 pub use csl::{
-    standalone_citation, BibliographyDriver, BibliographyRequest, Brackets,
-    BufWriteFormat, CitationItem, CitationRequest, CitePurpose, Elem, ElemChild,
-    ElemChildren, ElemMeta, Formatted, Formatting, LocatorPayload, Rendered,
-    RenderedBibliography, RenderedCitation, SpecificLocator,
+    apply_dependent, lint_style, lint_style_with_locales, math_to_mathml,
+    math_to_unicode, parse_locator, resolve_style, standalone_citation, Abbreviations,
+    BibliographyDriver, BibliographyRequest, Brackets, BufWriteFormat,
+    BuiltinMathRenderer, CitationItem, CitationRequest, CitePurpose, CompiledStyle, Elem,
+    ElemChild, ElemChildren, ElemMeta, Formatted, Formatting, InText, LanguageVariants,
+    LintKind, LintSeverity, LocaleChain, LocaleResolver, LocatorParser, LocatorPayload,
+    MathRenderer, ParsedLocator, RawMathRenderer, RegistryError, RenderTrace, Rendered,
+    RenderedBibliography, RenderedCitation, SpecificLocator, StyleEntry, StyleLint,
+    StyleOrigin, StyleRegistry, SuppressionReason, TraceEvent, TraceKind,
 };
+pub use reconcile::NameCluster;
 pub use selectors::{Selector, SelectorError};
 
 use indexmap::IndexMap;
@@ -168,8 +175,8 @@ use serde::{de::Visitor, Deserialize, Serialize};
 use types::*;
 use unic_langid::LanguageIdentifier;
 use util::{
-    deserialize_one_or_many_opt, serialize_one_or_many, serialize_one_or_many_opt,
-    OneOrMany,
+    deserialize_one_or_many_opt, deserialize_roman_opt, serialize_one_or_many,
+    serialize_one_or_many_opt, OneOrMany,
 };
 
 /// A collection of bibliographic entries.
@@ -221,6 +228,18 @@ impl Library {
     pub fn nth(&self, n: usize) -> Option<&Entry> {
         self.0.get_index(n).map(|(_, v)| v)
     }
+
+    /// Check the identifiers like DOIs and ISBNs of all entries.
+    pub fn validate_identifiers(&self) -> Vec<IdentifierIssue> {
+        self.iter().flat_map(Entry::validate_identifiers).collect()
+    }
+
+    /// Bring the identifiers like DOIs and ISBNs of all entries into their
+    /// canonical form. Malformed identifiers are left unchanged and returned
+    /// as issues.
+    pub fn normalize_identifiers(&mut self) -> Vec<IdentifierIssue> {
+        self.0.values_mut().flat_map(Entry::normalize_identifiers).collect()
+    }
 }
 
 impl<'a> IntoIterator for &'a Library {
@@ -516,12 +535,14 @@ entry! {
     "issue" => issue: MaybeTyped<Numeric>,
     /// For an item whose parent has multiple volumes/parts/seasons ... of which
     /// this item is one.
+    #[serde(deserialize_with = "deserialize_roman_opt")]
     "volume" => volume: MaybeTyped<Numeric>,
     /// Total number of volumes/parts/seasons ... this item consists of.
     "volume-total" => volume_total: Numeric,
     /// Published version of an item.
     "edition" => edition: MaybeTyped<Numeric>,
     /// The range of pages within the parent this item occupies
+    #[serde(deserialize_with = "deserialize_roman_opt")]
     "page-range" => page_range: MaybeTyped<Numeric>,
     /// The total number of pages the item has.
     "page-total" => page_total: Numeric,
@@ -560,6 +581,10 @@ entry! {
     "genre" => genre: FormatString,
 }
 
+/// The parents of a review that contain it rather than being reviewed.
+const REVIEW_CONTAINERS: [EntryType; 4] =
+    [EntryType::Periodical, EntryType::Newspaper, EntryType::Blog, EntryType::Web];
+
 impl Entry {
     /// Get and parse the `affiliated` field and only return persons of a given
     /// [role](PersonRole).
@@ -731,6 +756,63 @@ impl Entry {
         self.set_keyed_serial_number("arxiv", arxiv);
     }
 
+    /// Check the identifiers like DOIs and ISBNs of this entry and its
+    /// parents.
+    pub fn validate_identifiers(&self) -> Vec<IdentifierIssue> {
+        let mut issues = vec![];
+        self.check_identifiers(&self.key, &mut issues);
+        issues
+    }
+
+    /// Bring the identifiers like DOIs and ISBNs of this entry and its parents
+    /// into their canonical form. Malformed identifiers are left unchanged and
+    /// returned as issues.
+    pub fn normalize_identifiers(&mut self) -> Vec<IdentifierIssue> {
+        let mut issues = vec![];
+        let key = self.key.clone();
+        self.normalize_identifiers_impl(&key, &mut issues);
+        issues
+    }
+
+    fn check_identifiers(&self, key: &str, issues: &mut Vec<IdentifierIssue>) {
+        for (kind, value) in self.serial_number.iter().flat_map(|s| s.0.iter()) {
+            let Some(kind) = IdentifierKind::from_key(kind) else { continue };
+            if let Err(error) = kind.normalize(value) {
+                issues.push(IdentifierIssue {
+                    key: key.to_string(),
+                    value: value.clone(),
+                    error,
+                });
+            }
+        }
+
+        for parent in &self.parents {
+            parent.check_identifiers(key, issues);
+        }
+    }
+
+    fn normalize_identifiers_impl(
+        &mut self,
+        key: &str,
+        issues: &mut Vec<IdentifierIssue>,
+    ) {
+        for (kind, value) in self.serial_number.iter_mut().flat_map(|s| s.0.iter_mut()) {
+            let Some(kind) = IdentifierKind::from_key(kind) else { continue };
+            match kind.normalize(value) {
+                Ok(normalized) => *value = normalized,
+                Err(error) => issues.push(IdentifierIssue {
+                    key: key.to_string(),
+                    value: value.clone(),
+                    error,
+                }),
+            }
+        }
+
+        for parent in &mut self.parents {
+            parent.normalize_identifiers_impl(key, issues);
+        }
+    }
+
     /// Get the container of an entry like CSL defines it.
     pub(crate) fn get_container(&self) -> Option<&Self> {
         let retrieve_container = |possible: &[EntryType]| {
@@ -793,6 +875,8 @@ impl Entry {
             EntryType::Thread => {
                 retrieve_container(&[EntryType::Thread, EntryType::Web, EntryType::Blog])
             }
+            EntryType::Entry => retrieve_container(&[EntryType::Dictionary]),
+            EntryType::Review => retrieve_container(&REVIEW_CONTAINERS),
             _ => None,
         }
     }
@@ -849,6 +933,17 @@ impl Entry {
     pub(crate) fn get_original(&self) -> Option<&Self> {
         self.dfs_parent(EntryType::Original)
     }
+
+    /// Get the work that a review assesses.
+    pub(crate) fn get_reviewed(&self) -> Option<&Self> {
+        if self.entry_type != EntryType::Review {
+            return None;
+        }
+
+        self.parents
+            .iter()
+            .find(|p| !REVIEW_CONTAINERS.contains(&p.entry_type))
+    }
 }
 
 #[cfg(feature = "biblatex")]
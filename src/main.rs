@@ -5,19 +5,18 @@ use std::path::Path;
 use std::process::exit;
 use std::str::FromStr;
 
-use citationberg::taxonomy::Locator;
 use citationberg::{
     IndependentStyle, Locale, LocaleCode, LocaleFile, LongShortForm, Style,
 };
-use clap::{crate_version, Arg, ArgAction, Command};
+use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use strum::VariantNames;
 
-use hayagriva::archive::{locales, ArchivedStyle};
+use hayagriva::types::PersonIdentifier;
+use hayagriva::{io, BibliographyDriver, CitationItem, CitationRequest, LocatorParser};
 use hayagriva::{
-    io, BibliographyDriver, CitationItem, CitationRequest, LocatorPayload,
-    SpecificLocator,
+    lint_style_with_locales, BibliographyRequest, LintSeverity, LocaleResolver, Selector,
+    StyleOrigin, StyleRegistry,
 };
-use hayagriva::{BibliographyRequest, Selector};
 
 #[derive(Debug, Copy, Clone, PartialEq, VariantNames)]
 #[strum(serialize_all = "kebab_case")]
@@ -55,7 +54,9 @@ fn main() {
                     .help("Sets the bibliography file to use")
                     .required(true)
                     .index(1)
-            ).arg(
+            )
+            .subcommand_negates_reqs(true)
+            .arg(
                 Arg::new("format")
                     .long("format")
                     .help("What input file format to expect")
@@ -100,6 +101,20 @@ fn main() {
                     .action(ArgAction::SetTrue)
                     .global(true)
             )
+            .arg(
+                Arg::new("normalize-ids")
+                    .long("normalize-ids")
+                    .help("Bring DOIs, ISBNs, ISSNs, and other identifiers into their canonical form and report malformed ones")
+                    .action(ArgAction::SetTrue)
+                    .global(true)
+            )
+            .arg(
+                Arg::new("style-dir")
+                    .long("style-dir")
+                    .help("Add a directory of CSL styles and locales, which take precedence over the built-in ones (can be repeated)")
+                    .action(ArgAction::Append)
+                    .global(true)
+            )
             .subcommand(
                 Command::new("cite")
                     .about("Format citations for all filtered entries")
@@ -107,7 +122,7 @@ fn main() {
                         Arg::new("style")
                             .long("style")
                             .short('s')
-                            .help("Set the citation style")
+                            .help("Set the citation style by name, journal title, or ISSN")
                             .ignore_case(true)
                             .num_args(1)
                             .required_unless_present("csl")
@@ -143,6 +158,12 @@ fn main() {
                             .action(ArgAction::SetTrue)
                             .help("Combine all keys into one citation (ignored for Chicago Notes)")
                     )
+                    .arg(
+                        Arg::new("trace")
+                            .long("trace")
+                            .action(ArgAction::SetTrue)
+                            .help("Print how the style rendered each item to stderr")
+                    )
             )
             .subcommand(
                 Command::new("reference")
@@ -151,7 +172,7 @@ fn main() {
                         Arg::new("style")
                             .long("style")
                             .short('s')
-                            .help("Set the referencing style")
+                            .help("Set the referencing style by name, journal title, or ISSN")
                             .ignore_case(true)
                             .num_args(1)
                             .required_unless_present("csl")
@@ -174,14 +195,99 @@ fn main() {
                             .help("Set a space-separated list of CSL locales")
                             .num_args(1)
                     )
+                    .arg(
+                        Arg::new("trace")
+                            .long("trace")
+                            .action(ArgAction::SetTrue)
+                            .help("Print how the style rendered each item to stderr")
+                    )
+            )
+            .subcommand(
+                Command::new("names")
+                    .about("List names that refer to the same person in different forms")
+                    .arg(
+                        Arg::new("all")
+                            .long("all")
+                            .action(ArgAction::SetTrue)
+                            .help("Also list names that only appear in one form")
+                    )
+                    .arg(
+                        Arg::new("fix")
+                            .long("fix")
+                            .action(ArgAction::SetTrue)
+                            .help("Print the bibliography with all names in their canonical form")
+                    )
             )
             .subcommand(
                 Command::new("styles")
                     .about("List all available citation styles")
+                    .arg(
+                        Arg::new("search")
+                            .help("Only list styles whose name, title, ID, or ISSN contains this text")
+                            .index(1)
+                    )
+            )
+            .subcommand(
+                Command::new("check-style")
+                    .about("Check a citation style for problems")
+                    .arg(
+                        Arg::new("style")
+                            .long("style")
+                            .short('s')
+                            .help("Set the citation style by name, journal title, or ISSN")
+                            .ignore_case(true)
+                            .num_args(1)
+                            .required_unless_present("csl")
+                    )
+                    .arg(
+                        Arg::new("csl")
+                            .long("csl")
+                            .help("Set a CSL file to use the style therein")
+                            .num_args(1)
+                    )
+                    .arg(
+                        Arg::new("locale")
+                            .long("locale")
+                            .help("Set a comma-separated list of locales to check the terms for (e.g. `en-US,de-DE`)")
+                            .num_args(1)
+                    )
+                    .arg(
+                        Arg::new("locales")
+                            .long("locales")
+                            .help("Set a comma-separated list of CSL locales")
+                            .num_args(1)
+                    )
+                    .arg(
+                        Arg::new("json")
+                            .long("json")
+                            .action(ArgAction::SetTrue)
+                            .help("Print the problems as JSON")
+                    )
             )
             .get_matches();
 
-    let input = Path::new(matches.get_one::<String>("INPUT").unwrap());
+    let mut registry = StyleRegistry::new();
+    for dir in matches.get_many::<String>("style-dir").into_iter().flatten() {
+        if let Err(err) = registry.add_dir(dir) {
+            eprintln!("{err}");
+            exit(9);
+        }
+    }
+
+    match matches.subcommand() {
+        Some(("check-style", sub_matches)) => check_style(&registry, sub_matches),
+        Some(("styles", sub_matches)) => {
+            print_styles(&registry, sub_matches.get_one::<String>("search"));
+            exit(0);
+        }
+        _ => {}
+    }
+
+    let Some(input) = matches.get_one::<String>("INPUT") else {
+        eprintln!("no bibliography file given");
+        exit(2);
+    };
+    let input = Path::new(input);
 
     let format = matches.get_one("format").cloned().unwrap_or_else(|| {
         #[allow(unused_mut)]
@@ -223,11 +329,21 @@ fn main() {
             }
         };
 
-        match format {
-            Format::Yaml => io::from_yaml_str(&input).unwrap(),
+        let mut options = io::LoadOptions::default();
+        options.normalize_identifiers = matches.get_flag("normalize-ids");
+        let (bibliography, issues) = match format {
+            Format::Yaml => io::from_yaml_str_with(&input, options).unwrap(),
             #[cfg(feature = "biblatex")]
-            Format::Biblatex | Format::Bibtex => io::from_biblatex_str(&input).unwrap(),
+            Format::Biblatex | Format::Bibtex => {
+                io::from_biblatex_str_with(&input, options).unwrap()
+            }
+        };
+
+        for issue in issues {
+            eprintln!("{issue}");
         }
+
+        bibliography
     };
 
     let bib_len = bibliography.len();
@@ -303,6 +419,35 @@ fn main() {
     }
 
     match matches.subcommand() {
+        Some(("names", sub_matches)) if sub_matches.get_flag("fix") => {
+            let mut bibliography = bibliography;
+            let changed = bibliography.reconcile_names();
+            eprintln!("Changed {changed} names");
+            println!("{}", io::to_yaml_str(&bibliography).unwrap());
+        }
+        Some(("names", sub_matches)) => {
+            let all = sub_matches.get_flag("all");
+            for cluster in bibliography.name_clusters() {
+                if !all && !cluster.needs_reconciliation() {
+                    continue;
+                }
+
+                println!("- {}", cluster.canonical.name_first(false, false));
+                for (kind, label) in [
+                    (PersonIdentifier::Orcid, "ORCID"),
+                    (PersonIdentifier::Isni, "ISNI"),
+                    (PersonIdentifier::Wikidata, "Wikidata"),
+                ] {
+                    if let Some(id) = cluster.canonical.identifier(kind) {
+                        println!("  {label}: {id}");
+                    }
+                }
+                for variant in &cluster.variants {
+                    println!("  Variant: {}", variant.name_first(false, false));
+                }
+                println!("  Entries: {}", cluster.entries.join(", "));
+            }
+        }
         Some(("reference", sub_matches)) => {
             let style: Option<&String> = sub_matches.get_one("style");
             let csl: Option<&String> = sub_matches.get_one("csl");
@@ -311,30 +456,27 @@ fn main() {
             let locale_str: Option<&String> = sub_matches.get_one("locale");
 
             let (style, locales, locale) =
-                retrieve_assets(style, csl, locale_path, locale_str);
+                retrieve_assets(&registry, style, csl, locale_path, locale_str);
 
             if style.bibliography.is_none() {
                 eprintln!("style has no bibliography");
                 exit(4);
             }
 
+            let trace = sub_matches.get_flag("trace");
             let mut driver = BibliographyDriver::new();
-            for entry in &bibliography {
-                driver.citation(CitationRequest::new(
-                    vec![CitationItem::with_entry(entry)],
-                    &style,
-                    locale.clone(),
-                    &locales,
-                    None,
-                ))
-            }
+            driver.nocite_all(&bibliography);
 
             for row in driver
-                .finish(BibliographyRequest::new(&style, locale, &locales))
+                .finish(BibliographyRequest::new(&style, locale, &locales).trace(trace))
                 .bibliography
                 .map(|b| b.items)
                 .unwrap_or_default()
             {
+                if let Some(trace) = row.trace() {
+                    eprint!("{trace}");
+                }
+
                 let alternate = matches.get_flag("no-fmt");
 
                 if let Some(prefix) = row.first_field {
@@ -366,15 +508,15 @@ fn main() {
                 .collect();
 
             let (style, locales, locale) =
-                retrieve_assets(style, csl, locale_path, locale_str);
+                retrieve_assets(&registry, style, csl, locale_path, locale_str);
 
+            let parser = LocatorParser::new(&style, locale.clone(), &locales);
             let assign_locator = |(i, e)| {
                 let mut item = CitationItem::with_entry(e);
                 if let Some(&locator) = locators.get(i) {
-                    item.locator = Some(SpecificLocator(
-                        Locator::Custom,
-                        LocatorPayload::Str(locator),
-                    ));
+                    let parsed = parser.parse(locator);
+                    item.locator = Some(parsed.locator);
+                    item.suffix = parsed.suffix.map(Into::into);
                 }
                 item
             };
@@ -400,12 +542,15 @@ fn main() {
                 }
             }
 
-            for row in driver
-                .finish(BibliographyRequest::new(&style, locale, &locales))
-                .bibliography
-                .map(|b| b.items)
-                .unwrap_or_default()
-            {
+            let rendered = driver.finish(
+                BibliographyRequest::new(&style, locale, &locales)
+                    .trace(sub_matches.get_flag("trace")),
+            );
+            for trace in rendered.citations.iter().flat_map(|c| c.traces()) {
+                eprint!("{trace}");
+            }
+
+            for row in rendered.bibliography.map(|b| b.items).unwrap_or_default() {
                 let alternate = matches.get_flag("no-fmt");
 
                 if let Some(prefix) = row.first_field {
@@ -423,25 +568,6 @@ fn main() {
                 }
             }
         }
-        Some(("styles", _)) => {
-            for key in ArchivedStyle::all() {
-                let style = key.get();
-                println!("- {}", &key.names()[0]);
-                println!("  Full name: {}", &style.info().title.value);
-                println!("  Authors:");
-                for author in style.info().authors.iter() {
-                    print!("  - {}", author.name);
-                    if let Some(email) = &author.email {
-                        println!(" <{}>", email);
-                    } else {
-                        println!();
-                    }
-                }
-                if let Some(desc) = &style.info().summary {
-                    println!("  Summary: {}", &desc.value);
-                }
-            }
-        }
         _ => {
             let bib = io::to_yaml_str(&bibliography).unwrap();
             println!("{}", bib);
@@ -449,7 +575,78 @@ fn main() {
     }
 }
 
+/// Print the available styles, optionally only those matching a query.
+fn print_styles(registry: &StyleRegistry, query: Option<&String>) {
+    let entries: Vec<_> = match query {
+        Some(query) => registry.search(query),
+        None => registry.styles().collect(),
+    };
+    let overrides = registry.overrides();
+
+    for entry in entries {
+        println!("- {}", entry.names().first().map_or(entry.id(), String::as_str));
+        println!("  Full name: {}", entry.title());
+        println!("  ID: {}", entry.id());
+        if let Some(parent) = entry.parent() {
+            println!("  Parent: {parent}");
+        }
+        match entry.origin() {
+            StyleOrigin::Archive => println!("  Source: archive"),
+            StyleOrigin::File(path) => println!("  Source: {}", path.display()),
+            StyleOrigin::Memory => println!("  Source: memory"),
+        }
+        for (shadowed, _) in overrides.iter().filter(|(_, by)| std::ptr::eq(*by, entry)) {
+            println!("  Overrides: {}", shadowed.id());
+        }
+        let info = entry.info();
+        if !info.authors.is_empty() {
+            println!("  Authors:");
+        }
+        for author in info.authors.iter() {
+            print!("  - {}", author.name);
+            if let Some(email) = &author.email {
+                println!(" <{}>", email);
+            } else {
+                println!();
+            }
+        }
+        if let Some(desc) = &info.summary {
+            println!("  Summary: {}", &desc.value);
+        }
+    }
+}
+
+/// Check a style and print the problems. Exits with status 1 if there are
+/// errors.
+fn check_style(registry: &StyleRegistry, matches: &ArgMatches) -> ! {
+    let style: Option<&String> = matches.get_one("style");
+    let csl: Option<&String> = matches.get_one("csl");
+    let locale_path = matches.get_one::<String>("locales").map(|s| s.split(','));
+    let (style, locales, _) = retrieve_assets(registry, style, csl, locale_path, None);
+    let codes: Vec<_> = matches
+        .get_one::<String>("locale")
+        .into_iter()
+        .flat_map(|s| s.split(','))
+        .map(|l| LocaleCode(l.into()))
+        .collect();
+
+    let lints = lint_style_with_locales(&style, &locales, &codes);
+    if matches.get_flag("json") {
+        println!("{}", serde_json::to_string_pretty(&lints).unwrap());
+    } else {
+        for lint in lints.iter() {
+            println!("{lint}");
+        }
+    }
+
+    if lints.iter().any(|l| l.severity == LintSeverity::Error) {
+        exit(1);
+    }
+    exit(0);
+}
+
 fn retrieve_assets<'a>(
+    registry: &StyleRegistry,
     style: Option<&String>,
     csl: Option<&String>,
     locale_paths: Option<impl Iterator<Item = &'a str>>,
@@ -460,30 +657,45 @@ fn retrieve_assets<'a>(
     let style = match (style, csl) {
         (_, Some(csl)) => {
             let file_str = fs::read_to_string(csl).expect("could not read CSL file");
-            IndependentStyle::from_xml(&file_str).expect("CSL file malformed")
-        }
-        (Some(style), _) => {
-            let Style::Independent(indep) =
-                ArchivedStyle::by_name(style.as_str()).expect("no style found").get()
-            else {
-                panic!("dependent style in archive")
+            let style = Style::from_xml(&file_str).expect("CSL file malformed");
+            let parent = match &style {
+                Style::Dependent(dependent) => Some(dependent.parent_link.href.clone()),
+                Style::Independent(_) => None,
             };
-            indep
+            match registry.resolve_style(style) {
+                Some(style) => style,
+                None => {
+                    eprintln!(
+                        "parent style {} of the dependent style is not available",
+                        parent.unwrap_or_default()
+                    );
+                    exit(8);
+                }
+            }
         }
+        (Some(style), _) => registry.independent(style).expect("no style found"),
         (None, None) => panic!("must specify style or CSL file"),
     };
 
-    let locales: Vec<Locale> = match locale_paths {
-        Some(locale_paths) => locale_paths
-            .into_iter()
-            .map(|locale_path| {
-                let file_str =
-                    fs::read_to_string(locale_path).expect("could not read locale file");
-                LocaleFile::from_xml(&file_str).expect("locale file malformed").into()
-            })
-            .collect(),
-        None => locales(),
-    };
+    // The locale files given on the command line take precedence over the
+    // ones in the style directories and the archive.
+    let locales: Vec<Locale> = locale_paths
+        .into_iter()
+        .flatten()
+        .map(|locale_path| {
+            let file_str =
+                fs::read_to_string(locale_path).expect("could not read locale file");
+            LocaleFile::from_xml(&file_str).expect("locale file malformed").into()
+        })
+        .chain(registry.locales().iter().cloned())
+        .collect();
+
+    if let Some(locale) = &locale {
+        let chain = LocaleResolver::new(&locales).resolve(&style, Some(locale));
+        if !chain.has_language() {
+            eprintln!("no locale found for {}, falling back to en-US", locale.0);
+        }
+    }
 
     (style, locales, locale)
 }
@@ -0,0 +1,283 @@
+//! Reconciliation of name variants across a library.
+
+use std::cmp::Reverse;
+
+use indexmap::IndexMap;
+
+use crate::types::{Person, PersonsWithRoles};
+use crate::{Entry, Library};
+
+/// A group of name variants in a library that refer to the same person.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameCluster {
+    /// The most complete form of the name. It carries the identifiers of all
+    /// variants.
+    pub canonical: Person,
+    /// The distinct forms in which the name appears in the library, in order
+    /// of their first appearance.
+    pub variants: Vec<Person>,
+    /// The keys of the entries in which the name appears.
+    pub entries: Vec<String>,
+}
+
+impl NameCluster {
+    /// Whether reconciling the cluster would change any of its names.
+    pub fn needs_reconciliation(&self) -> bool {
+        self.variants.iter().any(|v| v != &self.canonical)
+    }
+}
+
+impl Library {
+    /// Groups the persons in the library by their identity.
+    ///
+    /// Two names belong to the same person if they share an identifier like
+    /// an ORCID iD. Names without a shared identifier are grouped if they have
+    /// the same family name and compatible given names, e.g. `J. Smith` and
+    /// `Smith, John A.`. A name that is compatible with more than one group
+    /// (like `J. Smith` if there are both `John Smith` and `Jane Smith`) is
+    /// left on its own.
+    pub fn name_clusters(&self) -> Vec<NameCluster> {
+        let mut occurrences: IndexMap<&Person, Vec<&str>> = IndexMap::new();
+        for entry in self.iter() {
+            for_each_person(entry, &mut |person| {
+                let keys = occurrences.entry(person).or_default();
+                if keys.last() != Some(&entry.key()) {
+                    keys.push(entry.key());
+                }
+            });
+        }
+
+        // Place names with identifiers and the most complete names first so
+        // that they found the groups.
+        let persons: Vec<_> = occurrences.keys().copied().collect();
+        let mut order: Vec<usize> = (0..persons.len()).collect();
+        order.sort_by_key(|&i| {
+            Reverse((persons[i].has_identifiers(), completeness(persons[i])))
+        });
+
+        let mut groups: Vec<Vec<usize>> = vec![];
+        for i in order {
+            let person = persons[i];
+            let same_id = groups.iter().position(|group| {
+                group.iter().any(|&j| person.same_identity(persons[j]) == Some(true))
+                    && group
+                        .iter()
+                        .all(|&j| person.same_identity(persons[j]) != Some(false))
+            });
+
+            let group = same_id.or_else(|| {
+                let mut candidates = groups.iter().enumerate().filter(|(_, group)| {
+                    group.iter().all(|&j| compatible(person, persons[j]))
+                });
+                match (candidates.next(), candidates.next()) {
+                    (Some((idx, _)), None) => Some(idx),
+                    _ => None,
+                }
+            });
+
+            match group {
+                Some(idx) => groups[idx].push(i),
+                None => groups.push(vec![i]),
+            }
+        }
+
+        let mut clusters: Vec<_> = groups
+            .into_iter()
+            .map(|group| {
+                let best =
+                    group.iter().min_by_key(|&&j| Reverse(completeness(persons[j])));
+                let mut canonical = persons[*best.unwrap()].clone();
+                for &j in &group {
+                    let other = persons[j];
+                    canonical.orcid = canonical.orcid.or_else(|| other.orcid.clone());
+                    canonical.isni = canonical.isni.or_else(|| other.isni.clone());
+                    canonical.wikidata =
+                        canonical.wikidata.or_else(|| other.wikidata.clone());
+                    canonical.alias = canonical.alias.or_else(|| other.alias.clone());
+                }
+
+                let mut members = group;
+                members.sort_unstable();
+                let mut entries: Vec<String> = vec![];
+                for &j in &members {
+                    for key in &occurrences[j] {
+                        if !entries.iter().any(|e| e == key) {
+                            entries.push(key.to_string());
+                        }
+                    }
+                }
+                entries.sort_by_key(|key| self.0.get_index_of(key.as_str()));
+
+                NameCluster {
+                    canonical,
+                    variants: members.iter().map(|&j| persons[j].clone()).collect(),
+                    entries,
+                }
+            })
+            .collect();
+
+        clusters.sort_by_key(|cluster| {
+            persons.iter().position(|p| *p == &cluster.variants[0])
+        });
+        clusters
+    }
+
+    /// Rewrites all names in the library to the canonical form of their
+    /// [cluster](Self::name_clusters). Returns the number of changed names.
+    pub fn reconcile_names(&mut self) -> usize {
+        let canonical: IndexMap<Person, Person> = self
+            .name_clusters()
+            .into_iter()
+            .filter(NameCluster::needs_reconciliation)
+            .flat_map(|cluster| {
+                let canonical = cluster.canonical;
+                cluster.variants.into_iter().map(move |v| (v, canonical.clone()))
+            })
+            .collect();
+
+        let mut changed = 0;
+        for entry in self.0.values_mut() {
+            for_each_person_mut(entry, &mut |person| {
+                if let Some(new) = canonical.get(person).filter(|new| *new != person) {
+                    *person = new.clone();
+                    changed += 1;
+                }
+            });
+        }
+        changed
+    }
+}
+
+/// Calls `f` for all persons of an entry and its parents.
+fn for_each_person<'a>(entry: &'a Entry, f: &mut impl FnMut(&'a Person)) {
+    let affiliated = entry.affiliated.iter().flatten().flat_map(|a| a.names.iter());
+    for person in entry.authors.iter().chain(&entry.editors).flatten().chain(affiliated) {
+        f(person);
+    }
+
+    for parent in &entry.parents {
+        for_each_person(parent, f);
+    }
+}
+
+/// Calls `f` for all persons of an entry and its parents mutably.
+fn for_each_person_mut(entry: &mut Entry, f: &mut impl FnMut(&mut Person)) {
+    let affiliated = entry
+        .affiliated
+        .iter_mut()
+        .flatten()
+        .flat_map(|PersonsWithRoles { names, .. }| names.iter_mut());
+    for person in entry
+        .authors
+        .iter_mut()
+        .chain(entry.editors.iter_mut())
+        .flatten()
+        .chain(affiliated)
+    {
+        f(person);
+    }
+
+    for parent in &mut entry.parents {
+        for_each_person_mut(parent, f);
+    }
+}
+
+/// How much information a name carries.
+fn completeness(person: &Person) -> (usize, usize, usize) {
+    let given = given_parts(person);
+    (
+        given.iter().filter(|p| p.chars().count() > 1).count(),
+        given.len(),
+        person.given_name.as_ref().map_or(0, String::len),
+    )
+}
+
+/// Whether two names could refer to the same person.
+fn compatible(a: &Person, b: &Person) -> bool {
+    fn optional_eq(a: &Option<String>, b: &Option<String>) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => a.to_lowercase() == b.to_lowercase(),
+            _ => true,
+        }
+    }
+
+    if a.same_identity(b) == Some(false)
+        || a.name_with_particle().to_lowercase() != b.name_with_particle().to_lowercase()
+        || a.given_name.is_some() != b.given_name.is_some()
+        || !optional_eq(&a.prefix, &b.prefix)
+        || !optional_eq(&a.suffix, &b.suffix)
+    {
+        return false;
+    }
+
+    given_parts(a).iter().zip(given_parts(b)).all(|(a, b)| {
+        let (a, b) = (a.to_lowercase(), b.to_lowercase());
+        let is_initial = |s: &str| s.chars().count() == 1;
+        a == b
+            || (is_initial(&a) && b.starts_with(&a))
+            || (is_initial(&b) && a.starts_with(&b))
+    })
+}
+
+/// The parts of a given name, split at spaces, periods, and hyphens.
+fn given_parts(person: &Person) -> Vec<&str> {
+    person
+        .given_name
+        .iter()
+        .flat_map(|g| g.split(|c: char| c.is_whitespace() || c == '.' || c == '-'))
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::from_yaml_str;
+    use crate::types::PersonIdentifier;
+
+    #[test]
+    fn name_clusters() {
+        let mut lib = from_yaml_str(
+            r#"
+            a:
+                type: article
+                author: ["Smith, J.", "Doe, Jane"]
+            b:
+                type: article
+                author: ["Smith, John", { name: Doe, given-name: J., orcid: "https://orcid.org/0000-0002-1825-0097" }]
+            c:
+                type: article
+                author: ["Smith, John A.", "Smith, Jane"]
+            d:
+                type: article
+                author: { name: Doe, given-name: Jane, orcid: 0000-0002-1825-0097 }
+            "#,
+        )
+        .unwrap();
+
+        let clusters = lib.name_clusters();
+        let names: Vec<Vec<String>> = clusters
+            .iter()
+            .map(|c| c.variants.iter().map(|v| v.name_first(false, false)).collect())
+            .collect();
+
+        // `Smith, J.` could be John or Jane.
+        assert_eq!(
+            names,
+            [
+                vec!["Smith, J."],
+                vec!["Doe, Jane", "Doe, J.", "Doe, Jane"],
+                vec!["Smith, John", "Smith, John A."],
+                vec!["Smith, Jane"],
+            ]
+        );
+        assert_eq!(clusters[1].canonical.given_name.as_deref(), Some("Jane"));
+        assert_eq!(clusters[1].entries, ["a", "b", "d"]);
+        assert_eq!(clusters[2].canonical.given_name.as_deref(), Some("John A."));
+
+        assert_eq!(lib.reconcile_names(), 3);
+        let b = lib.get("b").unwrap().authors().unwrap();
+        assert_eq!(b[1].given_name.as_deref(), Some("Jane"));
+        assert_eq!(b[1].identifier(PersonIdentifier::Orcid), Some("0000-0002-1825-0097"));
+        assert!(lib.name_clusters().iter().all(|c| !c.needs_reconciliation()));
+    }
+}
@@ -0,0 +1,376 @@
+use std::fmt::{self, Display};
+
+use thiserror::Error;
+
+/// A kind of standard identifier that can be validated and normalized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IdentifierKind {
+    /// A Digital Object Identifier, normalized to a lowercase `10.xxxx/yyyy`.
+    Doi,
+    /// An International Standard Book Number, normalized to thirteen digits.
+    Isbn,
+    /// An International Standard Serial Number, normalized to `NNNN-NNNC`.
+    Issn,
+    /// A PubMed Identifier, normalized to its digits.
+    Pmid,
+    /// A PubMed Central Identifier, normalized to `PMC` followed by digits.
+    Pmcid,
+    /// An arXiv identifier in the new (`2101.00001v2`) or old style
+    /// (`hep-th/9901001`).
+    Arxiv,
+}
+
+impl IdentifierKind {
+    /// All kinds of identifiers.
+    pub const ALL: [Self; 6] =
+        [Self::Doi, Self::Isbn, Self::Issn, Self::Pmid, Self::Pmcid, Self::Arxiv];
+
+    /// The key of the identifier in an entry's serial numbers.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Doi => "doi",
+            Self::Isbn => "isbn",
+            Self::Issn => "issn",
+            Self::Pmid => "pmid",
+            Self::Pmcid => "pmcid",
+            Self::Arxiv => "arxiv",
+        }
+    }
+
+    /// Get the kind of identifier stored under a serial number key.
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.key() == key)
+    }
+
+    /// Validate an identifier and bring it into its canonical form.
+    ///
+    /// Resolver URLs and prefixes like `doi:` or `ISBN` are stripped. ISBN-10s
+    /// are converted to ISBN-13s.
+    ///
+    /// ```
+    /// use hayagriva::types::IdentifierKind;
+    ///
+    /// let doi = IdentifierKind::Doi.normalize("https://doi.org/10.1000/ABC");
+    /// assert_eq!(doi.unwrap(), "10.1000/abc");
+    ///
+    /// let isbn = IdentifierKind::Isbn.normalize("0-306-40615-2");
+    /// assert_eq!(isbn.unwrap(), "9780306406157");
+    /// ```
+    pub fn normalize(self, value: &str) -> Result<String, IdentifierError> {
+        let value = value.trim();
+        match self {
+            Self::Doi => normalize_doi(value),
+            Self::Isbn => normalize_isbn(value),
+            Self::Issn => normalize_issn(value),
+            Self::Pmid => normalize_pmid(value),
+            Self::Pmcid => normalize_pmcid(value),
+            Self::Arxiv => normalize_arxiv(value),
+        }
+    }
+}
+
+impl Display for IdentifierKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Doi => "DOI",
+            Self::Isbn => "ISBN",
+            Self::Issn => "ISSN",
+            Self::Pmid => "PMID",
+            Self::Pmcid => "PMCID",
+            Self::Arxiv => "arXiv identifier",
+        })
+    }
+}
+
+/// Error that may occur when validating an identifier.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum IdentifierError {
+    /// The identifier does not have the expected structure.
+    #[error("malformed {0}")]
+    Malformed(IdentifierKind),
+    /// The check digit of the identifier does not match.
+    #[error("{0} has an invalid check digit")]
+    Checksum(IdentifierKind),
+}
+
+/// A malformed identifier in an entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdentifierIssue {
+    /// The key of the entry. For identifiers in parent entries, this is the
+    /// key of the top-level entry.
+    pub key: String,
+    /// The value of the identifier.
+    pub value: String,
+    /// What is wrong with the identifier.
+    pub error: IdentifierError,
+}
+
+impl Display for IdentifierIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} ({})", self.key, self.error, self.value)
+    }
+}
+
+/// Strip the first matching prefix, ignoring ASCII case.
+fn strip_prefixes<'a>(value: &'a str, prefixes: &[&str]) -> &'a str {
+    for prefix in prefixes {
+        if value.len() >= prefix.len()
+            && value.is_char_boundary(prefix.len())
+            && value[..prefix.len()].eq_ignore_ascii_case(prefix)
+        {
+            return value[prefix.len()..].trim_start();
+        }
+    }
+    value
+}
+
+fn normalize_doi(value: &str) -> Result<String, IdentifierError> {
+    let doi = strip_prefixes(
+        value,
+        &[
+            "https://doi.org/",
+            "http://doi.org/",
+            "https://dx.doi.org/",
+            "http://dx.doi.org/",
+            "doi.org/",
+            "doi:",
+        ],
+    );
+
+    match doi.split_once('/') {
+        Some((prefix, suffix))
+            if prefix.strip_prefix("10.").is_some_and(|registrant| {
+                !registrant.is_empty()
+                    && registrant.chars().all(|c| c.is_ascii_digit() || c == '.')
+            }) && !suffix.is_empty()
+                && !doi.contains(char::is_whitespace) =>
+        {
+            Ok(doi.to_lowercase())
+        }
+        _ => Err(IdentifierError::Malformed(IdentifierKind::Doi)),
+    }
+}
+
+/// Remove hyphens and spaces and uppercase a trailing check character.
+fn compact(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' ' | '‐' | '–'))
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// The digit values of an identifier where only the last character may be an
+/// `X` standing for ten.
+fn digits(value: &str, x_allowed: bool) -> Option<Vec<u32>> {
+    let last = value.len().checked_sub(1)?;
+    value
+        .chars()
+        .enumerate()
+        .map(|(i, c)| match c {
+            'X' if x_allowed && i == last => Some(10),
+            _ => c.to_digit(10),
+        })
+        .collect()
+}
+
+/// The check digit of an ISBN-13 or EAN from its first twelve digits.
+fn ean_check(digits: &[u32]) -> u32 {
+    let sum: u32 = digits
+        .iter()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { *d } else { d * 3 })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+/// Whether the weighted modulo-11 checksum used by ISBN-10 and ISSN holds.
+fn mod11_valid(digits: &[u32]) -> bool {
+    let len = digits.len() as u32;
+    let sum: u32 = digits.iter().enumerate().map(|(i, d)| d * (len - i as u32)).sum();
+    sum.is_multiple_of(11)
+}
+
+fn normalize_isbn(value: &str) -> Result<String, IdentifierError> {
+    let malformed = IdentifierError::Malformed(IdentifierKind::Isbn);
+    let checksum = IdentifierError::Checksum(IdentifierKind::Isbn);
+    let isbn = compact(strip_prefixes(value, &["ISBN-13:", "ISBN-10:", "ISBN:", "ISBN"]));
+
+    match isbn.len() {
+        10 => {
+            let digits = digits(&isbn, true).ok_or(malformed)?;
+            if !mod11_valid(&digits) {
+                return Err(checksum);
+            }
+
+            let mut ean: Vec<u32> = vec![9, 7, 8];
+            ean.extend_from_slice(&digits[..9]);
+            let check = ean_check(&ean);
+            Ok(format!("978{}{}", &isbn[..9], check))
+        }
+        13 => {
+            let digits = digits(&isbn, false).ok_or(malformed)?;
+            if !isbn.starts_with("978") && !isbn.starts_with("979") {
+                return Err(malformed);
+            }
+            if ean_check(&digits[..12]) != digits[12] {
+                return Err(checksum);
+            }
+            Ok(isbn)
+        }
+        _ => Err(malformed),
+    }
+}
+
+fn normalize_issn(value: &str) -> Result<String, IdentifierError> {
+    let issn = compact(strip_prefixes(value, &["ISSN:", "ISSN"]));
+    let digits = (issn.len() == 8)
+        .then(|| digits(&issn, true))
+        .flatten()
+        .ok_or(IdentifierError::Malformed(IdentifierKind::Issn))?;
+
+    if !mod11_valid(&digits) {
+        return Err(IdentifierError::Checksum(IdentifierKind::Issn));
+    }
+
+    Ok(format!("{}-{}", &issn[..4], &issn[4..]))
+}
+
+fn normalize_pmid(value: &str) -> Result<String, IdentifierError> {
+    let pmid = strip_prefixes(
+        value,
+        &[
+            "https://pubmed.ncbi.nlm.nih.gov/",
+            "https://www.ncbi.nlm.nih.gov/pubmed/",
+            "PMID:",
+            "PMID",
+        ],
+    );
+    let pmid = pmid.trim_end_matches('/').trim_start_matches('0');
+
+    if pmid.is_empty() || !pmid.chars().all(|c| c.is_ascii_digit()) {
+        return Err(IdentifierError::Malformed(IdentifierKind::Pmid));
+    }
+
+    Ok(pmid.to_string())
+}
+
+fn normalize_pmcid(value: &str) -> Result<String, IdentifierError> {
+    let pmcid = strip_prefixes(
+        value,
+        &[
+            "https://www.ncbi.nlm.nih.gov/pmc/articles/",
+            "https://pmc.ncbi.nlm.nih.gov/articles/",
+            "PMCID:",
+        ],
+    );
+    let pmcid = pmcid.trim_end_matches('/');
+    let digits = strip_prefixes(pmcid, &["PMC"]);
+
+    if digits.len() == pmcid.len()
+        || digits.is_empty()
+        || !digits.chars().all(|c| c.is_ascii_digit())
+    {
+        return Err(IdentifierError::Malformed(IdentifierKind::Pmcid));
+    }
+
+    Ok(format!("PMC{}", digits))
+}
+
+fn normalize_arxiv(value: &str) -> Result<String, IdentifierError> {
+    let id = strip_prefixes(
+        value,
+        &[
+            "https://arxiv.org/abs/",
+            "http://arxiv.org/abs/",
+            "https://arxiv.org/pdf/",
+            "arxiv.org/abs/",
+            "arXiv:",
+        ],
+    );
+    let id = id.trim_end_matches(".pdf");
+
+    // Split off the version.
+    let (base, version) = match id.rsplit_once('v') {
+        Some((base, version))
+            if !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            (base, Some(version))
+        }
+        _ => (id, None),
+    };
+
+    let valid_month = |yymm: &str| {
+        yymm.len() == 4
+            && yymm.chars().all(|c| c.is_ascii_digit())
+            && (1..=12).contains(&yymm[2..].parse::<u32>().unwrap())
+    };
+
+    let valid = if let Some((archive, number)) = base.split_once('/') {
+        // Old style: `archive(.SC)/YYMMNNN`.
+        let (archive, class) = archive.split_once('.').unwrap_or((archive, ""));
+        !archive.is_empty()
+            && archive.chars().all(|c| c.is_ascii_lowercase() || c == '-')
+            && class.chars().all(|c| c.is_ascii_uppercase())
+            && number.len() == 7
+            && number.chars().all(|c| c.is_ascii_digit())
+            && valid_month(&number[..4])
+    } else if let Some((yymm, number)) = base.split_once('.') {
+        // New style: `YYMM.NNNN` until 2014, `YYMM.NNNNN` afterwards.
+        let len = if yymm.get(..2).is_some_and(|yy| yy >= "15") { 5 } else { 4 };
+        valid_month(yymm)
+            && number.len() == len
+            && number.chars().all(|c| c.is_ascii_digit())
+    } else {
+        false
+    };
+
+    if !valid {
+        return Err(IdentifierError::Malformed(IdentifierKind::Arxiv));
+    }
+
+    Ok(match version {
+        Some(version) => format!("{}v{}", base, version),
+        None => base.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifiers() {
+        let norm = |kind: IdentifierKind, s: &str| kind.normalize(s);
+        use IdentifierKind::*;
+
+        assert_eq!(norm(Doi, "doi:10.1038/NPHYS1170").unwrap(), "10.1038/nphys1170");
+        assert_eq!(norm(Doi, "http://dx.doi.org/10.1000/182").unwrap(), "10.1000/182");
+        assert!(norm(Doi, "11.1000/182").is_err());
+        assert!(norm(Doi, "10.1000").is_err());
+
+        assert_eq!(norm(Isbn, "ISBN 978-0-306-40615-7").unwrap(), "9780306406157");
+        assert_eq!(norm(Isbn, "080442957X").unwrap(), "9780804429573");
+        assert_eq!(norm(Isbn, "978-0-306-40615-8"), Err(IdentifierError::Checksum(Isbn)));
+        assert_eq!(norm(Isbn, "0-306-40615-3"), Err(IdentifierError::Checksum(Isbn)));
+        assert_eq!(norm(Isbn, "12345"), Err(IdentifierError::Malformed(Isbn)));
+
+        assert_eq!(norm(Issn, "2434561x").unwrap(), "2434-561X");
+        assert_eq!(norm(Issn, "2049-3630").unwrap(), "2049-3630");
+        assert_eq!(norm(Issn, "2049-3631"), Err(IdentifierError::Checksum(Issn)));
+
+        assert_eq!(norm(Pmid, "PMID: 12345678").unwrap(), "12345678");
+        assert!(norm(Pmid, "12a").is_err());
+        assert_eq!(norm(Pmcid, "pmc123456").unwrap(), "PMC123456");
+        assert!(norm(Pmcid, "123456").is_err());
+
+        assert_eq!(norm(Arxiv, "arXiv:2101.00001v2").unwrap(), "2101.00001v2");
+        assert_eq!(norm(Arxiv, "https://arxiv.org/abs/0706.0001").unwrap(), "0706.0001");
+        assert_eq!(norm(Arxiv, "hep-th/9901001").unwrap(), "hep-th/9901001");
+        assert_eq!(norm(Arxiv, "math.GT/0309136v1").unwrap(), "math.GT/0309136v1");
+        assert!(norm(Arxiv, "2113.00001").is_err());
+        assert!(norm(Arxiv, "2101.0001").is_err());
+        assert!(norm(Arxiv, "HEP-TH/9901001").is_err());
+        assert!(norm(Arxiv, "hep-th/123é56").is_err());
+    }
+}
@@ -9,32 +9,21 @@ use serde::de::value::StrDeserializer;
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use unic_langid::LanguageIdentifier;
 use url::Url;
 
+pub use identifiers::*;
 pub use numeric::*;
 pub use persons::*;
 pub use strings::*;
 pub use time::*;
 
+mod identifiers;
 mod numeric;
 mod persons;
 mod strings;
 mod time;
 
-/// Use the [`Display`] implementation of a type for serialization.
-macro_rules! serialize_display {
-    ($t:ty) => {
-        impl Serialize for $t {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-            where
-                S: serde::Serializer,
-            {
-                serializer.serialize_str(&self.to_string())
-            }
-        }
-    };
-}
-
 /// Use the [`FromStr`] implementation of a type for deserialization.
 macro_rules! deserialize_from_str {
     ($t:ty) => {
@@ -55,7 +44,7 @@ macro_rules! deserialize_from_str {
 macro_rules! derive_or_from_str {
     (
         $(#[$global:meta])*
-        $gv:vis struct $s:ident where $expect:literal {
+        $gv:vis struct $s:ident where $expect:literal $(then $finish:path)? {
             $(
                 $(#[doc = $doc:literal])*
                 $(#[serde $serde:tt])*
@@ -110,7 +99,12 @@ macro_rules! derive_or_from_str {
                         }
 
                         Deserialize::deserialize(de::value::MapAccessDeserializer::new(map))
-                            .map(|inner: Inner| $s { $($i: inner.$i),* })
+                            .map(|inner: Inner| {
+                                #[allow(unused_mut)]
+                                let mut value = $s { $($i: inner.$i),* };
+                                $($finish(&mut value);)?
+                                value
+                            })
                     }
                 }
 
@@ -122,7 +116,6 @@ macro_rules! derive_or_from_str {
 
 use derive_or_from_str;
 use deserialize_from_str;
-use serialize_display;
 
 /// Describes which kind of work a database entry refers to.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -175,9 +168,14 @@ pub enum EntryType {
     #[serde(alias = "Newspaper")]
     Newspaper,
     /// Legal document or draft there of that is, is to be, or was to be
-    /// enacted into binding law.
+    /// enacted into binding law. Use `Bill` for drafts that are known not to
+    /// be enacted.
     #[serde(alias = "Legislation")]
     Legislation,
+    /// A draft of legislation that has been proposed to a legislative body but
+    /// not enacted.
+    #[serde(alias = "Bill")]
+    Bill,
     /// A document that is not yet published.
     #[serde(alias = "Manuscript")]
     Manuscript,
@@ -204,9 +202,13 @@ pub enum EntryType {
     /// Set of self-published articles on a website.
     #[serde(alias = "Blog")]
     Blog,
-    /// A work of reference. This could be a manual or a dictionary.
+    /// A work of reference. This could be a manual or an encyclopedia. Use
+    /// `Dictionary` for dictionaries.
     #[serde(alias = "Reference")]
     Reference,
+    /// A work of reference that lists and explains the words of a language.
+    #[serde(alias = "Dictionary")]
+    Dictionary,
     /// Professional conference. This Entry type implies that the item
     /// referenced has been an event at the conference itself. If you instead
     /// want to reference a paper published in the published proceedings of the
@@ -236,6 +238,33 @@ pub enum EntryType {
     /// A prior publication of the same item.
     #[serde(alias = "Original")]
     Original,
+    /// A structured collection of data, like research data or the results of a
+    /// survey.
+    #[serde(alias = "Dataset")]
+    Dataset,
+    /// A computer program, possibly in a specific version. Use `Repository`
+    /// for the storage of its source code.
+    #[serde(alias = "Software")]
+    Software,
+    /// A technical standard or specification issued by a standards body.
+    #[serde(alias = "Standard")]
+    Standard,
+    /// A depiction of a geographic area.
+    #[serde(alias = "Map")]
+    Map,
+    /// A conversation in which an interviewer asks questions. The authors are
+    /// the interviewees.
+    #[serde(alias = "Interview")]
+    Interview,
+    /// A talk, lecture, or presentation given to an audience. Can have a
+    /// `Conference` parent.
+    #[serde(alias = "Speech")]
+    Speech,
+    /// A critical assessment of another work. The reviewed work is a parent
+    /// of any type other than `Periodical`, `Newspaper`, `Blog`, or `Web`,
+    /// which contain the review.
+    #[serde(alias = "Review")]
+    Review,
 }
 
 impl EntryType {
@@ -251,6 +280,8 @@ impl EntryType {
             Self::Scene => Self::Video,
             Self::Artwork => Self::Exhibition,
             Self::Legislation => Self::Anthology,
+            Self::Review => Self::Periodical,
+            Self::Speech => Self::Conference,
             Self::Post => Self::Post,
             Self::Video => Self::Video,
             Self::Audio => Self::Audio,
@@ -361,14 +392,19 @@ impl<T> From<T> for MaybeTyped<T> {
 }
 
 derive_or_from_str! {
-    /// An URL, possibly with a last visited date.
+    /// An URL, possibly with a last visited date and an archived copy.
     #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-    pub struct QualifiedUrl where "URL string or dictionary with keys \"url\" and \"date\"" {
+    pub struct QualifiedUrl where "URL string or dictionary with keys \"url\" and \"date\"" then QualifiedUrl::fill_wayback_date {
         /// The [Url].
         pub value: Url,
         /// The last visited date.
         #[serde(rename = "date")]
         pub visit_date: Option<Date>,
+        /// The URL of an archived copy, e.g. in the Wayback Machine or on
+        /// perma.cc.
+        pub(crate) archive_url: Option<Url>,
+        /// The date on which the archived copy was made.
+        pub(crate) archive_date: Option<Date>,
     }
 }
 
@@ -377,29 +413,125 @@ impl Serialize for QualifiedUrl {
     where
         S: serde::Serializer,
     {
+        if self.visit_date.is_none()
+            && self.archive_url.is_none()
+            && self.archive_date.is_none()
+        {
+            return self.value.serialize(serializer);
+        }
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("value", &self.value)?;
         if let Some(date) = &self.visit_date {
-            let mut map = serializer.serialize_map(Some(2))?;
-            map.serialize_entry("value", &self.value)?;
             map.serialize_entry("date", date)?;
-            map.end()
-        } else {
-            self.value.serialize(serializer)
         }
+        if let Some(url) = &self.archive_url {
+            map.serialize_entry("archive-url", url)?;
+        }
+        if let Some(date) = &self.archive_date {
+            map.serialize_entry("archive-date", date)?;
+        }
+        map.end()
     }
 }
 
 impl QualifiedUrl {
     /// Create a new qualified URL.
     pub fn new(value: Url, visit_date: Option<Date>) -> Self {
-        Self { value, visit_date }
+        Self {
+            value,
+            visit_date,
+            archive_url: None,
+            archive_date: None,
+        }
+    }
+
+    /// Set the archived copy of the URL. If no date is given, it is taken
+    /// from the snapshot timestamp of Wayback Machine URLs.
+    ///
+    /// ```
+    /// use hayagriva::types::QualifiedUrl;
+    ///
+    /// let mut url: QualifiedUrl = "https://example.com/".parse().unwrap();
+    /// url.set_archive(
+    ///     "https://web.archive.org/web/20210314095126/https://example.com/"
+    ///         .parse()
+    ///         .unwrap(),
+    ///     None,
+    /// );
+    /// assert_eq!(url.archive_date().unwrap().year, 2021);
+    /// ```
+    pub fn set_archive(&mut self, url: Url, date: Option<Date>) {
+        self.archive_url = Some(url);
+        self.archive_date = date;
+        self.fill_wayback_date();
+    }
+
+    /// The URL of an archived copy, e.g. in the Wayback Machine or on
+    /// perma.cc.
+    pub fn archive_url(&self) -> Option<&Url> {
+        self.archive_url.as_ref()
+    }
+
+    /// The date on which the archived copy was made.
+    pub fn archive_date(&self) -> Option<Date> {
+        self.archive_date
+    }
+
+    /// Take a missing archive date from the snapshot timestamp of a Wayback
+    /// Machine URL.
+    fn fill_wayback_date(&mut self) {
+        if self.archive_date.is_none() {
+            self.archive_date = self.archive_url.as_ref().and_then(wayback_date);
+        }
+    }
+
+    /// The name of the service that holds the archived copy, if it is a
+    /// well-known one. Otherwise, the host of the archived URL.
+    pub fn archive_name(&self) -> Option<Cow<'_, str>> {
+        archive_name(self.archive_url.as_ref()?)
     }
 }
 
+/// The name of the service that holds an archived copy at the given URL, if it
+/// is a well-known one. Otherwise, the host of the URL.
+pub(crate) fn archive_name(url: &Url) -> Option<Cow<'_, str>> {
+    let host = url.host_str()?;
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    Some(match host {
+        "web.archive.org" | "wayback.archive.org" | "archive.org" => {
+            Cow::Borrowed("Internet Archive")
+        }
+        "perma.cc" => Cow::Borrowed("Perma.cc"),
+        "archive.today" | "archive.ph" | "archive.is" | "archive.li" | "archive.md"
+        | "archive.vn" | "archive.fo" => Cow::Borrowed("archive.today"),
+        "webcitation.org" => Cow::Borrowed("WebCite"),
+        _ => Cow::Owned(host.to_string()),
+    })
+}
+
+/// The snapshot date of a Wayback Machine URL like
+/// `https://web.archive.org/web/20210314095126/https://example.com/`.
+fn wayback_date(url: &Url) -> Option<Date> {
+    if !matches!(url.host_str()?, "web.archive.org" | "wayback.archive.org") {
+        return None;
+    }
+
+    let mut segments = url.path_segments()?;
+    if segments.next()? != "web" {
+        return None;
+    }
+
+    let stamp = segments.next()?;
+    let digits = stamp.get(..8).filter(|d| d.bytes().all(|b| b.is_ascii_digit()))?;
+    Date::from_str(&format!("{}-{}-{}", &digits[..4], &digits[4..6], &digits[6..])).ok()
+}
+
 impl FromStr for QualifiedUrl {
     type Err = url::ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self { value: Url::parse(s)?, visit_date: None })
+        Ok(Self::new(Url::parse(s)?, None))
     }
 }
 
@@ -459,6 +591,35 @@ impl<'de> Deserialize<'de> for SerialNumber {
     }
 }
 
+/// A language variant of a multilingual field like a title or a name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LanguageVariant {
+    /// The value in its original language and script.
+    Original,
+    /// A transliteration into another script, e.g. `ru-Latn`.
+    Transliteration(LanguageIdentifier),
+    /// A translation into another language, e.g. `en`.
+    Translation(LanguageIdentifier),
+}
+
+/// Find the variant for a language tag. A tag without script or region
+/// matches variants with any script or region.
+fn lookup_variant<'a, V>(
+    variants: &'a BTreeMap<LanguageIdentifier, V>,
+    tag: &LanguageIdentifier,
+) -> Option<&'a V> {
+    variants.get(tag).or_else(|| {
+        variants
+            .iter()
+            .find(|(key, _)| {
+                key.language == tag.language
+                    && (tag.script.is_none() || key.script == tag.script)
+                    && (tag.region.is_none() || key.region == tag.region)
+            })
+            .map(|(_, v)| v)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -527,7 +688,48 @@ mod tests {
         );
         assert_eq!(&val.to_string(), "2 & 3 & 4");
 
-        assert!(Numeric::from_str("second").is_err());
+        assert!(Numeric::from_str("second edition").is_err());
         assert!(Numeric::from_str("2nd edition").is_err());
     }
+
+    #[test]
+    fn archived_url() {
+        let url: QualifiedUrl = serde_yaml::from_str(
+            r#"
+            value: https://example.com/report
+            archive-url: https://web.archive.org/web/20210314095126/https://example.com/report
+            "#,
+        )
+        .unwrap();
+        assert_eq!(url.archive_name().as_deref(), Some("Internet Archive"));
+        assert_eq!(url.archive_date.unwrap().to_string(), "2021-03-14");
+
+        let mut url = QualifiedUrl::new(url.value, None);
+        url.set_archive(Url::parse("https://perma.cc/ABC1-D2EF").unwrap(), None);
+        assert_eq!(url.archive_name().as_deref(), Some("Perma.cc"));
+        assert!(url.archive_date.is_none());
+
+        url.set_archive(
+            Url::parse("https://web.archive.org/web/20210314095126/https://example.com/")
+                .unwrap(),
+            None,
+        );
+        assert_eq!(url.archive_date.unwrap().to_string(), "2021-03-14");
+
+        let yaml = serde_yaml::to_string(&url).unwrap();
+        let parsed: QualifiedUrl = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, url);
+
+        // An archive date without an archived URL is kept as well.
+        let url: QualifiedUrl = serde_yaml::from_str(
+            r#"
+            value: https://example.com/report
+            archive-date: 2020-01-01
+            "#,
+        )
+        .unwrap();
+        let yaml = serde_yaml::to_string(&url).unwrap();
+        let parsed: QualifiedUrl = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, url);
+    }
 }
@@ -11,6 +11,7 @@ use thiserror::Error;
 use unscanny::Scanner;
 
 use super::MaybeTyped;
+use crate::lang::en::{ORDINALS, TENS, TENS_ORDINALS};
 
 /// A numeric value that can be pluralized.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -21,6 +22,29 @@ pub struct Numeric {
     pub prefix: Option<Box<String>>,
     /// A string that is appended to the value.
     pub suffix: Option<Box<String>>,
+    /// How the numbers are written.
+    pub(crate) notation: NumberNotation,
+}
+
+/// How the numbers in a numeric value are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NumberNotation {
+    /// Arabic numerals like `14`.
+    #[default]
+    Arabic,
+    /// Arabic numerals that all carry the prefix of the numeric value, like
+    /// the page range `S12–S19`.
+    PrefixedArabic,
+    /// Roman numerals like `xiv` or `XIV`.
+    Roman {
+        /// Whether the numerals are uppercase.
+        uppercase: bool,
+    },
+    /// English ordinal words like `second`.
+    OrdinalWord {
+        /// Whether the word is capitalized.
+        capitalized: bool,
+    },
 }
 
 impl<'de> Deserialize<'de> for Numeric {
@@ -68,7 +92,9 @@ impl Serialize for Numeric {
         S: serde::Serializer,
     {
         match self.value {
-            NumericValue::Number(n) if self.will_transform() => {
+            NumericValue::Number(n)
+                if self.will_transform() && self.notation == NumberNotation::Arabic =>
+            {
                 serializer.serialize_i32(n)
             }
             _ => serializer.serialize_str(&self.to_string()),
@@ -83,6 +109,7 @@ impl Numeric {
             value: NumericValue::Number(value),
             prefix: None,
             suffix: None,
+            notation: NumberNotation::Arabic,
         }
     }
 
@@ -95,12 +122,34 @@ impl Numeric {
             ]),
             prefix: None,
             suffix: None,
+            notation: NumberNotation::Arabic,
         }
     }
 
-    /// Whether the numeric value contains only numbers.
+    /// Sets how the numbers are written.
+    pub fn with_notation(mut self, notation: NumberNotation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    /// How the numbers are written.
+    pub fn notation(&self) -> NumberNotation {
+        self.notation
+    }
+
+    /// Parses a numeric value like [`from_str`](FromStr::from_str), but also
+    /// accepts Roman numerals like `xiv–xx` or `XLII`. This is meant for
+    /// page-like values such as page ranges and volumes because elsewhere,
+    /// uppercase words like _CD_ or _MD_ are rarely numbers.
+    pub fn from_str_with_roman(value: &str) -> Result<Self, NumericError> {
+        parse_roman(value).map_or_else(|| value.parse(), Ok)
+    }
+
+    /// Whether the numeric value contains only numbers, possibly with a
+    /// prefix that each of them carries.
     pub fn will_transform(&self) -> bool {
-        self.prefix.is_none() && self.suffix.is_none()
+        (self.prefix.is_none() || self.notation == NumberNotation::PrefixedArabic)
+            && self.suffix.is_none()
     }
 
     /// Retrieve the prefix string slice.
@@ -113,17 +162,27 @@ impl Numeric {
         self.suffix.as_deref().map(String::as_str)
     }
 
-    /// Format the value without the prefix and suffix.
+    /// Format the value without the prefix and suffix. Machine-readable
+    /// values always use Arabic numerals.
     pub fn fmt_value<T>(&self, buf: &mut T, machine_readable: bool) -> std::fmt::Result
     where
         T: fmt::Write,
     {
-        let format = |n: i32, buf: &mut T| -> std::fmt::Result { write!(buf, "{}", n) };
+        let format = |n: i32, buf: &mut T| -> std::fmt::Result {
+            if machine_readable {
+                write!(buf, "{}", n)
+            } else {
+                self.fmt_number(n, buf)
+            }
+        };
 
         match &self.value {
             &NumericValue::Number(n) => format(n, buf)?,
             NumericValue::Set(s) => {
-                for &(n, sep) in s {
+                for (i, &(n, sep)) in s.iter().enumerate() {
+                    if i > 0 && !machine_readable {
+                        self.fmt_repeated_prefix(buf)?;
+                    }
                     format(n, buf)?;
                     if let Some(sep) = sep {
                         if machine_readable {
@@ -139,6 +198,48 @@ impl Numeric {
         Ok(())
     }
 
+    /// Format a single number in the notation of this value. Does not write
+    /// the prefix.
+    pub fn fmt_number<T>(&self, n: i32, buf: &mut T) -> std::fmt::Result
+    where
+        T: fmt::Write,
+    {
+        match self.notation {
+            NumberNotation::Roman { uppercase } if (1..=3999).contains(&n) => {
+                let roman = numerals::roman::Roman::from(n as i16);
+                if uppercase {
+                    write!(buf, "{:X}", roman)
+                } else {
+                    write!(buf, "{:x}", roman)
+                }
+            }
+            NumberNotation::OrdinalWord { capitalized } if (1..100).contains(&n) => {
+                let word = ordinal_word(n);
+                if capitalized {
+                    let mut chars = word.chars();
+                    let first = chars.next().unwrap();
+                    write!(buf, "{}{}", first.to_uppercase(), chars.as_str())
+                } else {
+                    buf.write_str(&word)
+                }
+            }
+            _ => write!(buf, "{}", n),
+        }
+    }
+
+    /// Writes the prefix if every number carries it.
+    pub(crate) fn fmt_repeated_prefix<T>(&self, buf: &mut T) -> std::fmt::Result
+    where
+        T: fmt::Write,
+    {
+        match &self.prefix {
+            Some(prefix) if self.notation == NumberNotation::PrefixedArabic => {
+                buf.write_str(prefix)
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn fmt_custom<T>(&self, buf: &mut T, machine_readable: bool) -> std::fmt::Result
     where
         T: fmt::Write,
@@ -179,14 +280,18 @@ impl Numeric {
                 NumberForm::Roman if n > 0 && n <= i16::MAX as i32 => {
                     write!(buf, "{:x}", numerals::roman::Roman::from(n as i16))
                 }
-                NumberForm::Numeric | NumberForm::Roman => write!(buf, "{}", n),
+                NumberForm::Numeric | NumberForm::Roman => self.fmt_number(n, buf),
             }
         };
 
         match &self.value {
-            &NumericValue::Number(n) => format(n, buf)?,
+            &NumericValue::Number(n) => {
+                self.fmt_repeated_prefix(buf)?;
+                format(n, buf)?
+            }
             NumericValue::Set(s) => {
                 for &(n, sep) in s {
+                    self.fmt_repeated_prefix(buf)?;
                     format(n, buf)?;
                     if let Some(sep) = sep {
                         write!(buf, "{}", sep)?
@@ -269,36 +374,29 @@ impl FromStr for Numeric {
     type Err = NumericError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some((n, capitalized)) = parse_ordinal_word(value.trim()) {
+            return Ok(Self {
+                notation: NumberNotation::OrdinalWord { capitalized },
+                ..Self::new(n)
+            });
+        }
+
         let mut s = Scanner::new(value);
         let prefix =
             s.eat_while(|c: char| !c.is_numeric() && !c.is_whitespace() && c != '-');
 
-        let value = number(&mut s).ok_or(NumericError::NoNumber)?;
-        s.eat_whitespace();
-
-        let value = match s.peek() {
-            Some(c) if is_delimiter(c) => {
-                s.eat();
-                s.eat_until(|c: char| !is_delimiter(c));
-                let mut items = vec![(value, Some(NumericDelimiter::try_from(c)?))];
-                loop {
-                    let num = number(&mut s).ok_or(NumericError::NoNumber)?;
-                    s.eat_whitespace();
-                    match NumericDelimiter::from_str(s.eat_while(is_delimiter)) {
-                        Ok(d) => {
-                            items.push((num, Some(d)));
-                        }
-                        Err(_) => {
-                            items.push((num, None));
-                            break;
-                        }
-                    }
-                }
-                NumericValue::Set(items)
+        // Count how many of the numbers after the first repeat the prefix.
+        let mut items = 0;
+        let mut prefixed = 0;
+        let value = number_set(&mut s, |s| {
+            s.eat_whitespace();
+            if items > 0 && !prefix.is_empty() && s.eat_if(prefix) {
+                prefixed += 1;
             }
+            items += 1;
+            number(s)
+        })?;
 
-            _ => NumericValue::Number(value),
-        };
         s.eat_whitespace();
         let post = s.eat_while(|c: char| !c.is_whitespace());
 
@@ -306,6 +404,14 @@ impl FromStr for Numeric {
             return Err(NumericError::UnexpectedCharactersAfterPostfix);
         }
 
+        let notation = if prefixed == 0 {
+            NumberNotation::Arabic
+        } else if prefixed + 1 == items {
+            NumberNotation::PrefixedArabic
+        } else {
+            return Err(NumericError::InconsistentPrefixes);
+        };
+
         Ok(Self {
             value,
             prefix: if prefix.is_empty() {
@@ -314,10 +420,114 @@ impl FromStr for Numeric {
                 Some(Box::new(prefix.to_string()))
             },
             suffix: if post.is_empty() { None } else { Some(Box::new(post.to_string())) },
+            notation,
         })
     }
 }
 
+/// Parses a single number or a set of numbers separated by delimiters.
+fn number_set(
+    s: &mut Scanner,
+    mut number: impl FnMut(&mut Scanner) -> Option<i32>,
+) -> Result<NumericValue, NumericError> {
+    let value = number(s).ok_or(NumericError::NoNumber)?;
+    s.eat_whitespace();
+
+    Ok(match s.peek() {
+        Some(c) if is_delimiter(c) => {
+            s.eat();
+            s.eat_until(|c: char| !is_delimiter(c));
+            let mut items = vec![(value, Some(NumericDelimiter::try_from(c)?))];
+            loop {
+                let num = number(s).ok_or(NumericError::NoNumber)?;
+                s.eat_whitespace();
+                match NumericDelimiter::from_str(s.eat_while(is_delimiter)) {
+                    Ok(d) => {
+                        items.push((num, Some(d)));
+                    }
+                    Err(_) => {
+                        items.push((num, None));
+                        break;
+                    }
+                }
+            }
+            NumericValue::Set(items)
+        }
+
+        _ => NumericValue::Number(value),
+    })
+}
+
+/// Parses a value that consists only of Roman numerals of the same case.
+fn parse_roman(value: &str) -> Option<Numeric> {
+    let uppercase = value.trim_start().starts_with(|c: char| c.is_ascii_uppercase());
+    let mut s = Scanner::new(value);
+    let value = number_set(&mut s, |s| {
+        s.eat_whitespace();
+        roman(s.eat_while(|c: char| c.is_ascii_alphabetic()), uppercase)
+    })
+    .ok()?;
+
+    s.eat_whitespace();
+    s.done().then_some(Numeric {
+        notation: NumberNotation::Roman { uppercase },
+        value,
+        prefix: None,
+        suffix: None,
+    })
+}
+
+/// Parses a well-formed Roman numeral. Single letters other than I, V, and X
+/// are not considered numerals since they more often serve as labels.
+fn roman(s: &str, uppercase: bool) -> Option<i32> {
+    if s.is_empty()
+        || !s.chars().all(|c| c.is_ascii_uppercase() == uppercase)
+        || (s.len() == 1 && !matches!(s, "I" | "V" | "X" | "i" | "v" | "x"))
+    {
+        return None;
+    }
+
+    let n = numerals::roman::Roman::parse(s)?.value_checked()?;
+    if !(1..=3999).contains(&n) {
+        return None;
+    }
+
+    // Only accept the canonical spelling, rejecting things like `IIII`.
+    let canonical = format!("{:x}", numerals::roman::Roman::from(n));
+    (canonical.eq_ignore_ascii_case(s)).then_some(n as i32)
+}
+
+/// Parses an English ordinal word from _first_ to _ninety-ninth_. Also
+/// returns whether the word was capitalized.
+fn parse_ordinal_word(s: &str) -> Option<(i32, bool)> {
+    let capitalized = s.starts_with(|c: char| c.is_uppercase());
+    let lower = s.to_lowercase();
+    let position = |list: &[&str], s: &str| list.iter().position(|w| *w == s);
+
+    let n = if let Some(i) = position(&ORDINALS, &lower) {
+        i + 1
+    } else if let Some(i) = position(&TENS_ORDINALS, &lower) {
+        (i + 2) * 10
+    } else {
+        let (tens, ones) = lower.split_once(['-', ' '])?;
+        let tens = position(&TENS, tens)?;
+        let ones = position(&ORDINALS[..9], ones)?;
+        (tens + 2) * 10 + ones + 1
+    };
+
+    Some((n as i32, capitalized))
+}
+
+/// Writes a number from 1 to 99 as an English ordinal word.
+fn ordinal_word(n: i32) -> String {
+    let n = n as usize;
+    match (n / 10, n % 10) {
+        (0 | 1, _) => ORDINALS[n - 1].to_string(),
+        (tens, 0) => TENS_ORDINALS[tens - 2].to_string(),
+        (tens, ones) => format!("{}-{}", TENS[tens - 2], ORDINALS[ones - 1]),
+    }
+}
+
 impl From<i32> for Numeric {
     fn from(n: i32) -> Self {
         Self::new(n)
@@ -346,6 +556,9 @@ pub enum NumericError {
     /// The string does not contain a delimiter.
     #[error("missing delimiter")]
     MissingDelimiter,
+    /// Only some of the numbers in a set carry the prefix.
+    #[error("inconsistent prefixes")]
+    InconsistentPrefixes,
 }
 
 fn number(s: &mut Scanner) -> Option<i32> {
@@ -496,4 +709,78 @@ mod test {
             ])
         );
     }
+
+    #[test]
+    fn test_roman() {
+        let n = Numeric::from_str_with_roman("xiv–xx").unwrap();
+        assert_eq!(n.notation, NumberNotation::Roman { uppercase: false });
+        assert_eq!(n.range(), Some(14..=20));
+        assert!(n.is_plural(false));
+        assert_eq!(n.to_string(), "xiv–xx");
+
+        let n = Numeric::from_str_with_roman("XLII").unwrap();
+        assert_eq!(n.single_number(), Some(42));
+        assert_eq!(n.to_string(), "XLII");
+        assert_eq!(Numeric::from_str_with_roman("12").unwrap().single_number(), Some(12));
+
+        // Malformed numerals and mixed case are not numbers.
+        assert_eq!(
+            Numeric::from_str_with_roman("IIII").unwrap_err(),
+            NumericError::NoNumber
+        );
+        assert!(Numeric::from_str_with_roman("XIV-xx").is_err());
+        assert!(Numeric::from_str_with_roman("C").is_err());
+
+        // Outside of page-like values, words like these are not numbers.
+        for s in ["CD", "DC", "MD", "MC", "xiv"] {
+            assert!(s.parse::<Numeric>().is_err());
+        }
+
+        let yaml =
+            "a:\n  type: article\n  volume: XIV\n  issue: CD\n  page-range: xii-xx";
+        let library = crate::io::from_yaml_str(yaml).unwrap();
+        let entry = library.get("a").unwrap();
+        assert!(
+            matches!(entry.volume(), Some(MaybeTyped::Typed(n)) if n.single_number() == Some(14))
+        );
+        assert!(matches!(entry.issue(), Some(MaybeTyped::String(s)) if s == "CD"));
+        assert!(
+            matches!(entry.page_range(), Some(MaybeTyped::Typed(n)) if n.range() == Some(12..=20))
+        );
+    }
+
+    #[test]
+    fn test_prefixed() {
+        let n: Numeric = "S12–S19".parse().unwrap();
+        assert_eq!(n.notation, NumberNotation::PrefixedArabic);
+        assert_eq!(n.prefix_str(), Some("S"));
+        assert_eq!(n.range(), Some(12..=19));
+        assert!(n.will_transform());
+        assert_eq!(n.to_string(), "S12–S19");
+
+        let n: Numeric = "A3".parse().unwrap();
+        assert_eq!(n.notation, NumberNotation::Arabic);
+        assert_eq!(n.nth(0), Some(3));
+        assert!(!n.is_plural(false));
+
+        assert_eq!(
+            "S1, S3, 5".parse::<Numeric>().unwrap_err(),
+            NumericError::InconsistentPrefixes
+        );
+    }
+
+    #[test]
+    fn test_ordinal_word() {
+        let n: Numeric = "second".parse().unwrap();
+        assert_eq!(n.notation, NumberNotation::OrdinalWord { capitalized: false });
+        assert_eq!(n.single_number(), Some(2));
+        assert_eq!(n.to_string(), "second");
+
+        let n: Numeric = "Twenty-First".parse().unwrap();
+        assert_eq!(n.single_number(), Some(21));
+        assert_eq!(n.to_string(), "Twenty-first");
+
+        let n: Numeric = "fortieth".parse().unwrap();
+        assert_eq!(n.single_number(), Some(40));
+    }
 }
@@ -1,19 +1,22 @@
 use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use citationberg::LongShortForm;
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use unic_langid::LanguageIdentifier;
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::lang::collation::Collator;
 use crate::lang::en::ARTICLES;
 use crate::lang::is_cjk;
 use crate::lang::name::NAME_PARTICLES;
 use crate::util::{deserialize_one_or_many, serialize_one_or_many};
 
-use super::derive_or_from_str;
+use super::{derive_or_from_str, lookup_variant, LanguageVariant};
 
 /// A list of persons with a common role.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -79,6 +82,8 @@ pub enum PersonRole {
     Illustrator,
     /// Provided narration or voice-over for the cited item.
     Narrator,
+    /// Asked the questions in an [Interview](super::EntryType::Interview).
+    Interviewer,
 
     /// Various other roles described by the contained string.
     #[serde(skip)]
@@ -87,18 +92,39 @@ pub enum PersonRole {
 
 derive_or_from_str! {
     /// Holds the name of a person.
-    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
     pub struct Person where "a name string or a dictionary with a \"name\" key" {
         /// The family name.
         pub name: String,
         /// The given name / forename.
         pub given_name: Option<String>,
-        /// A prefix of the family name such as 'van' or 'de'.
+        /// The dropping particle of the family name such as the 'van' in
+        /// 'Ludwig van Beethoven'. It is omitted if the family name stands
+        /// alone and is not considered for sorting.
+        #[serde(alias = "dropping-particle")]
         pub prefix: Option<String>,
+        /// The non-dropping particle of the family name such as the 'van' in
+        /// 'Vincent van Gogh'. It always accompanies the family name. If this
+        /// is not set, known particles at the start of the family name are
+        /// treated as non-dropping.
+        pub(crate) non_dropping_particle: Option<String>,
         /// A suffix of the family name such as 'Jr.' or 'IV'.
         pub suffix: Option<String>,
         /// Another name (often user name) the person might be known under.
         pub alias: Option<String>,
+        /// The person's ORCID iD, e.g. `0000-0002-1825-0097`.
+        pub(crate) orcid: Option<String>,
+        /// The person's ISNI, e.g. `0000 0001 2103 2683`.
+        pub(crate) isni: Option<String>,
+        /// The person's Wikidata item, e.g. `Q42`.
+        pub(crate) wikidata: Option<String>,
+        /// Translations of the name, keyed by their language.
+        #[serde(default)]
+        pub(crate) translations: BTreeMap<LanguageIdentifier, Person>,
+        /// Transliterations of the name, keyed by their language and script,
+        /// e.g. `ru-Latn`.
+        #[serde(default)]
+        pub(crate) transliterations: BTreeMap<LanguageIdentifier, Person>,
     }
 }
 
@@ -107,20 +133,32 @@ impl Serialize for Person {
     where
         S: serde::Serializer,
     {
-        // Aliases are not represented in the string, prefixes can create
-        // ambiguity.
-        if self.alias.is_none() && self.prefix.is_none() {
-            serializer.serialize_str(&self.name_first(false, false))
+        // Aliases, identifiers, and language variants are not represented in
+        // the string.
+        if let Some(name) = self
+            .to_name_string()
+            .filter(|_| self.translations.is_empty() && self.transliterations.is_empty())
+        {
+            serializer.serialize_str(&name)
         } else {
             let entries = [
                 ("name", Some(&self.name)),
                 ("given-name", self.given_name.as_ref()),
                 ("prefix", self.prefix.as_ref()),
+                ("non-dropping-particle", self.non_dropping_particle.as_ref()),
                 ("suffix", self.suffix.as_ref()),
                 ("alias", self.alias.as_ref()),
+                ("orcid", self.orcid.as_ref()),
+                ("isni", self.isni.as_ref()),
+                ("wikidata", self.wikidata.as_ref()),
+            ];
+            let variants = [
+                ("translations", &self.translations),
+                ("transliterations", &self.transliterations),
             ];
 
-            let map_len = entries.iter().filter(|(_, v)| v.is_some()).count();
+            let map_len = entries.iter().filter(|(_, v)| v.is_some()).count()
+                + variants.iter().filter(|(_, v)| !v.is_empty()).count();
 
             let mut map = serializer.serialize_map(Some(map_len))?;
 
@@ -130,11 +168,46 @@ impl Serialize for Person {
                 }
             }
 
+            for (key, value) in variants.iter() {
+                if !value.is_empty() {
+                    map.serialize_entry(key, value)?;
+                }
+            }
+
             map.end()
         }
     }
 }
 
+/// A kind of persistent identifier for persons.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PersonIdentifier {
+    /// An Open Researcher and Contributor ID.
+    Orcid,
+    /// An International Standard Name Identifier.
+    Isni,
+    /// A Wikidata item identifier.
+    Wikidata,
+}
+
+impl PersonIdentifier {
+    /// Reduce an identifier to its canonical form, stripping resolver URLs,
+    /// whitespace, and separators.
+    pub fn normalize(self, id: &str) -> String {
+        let id = id.trim();
+        let id = match self {
+            Self::Orcid => id.rsplit("orcid.org/").next().unwrap(),
+            Self::Isni => id.rsplit("isni.org/isni/").next().unwrap(),
+            Self::Wikidata => id.rsplit(['/', ':']).next().unwrap(),
+        };
+
+        id.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_uppercase())
+            .collect()
+    }
+}
+
 /// Error that may occur when parsing a slice of strings as a name.
 #[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
 pub enum PersonError {
@@ -152,82 +225,149 @@ pub enum PersonError {
 impl Person {
     /// This function expects a list of strings with its length between one and
     /// three. The first part will be interpreted as the `<prefix> <Name>`, the
-    /// second part as the given name and the third part as the suffix.
+    /// last part as the given name and, if there are three parts, the middle
+    /// part as the suffix.
     ///
     /// The prefix and name are separated just like in BiBTeX, as described
     /// [Nicolas Markey describes in "Tame the BeaST"][taming], p. 24. The gist
-    /// is that the given name will start at the first word with a capital
-    /// letter, if there are any such words.
+    /// is that all words up to the last lowercase word before the family name
+    /// form the prefix. Braces protect their contents from this analysis and
+    /// are removed.
     ///
     /// The call site of this function in the library obtains the slice by
-    /// calling `split(",")` on a string like `"Des Egdens, Britta"`.
+    /// splitting a string like `"Des Egdens, Britta"` at its commas.
     ///
     /// [taming]: https://ftp.rrze.uni-erlangen.de/ctan/info/bibtex/tamethebeast/ttb_en.pdf
-    pub fn from_strings(mut parts: Vec<&str>) -> Result<Self, PersonError> {
+    pub fn from_strings(parts: Vec<&str>) -> Result<Self, PersonError> {
         if parts.is_empty() {
             return Err(PersonError::Empty);
         } else if parts.len() > 3 {
             return Err(PersonError::TooManyParts);
         }
 
-        for part in parts.iter_mut() {
-            *part = part.trim();
+        let words = split_words(parts[0]);
+        if words.is_empty() {
+            return Err(PersonError::Empty);
         }
 
-        let last_pre = parts[0];
-        let given_name =
-            if parts.len() > 1 { Some(parts.last().unwrap().to_string()) } else { None };
+        // The prefix reaches up to the last lowercase word, but the family
+        // name always keeps at least one word.
+        let split = words[..words.len() - 1]
+            .iter()
+            .rposition(|w| is_lowercase_word(w))
+            .map_or(0, |idx| idx + 1);
+
+        let part = |idx: usize| {
+            parts
+                .get(idx)
+                .map(|p| strip_braces(p.trim()))
+                .filter(|p| !p.is_empty())
+        };
 
-        let suffix = if parts.len() > 2 { Some(parts[1].to_string()) } else { None };
+        Ok(Person {
+            name: join_words(&words[split..]),
+            given_name: if parts.len() > 1 { part(parts.len() - 1) } else { None },
+            prefix: (split > 0).then(|| join_words(&words[..split])),
+            suffix: if parts.len() > 2 { part(1) } else { None },
+            ..Default::default()
+        })
+    }
 
-        let mut word_start = true;
-        let mut last_lower_case_end: i32 = -1;
-        let mut is_lowercase = false;
-        let mut last_word_start = 0;
-        let mut has_seen_uppercase_words = false;
+    /// Parses a name string in any of the three forms BibTeX understands:
+    /// `First von Last`, `von Last, First`, and `von Last, Jr, First`.
+    ///
+    /// Commas and whitespace within braces do not split the name, so
+    /// `"{Barnes and Noble, Inc.}"` yields a single family name. Lowercase
+    /// words before the family name become its prefix. In the `First von
+    /// Last` form, capitalized particles from a list of known particles
+    /// (like the _Van_ in `"Eddie Van Halen"`) start the family name.
+    pub fn parse_bibtex(name: &str) -> Result<Self, PersonError> {
+        Self::parse_name(name, true)
+    }
 
-        for (index, c) in last_pre.chars().enumerate() {
-            if c.is_whitespace() {
-                word_start = true;
-                continue;
-            }
+    /// Parses a name string. If `first_last` is false, a name without commas
+    /// is only split if it contains a particle and otherwise becomes the
+    /// family name, as is appropriate for institutions.
+    fn parse_name(name: &str, first_last: bool) -> Result<Self, PersonError> {
+        let parts = split_top_level(name, |c| c == ',');
+        if parts.len() > 1 {
+            return Self::from_strings(parts);
+        }
 
-            if word_start {
-                last_word_start = index;
+        let words = split_words(name);
+        if words.is_empty() {
+            return Err(PersonError::Empty);
+        }
 
-                if c.is_lowercase() {
-                    is_lowercase = true;
-                } else {
-                    is_lowercase = false;
-                    has_seen_uppercase_words = true;
+        let last = words.len() - 1;
+        let (given, prefix, family) =
+            match words[..last].iter().position(|w| is_lowercase_word(w)) {
+                // Lowercase words form the prefix.
+                Some(start) => {
+                    let end = start
+                        + words[start..last]
+                            .iter()
+                            .rposition(|w| is_lowercase_word(w))
+                            .unwrap();
+                    let prefix = &words[start..=end];
+                    if !first_last && !is_particle_phrase(prefix) {
+                        (&words[..0], &words[..0], &words[..])
+                    } else {
+                        (&words[..start], prefix, &words[end + 1..])
+                    }
                 }
-            }
+                // Capitalized particles start the family name.
+                None => match (1..last).find(|&i| is_capitalized_particle(words[i])) {
+                    Some(start) => (&words[..start], &words[..0], &words[start..]),
+                    None if first_last => (&words[..last], &words[..0], &words[last..]),
+                    None => (&words[..0], &words[..0], &words[..]),
+                },
+            };
 
-            if is_lowercase {
-                last_lower_case_end = index as i32;
-            }
+        let join = |words: &[&str]| (!words.is_empty()).then(|| join_words(words));
+        Ok(Person {
+            name: join_words(family),
+            given_name: join(given),
+            prefix: join(prefix),
+            ..Default::default()
+        })
+    }
 
-            word_start = false;
+    /// Writes the name as a string that parses back to the same person, if
+    /// there is one.
+    fn to_name_string(&self) -> Option<String> {
+        if self.alias.is_some()
+            || self.has_identifiers()
+            || self.non_dropping_particle.is_some()
+            || (self.suffix.is_some() && self.given_name.is_none())
+        {
+            return None;
         }
 
-        let mut name = String::new();
-        let mut prefix = String::new();
-        for (index, c) in last_pre.chars().enumerate() {
-            if (index as i32 <= last_lower_case_end && has_seen_uppercase_words)
-                || (!has_seen_uppercase_words && index < last_word_start)
-            {
-                prefix.push(c);
-            } else if has_seen_uppercase_words || index >= last_word_start {
-                name.push(c);
+        let candidate = |name: &str| {
+            let mut res = String::new();
+            if let Some(prefix) = &self.prefix {
+                res += prefix;
+                res.push(' ');
             }
-        }
-
-        let prefix = if prefix.is_empty() { None } else { Some(prefix) };
-        if prefix.is_some() {
-            name = name.trim_start().to_string();
-        }
+            res += name;
+            for part in [&self.suffix, &self.given_name].into_iter().flatten() {
+                res += ", ";
+                res += part;
+            }
+            res
+        };
 
-        Ok(Person { name, given_name, prefix, suffix, alias: None })
+        [candidate(&self.name), candidate(&format!("{{{}}}", self.name))]
+            .into_iter()
+            .find(|s| {
+                s.parse::<Self>().is_ok_and(|p| {
+                    p.name == self.name
+                        && p.given_name == self.given_name
+                        && p.prefix == self.prefix
+                        && p.suffix == self.suffix
+                })
+            })
     }
 
     /// Formats the given name into initials.
@@ -317,14 +457,10 @@ impl Person {
     /// Get the name with the family name fist, the initials
     /// afterwards, separated by a comma.
     pub fn name_first(&self, initials: bool, prefix_given_name: bool) -> String {
-        let mut res = if !prefix_given_name {
-            if let Some(prefix) = &self.prefix {
-                format!("{} {}", prefix, self.name)
-            } else {
-                self.name.clone()
-            }
-        } else {
-            self.name.clone()
+        let name = self.name_with_particle();
+        let mut res = match &self.prefix {
+            Some(prefix) if !prefix_given_name => format!("{} {}", prefix, name),
+            _ => name.into_owned(),
         };
 
         if initials {
@@ -373,7 +509,7 @@ impl Person {
             res += " ";
         }
 
-        res += &self.name;
+        res += &self.name_with_particle();
 
         if let Some(suffix) = &self.suffix {
             res += " ";
@@ -383,8 +519,68 @@ impl Person {
         res
     }
 
-    /// Get the non-dropping name particle in the family name.
+    /// Adds a translation of the name in the given language.
+    pub fn with_translation(
+        mut self,
+        language: LanguageIdentifier,
+        name: Person,
+    ) -> Self {
+        self.translations.insert(language, name);
+        self
+    }
+
+    /// Adds a transliteration of the name in the given language and script,
+    /// e.g. `ru-Latn`.
+    pub fn with_transliteration(
+        mut self,
+        language: LanguageIdentifier,
+        name: Person,
+    ) -> Self {
+        self.transliterations.insert(language, name);
+        self
+    }
+
+    /// Translations of the name, keyed by their language.
+    pub fn translations(&self) -> &BTreeMap<LanguageIdentifier, Person> {
+        &self.translations
+    }
+
+    /// Transliterations of the name, keyed by their language and script.
+    pub fn transliterations(&self) -> &BTreeMap<LanguageIdentifier, Person> {
+        &self.transliterations
+    }
+
+    /// Returns the given language variant of the name if it is available.
+    pub fn select_variant(&self, variant: &LanguageVariant) -> Option<&Person> {
+        match variant {
+            LanguageVariant::Original => Some(self),
+            LanguageVariant::Transliteration(tag) => {
+                lookup_variant(&self.transliterations, tag)
+            }
+            LanguageVariant::Translation(tag) => lookup_variant(&self.translations, tag),
+        }
+    }
+
+    /// Sets the non-dropping particle of the family name such as the 'van' in
+    /// 'Vincent van Gogh'.
+    pub fn with_non_dropping_particle(mut self, particle: impl Into<String>) -> Self {
+        self.non_dropping_particle = Some(particle.into());
+        self
+    }
+
+    /// The non-dropping particle of the family name if it was set explicitly.
+    /// Use [`name_particle`](Self::name_particle) to also detect known
+    /// particles at the start of the family name.
+    pub fn non_dropping_particle(&self) -> Option<&str> {
+        self.non_dropping_particle.as_deref()
+    }
+
+    /// Get the non-dropping name particle of the family name.
     pub fn name_particle(&self) -> Option<&str> {
+        if let Some(particle) = &self.non_dropping_particle {
+            return Some(particle);
+        }
+
         for (idx, char) in self.name.char_indices().rev() {
             if char != ' ' {
                 continue;
@@ -402,13 +598,23 @@ impl Person {
 
     /// Get the family name without the non-dropping particle.
     pub fn name_without_particle(&self) -> &str {
-        if let Some(particle) = self.name_particle() {
+        if self.non_dropping_particle.is_some() {
+            self.name.as_str()
+        } else if let Some(particle) = self.name_particle() {
             self.name[particle.len()..].trim_start()
         } else {
             self.name.as_str()
         }
     }
 
+    /// Get the family name with the non-dropping particle.
+    pub fn name_with_particle(&self) -> Cow<'_, str> {
+        match &self.non_dropping_particle {
+            Some(particle) => Cow::Owned(format!("{} {}", particle, self.name)),
+            None => Cow::Borrowed(&self.name),
+        }
+    }
+
     /// Get only the dropping and non-dropping particle of the family name.
     pub fn name_particles(&self) -> Option<Cow<str>> {
         match (&self.prefix, self.name_particle()) {
@@ -421,9 +627,70 @@ impl Person {
         }
     }
 
+    /// Sets a persistent identifier of the person.
+    pub fn with_identifier(
+        mut self,
+        kind: PersonIdentifier,
+        id: impl Into<String>,
+    ) -> Self {
+        let id = Some(id.into());
+        match kind {
+            PersonIdentifier::Orcid => self.orcid = id,
+            PersonIdentifier::Isni => self.isni = id,
+            PersonIdentifier::Wikidata => self.wikidata = id,
+        }
+        self
+    }
+
+    /// The persistent identifier of the given kind as it was specified.
+    pub fn identifier(&self, kind: PersonIdentifier) -> Option<&str> {
+        match kind {
+            PersonIdentifier::Orcid => self.orcid.as_deref(),
+            PersonIdentifier::Isni => self.isni.as_deref(),
+            PersonIdentifier::Wikidata => self.wikidata.as_deref(),
+        }
+    }
+
+    /// Whether the person has any persistent identifier.
+    pub fn has_identifiers(&self) -> bool {
+        self.identifiers().next().is_some()
+    }
+
+    /// The person's persistent identifiers in a normalized form, keyed by
+    /// their kind. URL forms like `https://orcid.org/...` are reduced to the
+    /// bare identifier.
+    pub fn identifiers(&self) -> impl Iterator<Item = (PersonIdentifier, String)> + '_ {
+        [
+            (PersonIdentifier::Orcid, &self.orcid),
+            (PersonIdentifier::Isni, &self.isni),
+            (PersonIdentifier::Wikidata, &self.wikidata),
+        ]
+        .into_iter()
+        .filter_map(|(kind, id)| Some((kind, kind.normalize(id.as_deref()?))))
+    }
+
+    /// Whether two persons are known to be the same or different persons
+    /// based on their identifiers. Returns `None` if they have no identifier
+    /// kind in common.
+    pub fn same_identity(&self, other: &Self) -> Option<bool> {
+        let mut res = None;
+        for (kind, id) in self.identifiers() {
+            if let Some((_, other_id)) = other.identifiers().find(|(k, _)| *k == kind) {
+                if other_id != id {
+                    return Some(false);
+                }
+                res = Some(true);
+            }
+        }
+        res
+    }
+
     /// Whether to treat this as an institutional name.
     pub fn is_institutional(&self) -> bool {
-        self.given_name.is_none() && self.suffix.is_none() && self.prefix.is_none()
+        self.given_name.is_none()
+            && self.suffix.is_none()
+            && self.prefix.is_none()
+            && self.non_dropping_particle.is_none()
     }
 
     /// Whether the name contains CJK characters.
@@ -454,14 +721,13 @@ impl Person {
         other: &Self,
         form: LongShortForm,
         demote_particle: bool,
+        collator: &Collator,
     ) -> std::cmp::Ordering {
         let self_cjk = self.is_cjk();
         let other_cjk = other.is_cjk();
 
-        let name_a = self.name.to_lowercase();
-        let name_b = other.name.to_lowercase();
-        let given_a = self.given_name.as_ref().map(|s| s.to_lowercase());
-        let given_b = other.given_name.as_ref().map(|s| s.to_lowercase());
+        let given_a = self.given_name.as_deref();
+        let given_b = other.given_name.as_deref();
 
         if self_cjk != other_cjk {
             // Put CJK names last.
@@ -469,35 +735,35 @@ impl Person {
         } else if self_cjk && other_cjk {
             // Apply special CJK rules.
             return match form {
-                LongShortForm::Long => {
-                    name_a.cmp(&name_b).then_with(|| given_a.cmp(&given_b))
-                }
-                LongShortForm::Short => name_a.cmp(&name_b),
+                LongShortForm::Long => collator
+                    .compare(&self.name, &other.name)
+                    .then_with(|| collator.compare_opt(given_a, given_b)),
+                LongShortForm::Short => collator.compare(&self.name, &other.name),
             };
         }
 
-        let prefix_a = self.prefix.as_ref().map(|s| s.to_lowercase());
-        let prefix_b = other.prefix.as_ref().map(|s| s.to_lowercase());
-        let suffix_a = self.suffix.as_ref().map(|s| s.to_lowercase());
-        let suffix_b = other.suffix.as_ref().map(|s| s.to_lowercase());
+        let suffix_a = self.suffix.as_deref();
+        let suffix_b = other.suffix.as_deref();
 
         if demote_particle {
-            self.name_without_particle()
-                .to_lowercase()
-                .cmp(&other.name_without_particle().to_lowercase())
+            collator
+                .compare(self.name_without_particle(), other.name_without_particle())
                 .then_with(|| {
-                    self.name_particles()
-                        .map(|c| c.to_lowercase())
-                        .cmp(&other.name_particles().map(|c| c.to_lowercase()))
+                    collator.compare_opt(
+                        self.name_particles().as_deref(),
+                        other.name_particles().as_deref(),
+                    )
                 })
-                .then_with(|| given_a.cmp(&given_b))
-                .then_with(|| suffix_a.cmp(&suffix_b))
+                .then_with(|| collator.compare_opt(given_a, given_b))
+                .then_with(|| collator.compare_opt(suffix_a, suffix_b))
         } else {
-            name_a
-                .cmp(&name_b)
-                .then_with(|| prefix_a.cmp(&prefix_b))
-                .then_with(|| given_a.cmp(&given_b))
-                .then_with(|| suffix_a.cmp(&suffix_b))
+            collator
+                .compare(&self.name_with_particle(), &other.name_with_particle())
+                .then_with(|| {
+                    collator.compare_opt(self.prefix.as_deref(), other.prefix.as_deref())
+                })
+                .then_with(|| collator.compare_opt(given_a, given_b))
+                .then_with(|| collator.compare_opt(suffix_a, suffix_b))
         }
     }
 }
@@ -509,6 +775,7 @@ impl Ord for Person {
             .then(self.given_name.cmp(&other.given_name))
             .then(self.suffix.cmp(&other.suffix))
             .then(self.prefix.cmp(&other.prefix))
+            .then(self.non_dropping_particle.cmp(&other.non_dropping_particle))
     }
 }
 
@@ -522,8 +789,100 @@ impl FromStr for Person {
     type Err = PersonError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::from_strings(s.split(',').collect())
+        Self::parse_name(s, false)
+    }
+}
+
+/// Splits a string at characters matching `pat` outside of braces. The
+/// resulting parts are trimmed and empty parts are dropped.
+fn split_top_level(s: &str, pat: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut escaped = false;
+    for (idx, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && pat(c) => {
+                parts.push(s[start..idx].trim());
+                start = idx + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(s[start..].trim());
+
+    if parts.iter().all(|p| p.is_empty()) {
+        return vec![];
+    }
+    parts
+}
+
+/// Splits a name part into its words at whitespace and ties.
+fn split_words(s: &str) -> Vec<&str> {
+    split_top_level(s, |c| c.is_whitespace() || c == '~')
+        .into_iter()
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Whether the first letter of a word outside of braces is lowercase. Words
+/// starting with a braced group are caseless.
+fn is_lowercase_word(word: &str) -> bool {
+    for c in word.chars() {
+        if c == '{' {
+            return false;
+        } else if c.is_alphabetic() {
+            return c.is_lowercase();
+        }
+    }
+    false
+}
+
+/// Whether a capitalized word is a known name particle. Single letters are
+/// excluded since they are usually initials.
+fn is_capitalized_particle(word: &str) -> bool {
+    word.chars().next().is_some_and(char::is_uppercase)
+        && word.chars().count() > 1
+        && is_particle_phrase(&[word])
+}
+
+/// Whether a sequence of words is a known name particle, either as a whole or
+/// word by word. Words like "and" or "of" only count as part of a longer
+/// particle, so that institutions are not split.
+fn is_particle_phrase(words: &[&str]) -> bool {
+    let is_particle = |s: &str| NAME_PARTICLES.binary_search(&s).is_ok();
+    let lower: Vec<_> = words.iter().map(|w| w.to_lowercase()).collect();
+    (words.len() > 1 && is_particle(&lower.join(" ")))
+        || lower
+            .iter()
+            .all(|w| !CONJUNCTIONS.contains(&w.as_str()) && is_particle(w))
+}
+
+/// Particles that usually appear as conjunctions or prepositions in the names
+/// of institutions.
+const CONJUNCTIONS: [&str; 6] = ["and", "en", "et", "in", "of", "und"];
+
+/// Joins name words with spaces and removes protecting braces.
+fn join_words(words: &[&str]) -> String {
+    strip_braces(&words.join(" "))
+}
+
+/// Removes unescaped braces from a string.
+fn strip_braces(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut escaped = false;
+    for c in s.chars() {
+        match c {
+            '{' | '}' if !escaped => {}
+            _ => res.push(c),
+        }
+        escaped = c == '\\' && !escaped;
     }
+    res
 }
 
 #[cfg(test)]
@@ -554,6 +913,23 @@ mod tests {
         assert_eq!("Von Der", p.name_particle().unwrap());
         assert_eq!("Leyen", p.name_without_particle());
     }
+    #[test]
+    fn institution_sorting() {
+        use citationberg::{LocaleCode, LongShortForm};
+        use std::cmp::Ordering;
+
+        use crate::lang::collation::Collator;
+
+        // Articles are only ignored in titles, so this files under "T".
+        let collator = Collator::new(&LocaleCode::en_us());
+        let society = Person::from_strings(vec!["The Royal Society"]).unwrap();
+        let council = Person::from_strings(vec!["Science Council"]).unwrap();
+        assert_eq!(
+            society.csl_cmp(&council, LongShortForm::Long, true, &collator),
+            Ordering::Greater
+        );
+    }
+
     #[test]
     fn person_middle_initial() {
         let p = Person::from_strings(vec!["Kirk", "James T"]).unwrap();
@@ -561,4 +937,79 @@ mod tests {
         p.first_name_with_delimiter(&mut s, Some(".")).unwrap();
         assert_eq!("James T.", s);
     }
+
+    #[track_caller]
+    fn assert_parts(
+        p: &Person,
+        name: &str,
+        given: Option<&str>,
+        prefix: Option<&str>,
+        suffix: Option<&str>,
+    ) {
+        assert_eq!(name, p.name);
+        assert_eq!(given, p.given_name.as_deref());
+        assert_eq!(prefix, p.prefix.as_deref());
+        assert_eq!(suffix, p.suffix.as_deref());
+    }
+
+    #[test]
+    fn person_bibtex() {
+        let p = Person::parse_bibtex("Ludwig van Beethoven").unwrap();
+        assert_parts(&p, "Beethoven", Some("Ludwig"), Some("van"), None);
+
+        let p = Person::parse_bibtex("de la Fontaine, Jean").unwrap();
+        assert_parts(&p, "Fontaine", Some("Jean"), Some("de la"), None);
+
+        let p = Person::parse_bibtex("King, Jr., Martin Luther").unwrap();
+        assert_parts(&p, "King", Some("Martin Luther"), None, Some("Jr."));
+
+        let p = Person::parse_bibtex("{Barnes and Noble, Inc.}").unwrap();
+        assert_parts(&p, "Barnes and Noble, Inc.", None, None, None);
+
+        let p = Person::parse_bibtex("Eddie Van Halen").unwrap();
+        assert_parts(&p, "Van Halen", Some("Eddie"), None, None);
+        assert_eq!(Some("Van"), p.name_particle());
+
+        let p = Person::parse_bibtex("Jean~de~La~Fontaine").unwrap();
+        assert_parts(&p, "La Fontaine", Some("Jean"), Some("de"), None);
+
+        let p = Person::parse_bibtex("John A. Smith").unwrap();
+        assert_parts(&p, "Smith", Some("John A."), None, None);
+
+        let p = Person::parse_bibtex("{von Neumann}, John").unwrap();
+        assert_parts(&p, "von Neumann", Some("John"), None, None);
+
+        assert!(Person::parse_bibtex(" ").is_err());
+        assert!(Person::parse_bibtex("a, b, c, d").is_err());
+    }
+
+    #[test]
+    fn person_from_str() {
+        let p: Person = "Ludwig van Beethoven".parse().unwrap();
+        assert_parts(&p, "Beethoven", Some("Ludwig"), Some("van"), None);
+
+        // Braces keep institutions with particles in their names together.
+        for s in ["Université de Montréal", "Banco de España", "Museo del Prado"] {
+            let p: Person = format!("{{{s}}}").parse().unwrap();
+            assert_parts(&p, s, None, None, None);
+        }
+
+        let p: Person = "United Nations Development Programme".parse().unwrap();
+        assert_parts(&p, "United Nations Development Programme", None, None, None);
+
+        let p: Person = "Food and Agriculture Organization".parse().unwrap();
+        assert_parts(&p, "Food and Agriculture Organization", None, None, None);
+
+        let p: Person = "{Barnes and Noble, Inc.}".parse().unwrap();
+        assert_parts(&p, "Barnes and Noble, Inc.", None, None, None);
+
+        for s in [
+            "King, Jr., Martin Luther",
+            "van Beethoven, Ludwig",
+            "{Barnes and Noble, Inc.}",
+        ] {
+            let p: Person = s.parse().unwrap();
+            assert_eq!(Some(s.into()), p.to_name_string());
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::Display;
 use std::str::FromStr;
@@ -6,19 +7,27 @@ use std::str::FromStr;
 use citationberg::LongShortForm;
 use serde::{de::Visitor, ser::SerializeMap, Deserialize, Serialize};
 use thiserror::Error;
+use unic_langid::LanguageIdentifier;
 use unscanny::Scanner;
 
+use super::{lookup_variant, LanguageVariant};
 use crate::lang::{Case, CaseFolder, SentenceCase, TitleCase};
 
 /// A string for presentation.
 ///
-/// It can contain an optional short version and control case folding.
+/// It can contain an optional short version, translations and
+/// transliterations, and control case folding.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct FormatString {
     /// The canonical version of the string.
     pub value: ChunkedString,
     /// The short version of the string.
     pub short: Option<Box<ChunkedString>>,
+    /// Translations of the string, keyed by their language.
+    pub(crate) translations: BTreeMap<LanguageIdentifier, ChunkedString>,
+    /// Transliterations of the string, keyed by their language and script,
+    /// e.g. `ru-Latn`.
+    pub(crate) transliterations: BTreeMap<LanguageIdentifier, ChunkedString>,
 }
 
 impl<'de> Deserialize<'de> for FormatString {
@@ -52,15 +61,27 @@ impl<'de> Deserialize<'de> for FormatString {
                 #[derive(Deserialize)]
                 #[serde(untagged)]
                 enum Inner {
-                    Full { value: ChunkedString, short: Option<ChunkedString> },
+                    Full {
+                        value: ChunkedString,
+                        short: Option<ChunkedString>,
+                        #[serde(default)]
+                        translations: BTreeMap<LanguageIdentifier, ChunkedString>,
+                        #[serde(default)]
+                        transliterations: BTreeMap<LanguageIdentifier, ChunkedString>,
+                    },
                     Val(ChunkedString),
                 }
 
                 Deserialize::deserialize(de::value::MapAccessDeserializer::new(map)).map(
                     |inner: Inner| match inner {
-                        Inner::Val(value) => Self::Value { value, short: None },
-                        Inner::Full { value, short } => {
-                            Self::Value { value, short: short.map(Box::new) }
+                        Inner::Val(value) => Self::Value { value, ..Default::default() },
+                        Inner::Full { value, short, translations, transliterations } => {
+                            Self::Value {
+                                value,
+                                short: short.map(Box::new),
+                                translations,
+                                transliterations,
+                            }
                         }
                     },
                 )
@@ -76,10 +97,21 @@ impl Serialize for FormatString {
     where
         S: serde::Serializer,
     {
-        if let Some(short) = &self.short {
-            let mut map = serializer.serialize_map(Some(2))?;
+        if self.short.is_some()
+            || !self.translations.is_empty()
+            || !self.transliterations.is_empty()
+        {
+            let mut map = serializer.serialize_map(None)?;
             map.serialize_entry("value", &self.value)?;
-            map.serialize_entry("short", short)?;
+            if let Some(short) = &self.short {
+                map.serialize_entry("short", short)?;
+            }
+            if !self.translations.is_empty() {
+                map.serialize_entry("translations", &self.translations)?;
+            }
+            if !self.transliterations.is_empty() {
+                map.serialize_entry("transliterations", &self.transliterations)?;
+            }
             map.end()
         } else {
             self.value.serialize(serializer)
@@ -97,7 +129,7 @@ impl FormatString {
     pub fn with_value(value: impl Into<String>) -> Self {
         Self {
             value: StringChunk::normal(value).into(),
-            short: None,
+            ..Default::default()
         }
     }
 
@@ -106,9 +138,41 @@ impl FormatString {
         Self {
             value: StringChunk::normal(value).into(),
             short: Some(Box::new(StringChunk::normal(short).into())),
+            ..Default::default()
         }
     }
 
+    /// Adds a translation of the string in the given language.
+    pub fn with_translation(
+        mut self,
+        language: LanguageIdentifier,
+        value: impl Into<ChunkedString>,
+    ) -> Self {
+        self.translations.insert(language, value.into());
+        self
+    }
+
+    /// Adds a transliteration of the string in the given language and
+    /// script, e.g. `ru-Latn`.
+    pub fn with_transliteration(
+        mut self,
+        language: LanguageIdentifier,
+        value: impl Into<ChunkedString>,
+    ) -> Self {
+        self.transliterations.insert(language, value.into());
+        self
+    }
+
+    /// Translations of the string, keyed by their language.
+    pub fn translations(&self) -> &BTreeMap<LanguageIdentifier, ChunkedString> {
+        &self.translations
+    }
+
+    /// Transliterations of the string, keyed by their language and script.
+    pub fn transliterations(&self) -> &BTreeMap<LanguageIdentifier, ChunkedString> {
+        &self.transliterations
+    }
+
     /// Format the long version of the string as-is.
     fn fmt_long(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.value.fmt(buf)
@@ -139,6 +203,22 @@ impl FormatString {
             LongShortForm::Short => self.short.as_deref().unwrap_or(&self.value),
         }
     }
+
+    /// Returns the given language variant of the string if it is available.
+    /// Translations and transliterations have no short form.
+    pub fn select_variant(
+        &self,
+        form: LongShortForm,
+        variant: &LanguageVariant,
+    ) -> Option<&ChunkedString> {
+        match variant {
+            LanguageVariant::Original => Some(self.select(form)),
+            LanguageVariant::Transliteration(tag) => {
+                lookup_variant(&self.transliterations, tag)
+            }
+            LanguageVariant::Translation(tag) => lookup_variant(&self.translations, tag),
+        }
+    }
 }
 
 impl fmt::Display for FormatString {
@@ -155,7 +235,7 @@ impl From<String> for FormatString {
 
 impl From<StringChunk> for FormatString {
     fn from(chunk: StringChunk) -> Self {
-        Self { value: chunk.into(), short: None }
+        Self { value: chunk.into(), ..Default::default() }
     }
 }
 
@@ -163,7 +243,10 @@ impl FromStr for FormatString {
     type Err = ChunkedStrParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self { value: ChunkedString::from_str(s)?, short: None })
+        Ok(Self {
+            value: ChunkedString::from_str(s)?,
+            ..Default::default()
+        })
     }
 }
 
@@ -260,25 +343,25 @@ impl ChunkedString {
     /// Appends a string to the last chunk if it has the same kind or starts a
     /// new chunk if the types differ.
     pub fn push_str(&mut self, s: &str, kind: ChunkKind) {
-        match self.0.last_mut() {
-            Some(StringChunk { value, kind: target_kind }) if target_kind == &kind => {
-                value.push_str(s);
-            }
-            _ => {
-                self.0.push(StringChunk::new(s, kind));
-            }
-        }
+        self.push_formatted(s, kind, ChunkFormat::default());
     }
 
     /// Appends a character to the last chunk if it has the same kind or starts
     /// a new chunk if the types differ.
     pub fn push_char(&mut self, c: char, kind: ChunkKind) {
+        let mut buf = [0; 4];
+        self.push_str(c.encode_utf8(&mut buf), kind);
+    }
+
+    /// Appends a string with inline formatting to the last chunk if it has the
+    /// same kind and formatting or starts a new chunk otherwise.
+    pub fn push_formatted(&mut self, s: &str, kind: ChunkKind, format: ChunkFormat) {
         match self.0.last_mut() {
-            Some(StringChunk { value, kind: target_kind }) if target_kind == &kind => {
-                value.push(c);
+            Some(last) if last.kind == kind && last.format == format => {
+                last.value.push_str(s);
             }
             _ => {
-                self.0.push(StringChunk::new(c.to_string(), kind));
+                self.0.push(StringChunk::new(s, kind).with_format(format));
             }
         }
     }
@@ -300,12 +383,33 @@ impl ChunkedString {
         }
     }
 
+    /// Parse a string with CSL's rich-text markup, as used in CSL-JSON. Unlike
+    /// [`FromStr`], braces and dollar signs have no special meaning.
+    pub fn from_rich_text(s: &str) -> Self {
+        parse_chunks(s, false).unwrap_or_default()
+    }
+
     /// Write the chunked string as a parenthesized string.
     pub fn fmt_serialized(&self, buf: &mut impl fmt::Write) -> fmt::Result {
+        let mut open: Vec<MarkupTag> = vec![];
         for chunk in &self.0 {
+            let tags = chunk.format.tags();
+            let keep = open.iter().zip(&tags).take_while(|(a, b)| a == b).count();
+            for tag in open.drain(keep..).rev() {
+                buf.write_str(tag.close())?;
+            }
+            for &tag in &tags[keep..] {
+                buf.write_str(tag.open())?;
+                open.push(tag);
+            }
+
             chunk.fmt_serialized(buf)?;
         }
 
+        for tag in open.into_iter().rev() {
+            buf.write_str(tag.close())?;
+        }
+
         Ok(())
     }
 
@@ -383,45 +487,75 @@ impl FromStr for ChunkedString {
     type Err = ChunkedStrParseError;
 
     fn from_str(s: &str) -> Result<Self, ChunkedStrParseError> {
-        let mut s = Scanner::new(s);
-        let mut chunks = Self::new();
-        let mut kind = ChunkKind::Normal;
-        let mut depth = 0;
-
-        while let Some(c) = s.eat() {
-            if c == '\\' && s.peek().is_some_and(is_chunk_control) {
-                chunks.push_char(s.eat().unwrap(), kind);
-                continue;
-            }
+        parse_chunks(s, true)
+    }
+}
 
-            match c {
-                '{' if kind != ChunkKind::Math => {
-                    depth += 1;
-                    kind = ChunkKind::Verbatim;
+/// Parse a string into chunks. Rich-text tags are always recognized, braces,
+/// dollar signs, and backslash escapes only if `latex` is set.
+fn parse_chunks(s: &str, latex: bool) -> Result<ChunkedString, ChunkedStrParseError> {
+    let mut s = Scanner::new(s);
+    let mut chunks = ChunkedString::new();
+    let mut math = false;
+    let mut depth = 0;
+    let mut tags: Vec<MarkupTag> = vec![];
+
+    loop {
+        let kind = if math {
+            ChunkKind::Math
+        } else if depth > 0 || tags.contains(&MarkupTag::NoCase) {
+            ChunkKind::Verbatim
+        } else {
+            ChunkKind::Normal
+        };
+        let format = ChunkFormat::from_tags(&tags);
+
+        if !math {
+            match MarkupTag::parse(s.after()) {
+                Some((Tag::Open(tag), len)) => {
+                    s.jump(s.cursor() + len);
+                    tags.push(tag);
+                    continue;
                 }
-                '}' if kind != ChunkKind::Math => {
-                    if depth == 0 {
-                        return Err(ChunkedStrParseError(s.cursor().saturating_sub(1)));
+                Some((Tag::Close(close), len)) => {
+                    if let Some(i) = tags.iter().rposition(|t| t.close() == close) {
+                        s.jump(s.cursor() + len);
+                        tags.remove(i);
+                        continue;
                     }
-
-                    depth -= 1;
-                    if depth == 0 {
-                        kind = ChunkKind::Normal;
-                    }
-                }
-                '$' if kind == ChunkKind::Math => {
-                    kind =
-                        if depth > 0 { ChunkKind::Verbatim } else { ChunkKind::Normal };
-                }
-                '$' => {
-                    kind = ChunkKind::Math;
                 }
-                _ => chunks.push_char(c, kind),
+                None => {}
             }
         }
 
-        Ok(chunks)
+        let Some(c) = s.eat() else { break };
+
+        if !latex {
+            chunks.push_formatted(c.encode_utf8(&mut [0; 4]), kind, format);
+            continue;
+        }
+
+        if c == '\\' && s.peek().is_some_and(|c| is_chunk_control(c) || c == '<') {
+            let c = s.eat().unwrap();
+            chunks.push_formatted(c.encode_utf8(&mut [0; 4]), kind, format);
+            continue;
+        }
+
+        match c {
+            '{' if !math => depth += 1,
+            '}' if !math => {
+                if depth == 0 {
+                    return Err(ChunkedStrParseError(s.cursor().saturating_sub(1)));
+                }
+
+                depth -= 1;
+            }
+            '$' => math = !math,
+            _ => chunks.push_formatted(c.encode_utf8(&mut [0; 4]), kind, format),
+        }
     }
+
+    Ok(chunks)
 }
 
 impl From<String> for ChunkedString {
@@ -465,6 +599,8 @@ pub struct StringChunk {
     pub value: String,
     /// Whether the chunk is subject to case folding or contains math.
     pub kind: ChunkKind,
+    /// The inline formatting of the chunk.
+    pub(crate) format: ChunkFormat,
 }
 
 /// A chunk of a string.
@@ -474,26 +610,73 @@ pub struct FoldableStringChunk {
     pub value: String,
     /// Whether the chunk is subject to case folding or contains math.
     pub kind: FoldableKind,
+    /// The inline formatting of the chunk.
+    pub(crate) format: ChunkFormat,
 }
 
 impl TryFrom<StringChunk> for FoldableStringChunk {
     type Error = ();
 
     fn try_from(value: StringChunk) -> Result<Self, Self::Error> {
-        Ok(Self { value: value.value, kind: value.kind.try_into()? })
+        Ok(Self {
+            value: value.value,
+            kind: value.kind.try_into()?,
+            format: value.format,
+        })
+    }
+}
+
+impl FoldableStringChunk {
+    /// Creates a new `FoldableStringChunk` from a string and a kind.
+    pub fn new(value: impl Into<String>, kind: FoldableKind) -> Self {
+        Self {
+            value: value.into(),
+            kind,
+            format: ChunkFormat::default(),
+        }
+    }
+
+    /// Sets the inline formatting of the chunk.
+    pub fn with_format(mut self, format: ChunkFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// The inline formatting of the chunk.
+    pub fn format(&self) -> ChunkFormat {
+        self.format
     }
 }
 
 impl From<FoldableStringChunk> for StringChunk {
     fn from(value: FoldableStringChunk) -> Self {
-        Self { value: value.value, kind: value.kind.into() }
+        Self {
+            value: value.value,
+            kind: value.kind.into(),
+            format: value.format,
+        }
     }
 }
 
 impl StringChunk {
     /// Creates a new `StrChunk` from a string and a kind.
     pub fn new(value: impl Into<String>, kind: ChunkKind) -> Self {
-        Self { value: value.into(), kind }
+        Self {
+            value: value.into(),
+            kind,
+            format: ChunkFormat::default(),
+        }
+    }
+
+    /// Sets the inline formatting of the chunk.
+    pub fn with_format(mut self, format: ChunkFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// The inline formatting of the chunk.
+    pub fn format(&self) -> ChunkFormat {
+        self.format
     }
 
     /// Creates a new `StrChunk` with the `ChunkKind::Normal` kind.
@@ -548,8 +731,10 @@ impl StringChunk {
     /// Writes the chunk as a parenthesized string.
     fn fmt_serialized(&self, buf: &mut impl fmt::Write) -> fmt::Result {
         fn write_escaped(val: &StringChunk, buf: &mut impl fmt::Write) -> fmt::Result {
-            for char in val.value.chars() {
-                if is_chunk_control(char) {
+            for (i, char) in val.value.char_indices() {
+                if is_chunk_control(char)
+                    || (char == '<' && MarkupTag::parse(&val.value[i..]).is_some())
+                {
                     buf.write_char('\\')?;
                 }
 
@@ -588,10 +773,137 @@ pub enum ChunkKind {
     /// Case-folding will not be applied.
     Verbatim,
     /// The contained markup is expected to be evaluated using
-    /// [Typst](https://typst.app/).
+    /// [Typst](https://typst.app/). When writing output, it is rendered by a
+    /// [`MathRenderer`](crate::MathRenderer).
     Math,
 }
 
+/// The inline formatting of a string chunk.
+///
+/// Follows CSL's rich-text markup: `<i>`, `<b>`, `<sup>`, `<sub>`, and
+/// `<span style="font-variant:small-caps;">`. Italics, boldface, and small
+/// caps flip-flop: they are reversed if the surrounding text already has them.
+/// `<span class="nocase">` makes a chunk [`ChunkKind::Verbatim`] instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ChunkFormat {
+    /// Whether the chunk is emphasized with italics.
+    pub italic: bool,
+    /// Whether the chunk is bold.
+    pub bold: bool,
+    /// Whether the chunk is set in small caps.
+    pub small_caps: bool,
+    /// Whether the chunk is superscript.
+    pub superscript: bool,
+    /// Whether the chunk is subscript.
+    pub subscript: bool,
+}
+
+impl ChunkFormat {
+    /// Whether the chunk has no inline formatting.
+    pub fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The formatting within the given open tags. Nested italics, boldface,
+    /// and small caps cancel each other out.
+    fn from_tags(tags: &[MarkupTag]) -> Self {
+        let mut format = Self::default();
+        for tag in tags {
+            match tag {
+                MarkupTag::Italic => format.italic = !format.italic,
+                MarkupTag::Bold => format.bold = !format.bold,
+                MarkupTag::SmallCaps => format.small_caps = !format.small_caps,
+                MarkupTag::Superscript => format.superscript = true,
+                MarkupTag::Subscript => format.subscript = true,
+                MarkupTag::NoCase => {}
+            }
+        }
+        format
+    }
+
+    /// The tags that produce this formatting, in a fixed order.
+    fn tags(&self) -> Vec<MarkupTag> {
+        [
+            (self.bold, MarkupTag::Bold),
+            (self.italic, MarkupTag::Italic),
+            (self.small_caps, MarkupTag::SmallCaps),
+            (self.superscript, MarkupTag::Superscript),
+            (self.subscript, MarkupTag::Subscript),
+        ]
+        .into_iter()
+        .filter_map(|(set, tag)| set.then_some(tag))
+        .collect()
+    }
+}
+
+/// A rich-text tag in a [`ChunkedString`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum MarkupTag {
+    Italic,
+    Bold,
+    SmallCaps,
+    Superscript,
+    Subscript,
+    NoCase,
+}
+
+/// An opening or closing rich-text tag.
+enum Tag {
+    Open(MarkupTag),
+    Close(&'static str),
+}
+
+impl MarkupTag {
+    const ALL: [Self; 6] = [
+        Self::Italic,
+        Self::Bold,
+        Self::SmallCaps,
+        Self::Superscript,
+        Self::Subscript,
+        Self::NoCase,
+    ];
+
+    /// The opening tag.
+    fn open(self) -> &'static str {
+        match self {
+            Self::Italic => "<i>",
+            Self::Bold => "<b>",
+            Self::SmallCaps => "<span style=\"font-variant:small-caps;\">",
+            Self::Superscript => "<sup>",
+            Self::Subscript => "<sub>",
+            Self::NoCase => "<span class=\"nocase\">",
+        }
+    }
+
+    /// The closing tag.
+    fn close(self) -> &'static str {
+        match self {
+            Self::Italic => "</i>",
+            Self::Bold => "</b>",
+            Self::SmallCaps | Self::NoCase => "</span>",
+            Self::Superscript => "</sup>",
+            Self::Subscript => "</sub>",
+        }
+    }
+
+    /// Parse a tag at the start of the string and return its length.
+    fn parse(s: &str) -> Option<(Tag, usize)> {
+        if !s.starts_with('<') {
+            return None;
+        }
+
+        Self::ALL.into_iter().find_map(|tag| {
+            if s.starts_with(tag.open()) {
+                Some((Tag::Open(tag), tag.open().len()))
+            } else if s.starts_with(tag.close()) {
+                Some((Tag::Close(tag.close()), tag.close().len()))
+            } else {
+                None
+            }
+        })
+    }
+}
+
 /// The kind of a string chunk for use with the case folder.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum FoldableKind {
@@ -680,4 +992,36 @@ mod tests {
         c.fmt_serialized(&mut buf).unwrap();
         assert_eq!(buf, str2);
     }
+
+    #[test]
+    fn rich_text() {
+        let str = "On <i>E. coli</i> and {DNA}<sup>2</sup>";
+        let c = ChunkedString::from_str(str).unwrap();
+        assert_eq!(c.to_string(), "On E. coli and DNA2");
+        assert!(c.0[1].format.italic);
+        assert_eq!(c.0[1].value, "E. coli");
+        assert_eq!(c.0[3].kind, ChunkKind::Verbatim);
+        assert!(c.0[4].format.superscript);
+
+        let mut buf = String::new();
+        c.fmt_serialized(&mut buf).unwrap();
+        assert_eq!(buf, str);
+
+        // Nested italics flip back and unknown or unmatched tags are text.
+        let c = ChunkedString::from_str("<i>The <i>Titanic</i></i> <em>a</b>").unwrap();
+        assert!(c.0[0].format.italic);
+        assert!(c.0[1].format.is_plain());
+        assert_eq!(c.0[1].value, "Titanic <em>a</b>");
+
+        let c = ChunkedString::from_str("\\<i>a<span class=\"nocase\">B</span>").unwrap();
+        assert_eq!(c.0[0].value, "<i>a");
+        assert_eq!(c.0[1].kind, ChunkKind::Verbatim);
+        let mut buf = String::new();
+        c.fmt_serialized(&mut buf).unwrap();
+        assert_eq!(buf, "\\<i>a{B}");
+
+        let c = ChunkedString::from_rich_text("a {b} $c$ <b>d</b>");
+        assert_eq!(c.0[0].value, "a {b} $c$ ");
+        assert!(c.0[1].format.bold);
+    }
 }
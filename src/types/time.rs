@@ -8,7 +8,7 @@ use serde::{de, Deserialize, Serialize};
 use thiserror::Error;
 use unscanny::Scanner;
 
-use super::{derive_or_from_str, deserialize_from_str, serialize_display};
+use super::{derive_or_from_str, deserialize_from_str};
 
 /// A date that can be as coarse as a year and as fine-grained as a day.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -313,6 +313,14 @@ impl Serialize for Date {
 }
 
 /// A duration.
+///
+/// Durations can be written as `MM:SS`, `HH:MM:SS`, or `D:HH:MM:SS`. Seconds
+/// can have a fraction like in `01:02:03.5` or `01:02:03,500`. SMPTE timecodes
+/// like `01:02:03:12` give a frame number in the fourth field. They are told
+/// apart from days by their hours, which have two digits and are below 24.
+/// The frames can also follow a semicolon, as in `01:02:03;12`. The first
+/// field can exceed its usual bounds, e.g. `130:00:00` for 130 hours. ISO 8601
+/// durations like `PT1H30M` or `P2DT4H` are accepted, too.
 #[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
 pub struct Duration {
     /// Days (24 hours).
@@ -325,113 +333,185 @@ pub struct Duration {
     pub seconds: u8,
     /// Milliseconds.
     pub milliseconds: u16,
+    /// The frame number of an SMPTE timecode. Its length depends on the frame
+    /// rate of the medium.
+    pub(crate) frames: Option<u8>,
 }
 
-serialize_display!(Duration);
 deserialize_from_str!(Duration);
 
+impl Serialize for Duration {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_serialized())
+    }
+}
+
 impl Duration {
     fn scan(s: &mut Scanner, require_end: bool) -> Result<Self, DurationError> {
-        let mut days = 0;
-        let mut hours = 0;
-        let mut minutes = 0;
-        let mut seconds = 0;
-        let mut milliseconds = 0;
-
-        let idx = s.cursor();
-        let colons =
-            s.eat_while(|c: char| c != '-').chars().filter(|c| c == &':').count();
-        s.jump(idx);
+        s.eat_whitespace();
+        let duration =
+            if s.eat_if('P') { Self::scan_iso(s)? } else { Self::scan_clock(s)? };
 
-        if !(1..=3).contains(&colons) {
+        s.eat_whitespace();
+        if require_end && !s.done() {
             return Err(DurationError::Malformed);
         }
 
-        let start = 3 - colons;
-
-        for i in start..5 {
-            match i {
-                0 => days = parse_unsigned_int(s, 1..).ok_or(DurationError::Malformed)?,
-                1 => {
-                    hours = if start == i {
-                        parse_unsigned_int(s, 2..)
-                    } else {
-                        parse_unsigned_int(s, 2..=2)
-                    }
-                    .ok_or(DurationError::Malformed)?
-                }
-                2 => {
-                    minutes = if start == i {
-                        parse_unsigned_int(s, 2..)
-                    } else {
-                        parse_unsigned_int(s, 2..=2)
-                    }
-                    .ok_or(DurationError::Malformed)?
-                }
-                3 => {
-                    seconds =
-                        parse_unsigned_int(s, 2..=2).ok_or(DurationError::Malformed)?
-                }
-                4 => {
-                    s.eat_whitespace();
-                    let num = s.eat_while(char::is_numeric);
-                    if num.is_empty() {
-                        return Err(DurationError::Malformed);
-                    }
-                    let str = format!("0.{}", num);
-                    let ms: f64 = str.parse().map_err(|_| DurationError::Malformed)?;
-                    milliseconds = (ms * 1000.0).round() as u16;
-                }
-                _ => unreachable!(),
-            }
+        Ok(duration)
+    }
 
-            if s.done() {
-                if i < 3 {
-                    return Err(DurationError::Malformed);
-                } else {
-                    break;
-                }
+    /// Scan a duration of the form `[[D:]HH:]MM:SS[.fff]` or a timecode of the
+    /// form `HH:MM:SS:FF` or `HH:MM:SS;FF`.
+    fn scan_clock(s: &mut Scanner) -> Result<Self, DurationError> {
+        let mut fields: Vec<u64> = vec![];
+        let mut first_len = 0;
+        loop {
+            s.eat_whitespace();
+            let digits = s.eat_while(|c: char| c.is_ascii_digit());
+            if digits.is_empty() || (!fields.is_empty() && digits.len() != 2) {
+                return Err(DurationError::Malformed);
+            } else if fields.is_empty() {
+                first_len = digits.len();
             }
 
+            fields.push(digits.parse().map_err(|_| DurationError::TooLarge)?);
             s.eat_whitespace();
 
-            if i < 3 && !s.eat_if(':') {
+            if fields.len() == 4 || !s.eat_if(':') {
+                break;
+            }
+        }
+
+        // Four fields are a timecode if the first one looks like the hours of
+        // a timecode. Otherwise, they start with the days.
+        let mut frames = None;
+        let mut milliseconds = 0;
+        if fields.len() == 4 && first_len == 2 && fields[0] < 24 {
+            frames = fields.pop();
+        } else if fields.len() == 3 && s.eat_if(';') {
+            s.eat_whitespace();
+            let digits = s.eat_while(|c: char| c.is_ascii_digit());
+            if digits.len() != 2 {
                 return Err(DurationError::Malformed);
-            } else if i == 3 {
-                if s.peek() == Some('-') {
-                    break;
-                }
-                if !s.eat_if(",") {
-                    return Err(DurationError::Malformed);
-                }
             }
+            frames = Some(digits.parse().map_err(|_| DurationError::Malformed)?);
+        } else if s.eat_if(',') || s.eat_if('.') {
+            s.eat_whitespace();
+            milliseconds = parse_fraction(s)?;
         }
 
-        s.eat_whitespace();
-        if require_end && !s.done() {
-            return Err(DurationError::Malformed);
+        let (days, hours, minutes, seconds) = match fields[..] {
+            [minutes, seconds] => (0, 0, minutes, seconds),
+            [hours, minutes, seconds] => (0, hours, minutes, seconds),
+            [days, hours, minutes, seconds] => (days, hours, minutes, seconds),
+            _ => return Err(DurationError::Malformed),
+        };
+
+        // Only the left-most field can exceed its usual bounds.
+        if (fields.len() == 4 && hours >= 24)
+            || (fields.len() >= 3 && minutes >= 60)
+            || seconds >= 60
+        {
+            return Err(DurationError::TooLarge);
         }
 
-        for i in (0..=start).rev() {
-            match i {
-                0 => {}
-                1 => {
-                    days = hours / 24;
-                    hours %= 24;
-                }
-                2 => {
-                    hours += minutes / 60;
-                    minutes %= 60;
+        let hours = days
+            .checked_mul(24)
+            .and_then(|h| h.checked_add(hours))
+            .ok_or(DurationError::TooLarge)?;
+        Self::from_parts(hours, minutes, seconds, milliseconds, frames)
+    }
+
+    /// Scan an ISO 8601 duration like `PT1H30M` after its leading `P`.
+    fn scan_iso(s: &mut Scanner) -> Result<Self, DurationError> {
+        let (mut days, mut hours, mut minutes, mut seconds) = (0, 0, 0, 0);
+        let mut milliseconds = 0;
+        let mut time = false;
+        let mut last = 0;
+
+        loop {
+            if !time && s.eat_if('T') {
+                time = true;
+            }
+
+            let digits = s.eat_while(|c: char| c.is_ascii_digit());
+            if digits.is_empty() {
+                break;
+            }
+
+            let value: u64 = digits.parse().map_err(|_| DurationError::TooLarge)?;
+            let fraction = if time && (s.eat_if('.') || s.eat_if(',')) {
+                Some(parse_fraction(s)?)
+            } else {
+                None
+            };
+
+            // Years and months are not accepted because their length varies.
+            let unit = match (time, s.eat()) {
+                (false, Some('W')) => 1,
+                (false, Some('D')) => 2,
+                (true, Some('H')) => 3,
+                (true, Some('M')) => 4,
+                (true, Some('S')) => 5,
+                _ => return Err(DurationError::Malformed),
+            };
+
+            if unit <= last || (fraction.is_some() && unit != 5) {
+                return Err(DurationError::Malformed);
+            }
+
+            match unit {
+                1 => days = value * 7,
+                2 => days += value,
+                3 => hours = value,
+                4 => minutes = value,
+                _ => {
+                    seconds = value;
+                    milliseconds = fraction.unwrap_or_default();
                 }
-                _ => unreachable!(),
             }
+
+            last = unit;
         }
 
-        if hours >= 24 || minutes >= 60 || seconds >= 60 || milliseconds >= 1000 {
-            return Err(DurationError::TooLarge);
+        if last == 0 || (time && last < 3) {
+            return Err(DurationError::Malformed);
         }
 
-        Ok(Duration { days, hours, minutes, seconds, milliseconds })
+        let hours = days
+            .checked_mul(24)
+            .and_then(|h| h.checked_add(hours))
+            .ok_or(DurationError::TooLarge)?;
+        Self::from_parts(hours, minutes, seconds, milliseconds, None)
+    }
+
+    /// Create a duration from its parts, carrying overflowing minutes and
+    /// seconds into the larger units.
+    fn from_parts(
+        hours: u64,
+        minutes: u64,
+        seconds: u64,
+        milliseconds: u16,
+        frames: Option<u64>,
+    ) -> Result<Self, DurationError> {
+        let total = hours
+            .checked_mul(60)
+            .and_then(|m| m.checked_add(minutes))
+            .and_then(|m| m.checked_mul(60))
+            .and_then(|s| s.checked_add(seconds))
+            .ok_or(DurationError::TooLarge)?;
+
+        Ok(Duration {
+            days: (total / 86400).try_into().map_err(|_| DurationError::TooLarge)?,
+            hours: (total / 3600 % 24) as u32,
+            minutes: (total / 60 % 60) as u32,
+            seconds: (total % 60) as u8,
+            milliseconds,
+            frames: frames.map(|f| f as u8),
+        })
     }
 
     /// Get the duration in milliseconds.
@@ -442,6 +522,86 @@ impl Duration {
             + self.hours as u64 * 60 * 60 * 1000
             + self.days as u64 * 24 * 60 * 60 * 1000
     }
+
+    /// Format the duration in its clock form if it can be read back and as an
+    /// ISO 8601 duration otherwise. Between 10 and 23 days, the clock form
+    /// reads like a timecode.
+    fn to_serialized(self) -> String {
+        let clock = self.to_string();
+        if clock.parse() == Ok(self) {
+            clock
+        } else {
+            self.to_iso8601()
+        }
+    }
+
+    /// The frame number of an SMPTE timecode.
+    pub fn frames(&self) -> Option<u8> {
+        self.frames
+    }
+
+    /// The total number of hours, including those of the days.
+    fn total_hours(self) -> u64 {
+        self.days as u64 * 24 + self.hours as u64
+    }
+
+    /// Format the duration as an ISO 8601 duration like `PT1H30M`. Frames
+    /// cannot be expressed in this format and are dropped.
+    ///
+    /// ```
+    /// use hayagriva::types::Duration;
+    ///
+    /// let duration: Duration = "26:03:00.5".parse().unwrap();
+    /// assert_eq!(duration.to_iso8601(), "P1DT2H3M0.5S");
+    /// ```
+    pub fn to_iso8601(&self) -> String {
+        let mut buf = String::from("P");
+        if self.days > 0 {
+            write!(buf, "{}D", self.days).unwrap();
+        }
+
+        let has_time = self.hours > 0
+            || self.minutes > 0
+            || self.seconds > 0
+            || self.milliseconds > 0;
+        if !has_time {
+            if self.days == 0 {
+                buf.push_str("T0S");
+            }
+            return buf;
+        }
+
+        buf.push('T');
+        if self.hours > 0 {
+            write!(buf, "{}H", self.hours).unwrap();
+        }
+        if self.minutes > 0 {
+            write!(buf, "{}M", self.minutes).unwrap();
+        }
+        if self.milliseconds > 0 {
+            let fraction = format!("{:03}", self.milliseconds);
+            write!(buf, "{}.{}S", self.seconds, fraction.trim_end_matches('0')).unwrap();
+        } else if self.seconds > 0 {
+            write!(buf, "{}S", self.seconds).unwrap();
+        }
+
+        buf
+    }
+}
+
+/// Parse the digits of a decimal fraction into milliseconds. Digits beyond
+/// the milliseconds are truncated.
+fn parse_fraction(s: &mut Scanner) -> Result<u16, DurationError> {
+    let digits = s.eat_while(|c: char| c.is_ascii_digit());
+    if digits.is_empty() {
+        return Err(DurationError::Malformed);
+    }
+
+    Ok(digits
+        .bytes()
+        .chain(std::iter::repeat(b'0'))
+        .take(3)
+        .fold(0, |acc, d| acc * 10 + (d - b'0') as u16))
 }
 
 impl PartialOrd for Duration {
@@ -452,7 +612,9 @@ impl PartialOrd for Duration {
 
 impl Ord for Duration {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.milliseconds().cmp(&other.milliseconds())
+        self.milliseconds()
+            .cmp(&other.milliseconds())
+            .then(self.frames.cmp(&other.frames))
     }
 }
 
@@ -478,11 +640,21 @@ impl FromStr for Duration {
 
 impl Display for Duration {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.days > 0 {
-            write!(f, "{}:", self.days)?;
+        // Timecodes have no days, so their hours can exceed 24.
+        if let Some(frames) = self.frames {
+            return write!(
+                f,
+                "{:02}:{:02}:{:02};{:02}",
+                self.total_hours(),
+                self.minutes,
+                self.seconds,
+                frames
+            );
         }
 
-        if self.hours > 0 {
+        if self.days > 0 {
+            write!(f, "{}:{:02}:", self.days, self.hours)?;
+        } else if self.hours > 0 {
             write!(f, "{:02}:", self.hours)?;
         }
 
@@ -553,7 +725,18 @@ impl FromStr for DurationRange {
     }
 }
 
-serialize_display!(DurationRange);
+impl Serialize for DurationRange {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut buf = self.start.to_serialized();
+        if self.start != self.end {
+            write!(buf, "-{}", self.end.to_serialized()).unwrap();
+        }
+        serializer.serialize_str(&buf)
+    }
+}
 
 fn parse_int<R>(s: &mut Scanner, digits: R) -> Option<i32>
 where
@@ -698,6 +881,7 @@ mod tests {
                 minutes: 1,
                 seconds: 0,
                 milliseconds: 0,
+                frames: None,
             }
         );
         assert_eq!(
@@ -708,6 +892,7 @@ mod tests {
                 minutes: 1,
                 seconds: 0,
                 milliseconds: 0,
+                frames: None,
             }
         );
         assert_eq!(
@@ -715,9 +900,10 @@ mod tests {
             Duration {
                 days: 0,
                 hours: 0,
-                minutes: 1,
-                seconds: 0,
+                minutes: 0,
+                seconds: 1,
                 milliseconds: 0,
+                frames: Some(0),
             }
         );
         assert_eq!(
@@ -728,12 +914,69 @@ mod tests {
                 minutes: 1,
                 seconds: 0,
                 milliseconds: 0,
+                frames: None,
             }
         );
         assert!(Duration::from_str("01:00,").is_err());
         assert!(Duration::from_str("010:00,").is_err());
     }
 
+    #[test]
+    fn test_duration_forms() {
+        let roundtrip = |s: &str| Duration::from_str(s).unwrap().to_string();
+        assert_eq!(roundtrip("1:02:03.5"), "01:02:03,500");
+        assert_eq!(roundtrip("01:02:03.12345"), "01:02:03,123");
+        assert_eq!(roundtrip("01:02:03;12"), "01:02:03;12");
+        assert_eq!(roundtrip("00:02:03 ; 29"), "00:02:03;29");
+        assert_eq!(roundtrip("01:02:03:12"), "01:02:03;12");
+        assert_eq!(roundtrip("1:02:03:12"), "1:02:03:12");
+        assert_eq!(roundtrip("1:02:03:12,5"), "1:02:03:12,500");
+        assert_eq!(roundtrip("30:02:03:12"), "30:02:03:12");
+        assert_eq!(roundtrip("130:00:00"), "5:10:00:00");
+        assert_eq!(roundtrip("130:00:00;05"), "130:00:00;05");
+        assert_eq!(roundtrip("75:30"), "01:15:30");
+        assert_eq!(roundtrip("PT1H30M"), "01:30:00");
+        assert_eq!(roundtrip("P2DT4H0.25S"), "2:04:00:00,250");
+        assert_eq!(roundtrip("PT90M"), "01:30:00");
+        assert_eq!(roundtrip("P1W"), "7:00:00:00");
+
+        // The clock form of 10 to 23 days would be read as a timecode.
+        let days = Duration::from_str("P12DT2H").unwrap();
+        let yaml = serde_yaml::to_string(&days).unwrap();
+        assert_eq!(yaml.trim(), "P12DT2H");
+        assert_eq!(serde_yaml::from_str::<Duration>(&yaml).unwrap(), days);
+        let range = DurationRange::new(Duration::default(), Some(days));
+        let yaml = serde_yaml::to_string(&range).unwrap();
+        assert_eq!(yaml.trim(), "00:00-P12DT2H");
+        assert_eq!(serde_yaml::from_str::<DurationRange>(&yaml).unwrap(), range);
+
+        let long = Duration::from_str("130:00:00").unwrap();
+        assert_eq!((long.days, long.hours), (5, 10));
+        assert_eq!(long.to_iso8601(), "P5DT10H");
+        assert_eq!(Duration::default().to_iso8601(), "PT0S");
+
+        assert!(
+            Duration::from_str("01:02:03;12").unwrap()
+                > Duration::from_str("01:02:03").unwrap()
+        );
+
+        assert_eq!(Duration::from_str("01:60:00"), Err(DurationError::TooLarge));
+        assert_eq!(Duration::from_str("1:24:00:00"), Err(DurationError::TooLarge));
+        for malformed in [
+            "PT",
+            "P1Y",
+            "P1DT",
+            "PT1.5H",
+            "PT1M1H",
+            "01:02:03;12,5",
+            "01:02:03:12,5",
+            "02:03;12",
+            "1:2",
+        ] {
+            assert_eq!(Duration::from_str(malformed), Err(DurationError::Malformed));
+        }
+    }
+
     #[test]
     fn test_duration_range_parse() {
         assert_eq!(
@@ -745,6 +988,7 @@ mod tests {
                     minutes: 1,
                     seconds: 0,
                     milliseconds: 0,
+                    frames: None,
                 },
                 None
             )
@@ -758,6 +1002,7 @@ mod tests {
                     minutes: 1,
                     seconds: 0,
                     milliseconds: 0,
+                    frames: None,
                 },
                 Some(Duration {
                     days: 0,
@@ -765,8 +1010,13 @@ mod tests {
                     minutes: 2,
                     seconds: 0,
                     milliseconds: 0,
+                    frames: None,
                 })
             )
         );
+        assert_eq!(
+            DurationRange::from_str("PT1H-PT1H30M").unwrap().to_string(),
+            "01:00:00-01:30:00"
+        );
     }
 }
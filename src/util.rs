@@ -2,6 +2,8 @@
 
 use serde::{de::Visitor, Deserialize, Deserializer, Serialize};
 
+use crate::types::{MaybeTyped, Numeric};
+
 /// Generic wrapper that allow one or more occurrences of specified type.
 ///
 /// In YAML it will presented or as a value, or as an array:
@@ -185,3 +187,18 @@ where
 {
     <Option<MapOneOrMany<T>>>::deserialize(deserializer).map(|v| v.map(|v| v.into()))
 }
+
+/// Deserializes a page-like numeric value that may be written in Roman
+/// numerals.
+pub fn deserialize_roman_opt<'de, D>(
+    deserializer: D,
+) -> Result<Option<MaybeTyped<Numeric>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(<Option<MaybeTyped<Numeric>>>::deserialize(deserializer)?.map(|v| match v {
+        MaybeTyped::String(s) => Numeric::from_str_with_roman(&s)
+            .map_or(MaybeTyped::String(s), MaybeTyped::Typed),
+        v => v,
+    }))
+}
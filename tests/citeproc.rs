@@ -15,7 +15,7 @@ use hayagriva::archive::{locales, ArchivedStyle};
 use hayagriva::io::from_biblatex_str;
 use hayagriva::{
     BibliographyDriver, BibliographyRequest, CitationItem, CitationRequest, CitePurpose,
-    Entry, LocatorPayload, SpecificLocator,
+    CompiledStyle, Entry, LocatorPayload, SpecificLocator, SuppressionReason, TraceKind,
 };
 use unscanny::Scanner;
 
@@ -796,3 +796,1071 @@ fn no_author() {
         .unwrap();
     assert_eq!(buf, "(Definition and Objectives of Systems Development, 2016)");
 }
+
+#[test]
+fn nocite() {
+    let style = ArchivedStyle::by_name("ieee").unwrap().get();
+    let locales = locales();
+    let Style::Independent(style) = style else {
+        panic!("test has dependent style");
+    };
+
+    let lib = from_biblatex_str(
+        r#"
+        @book{a, title = {Book A}, author = {Doe, John}, date = {2000}}
+        @book{b, title = {Book B}, author = {Roe, Jane}, date = {2001}}
+        @book{c, title = {Book C}, author = {Poe, Edgar}, date = {2002}}
+      "#,
+    )
+    .unwrap();
+
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    driver.citation(CitationRequest::from_items(
+        vec![CitationItem::with_entry(lib.get("b").unwrap())],
+        &style,
+        &locales,
+    ));
+    driver.nocite_all(&lib);
+
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    assert_eq!(rendered.citations.len(), 1);
+
+    let mut buf = String::new();
+    rendered.citations[0]
+        .citation
+        .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+        .unwrap();
+    assert_eq!(buf, "[1]");
+
+    let bib = rendered.bibliography.unwrap();
+    let keys: Vec<_> = bib.items.iter().map(|i| i.key.as_str()).collect();
+    assert_eq!(keys, ["b", "a", "c"]);
+
+    let mut buf = String::new();
+    bib.items[2]
+        .first_field
+        .as_ref()
+        .unwrap()
+        .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+        .unwrap();
+    assert_eq!(buf, "[3]");
+}
+
+#[test]
+fn compiled_style() {
+    let style = ArchivedStyle::by_name("apa").unwrap().get();
+    let locales = locales();
+    let Style::Independent(style) = style else {
+        panic!("test has dependent style");
+    };
+
+    let lib = from_biblatex_str(
+        r#"
+        @book{a, title = {The Zebra}, author = {Doe, John}, date = {2000}}
+        @book{b, title = {An Apple}, author = {Doe, John}, date = {2000}}
+        @book{c, title = {Book C}, author = {Abel, Jane}, date = {2002}}
+        @book{d, title = {Book D}, author = {Doe, Jane}, date = {2001}}
+      "#,
+    )
+    .unwrap();
+
+    let render = |compiled: Option<&CompiledStyle>| {
+        let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+        for entry in lib.iter() {
+            let items = vec![CitationItem::with_entry(entry)];
+            driver.citation(match compiled {
+                Some(compiled) => {
+                    CitationRequest::with_compiled(items, compiled, None, None)
+                }
+                None => CitationRequest::from_items(items, &style, &locales),
+            });
+        }
+
+        let rendered = driver.finish(match compiled {
+            Some(compiled) => BibliographyRequest::with_compiled(compiled, None),
+            None => BibliographyRequest::new(&style, None, &locales),
+        });
+
+        let mut buf = String::new();
+        for cite in rendered.citations.iter() {
+            cite.citation
+                .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+                .unwrap();
+            buf.push('\n');
+        }
+        for item in rendered.bibliography.unwrap().items.iter() {
+            item.content
+                .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+                .unwrap();
+            buf.push('\n');
+        }
+        buf
+    };
+
+    let expected = render(None);
+    assert_eq!(
+        expected,
+        "(Doe, 2000a)\n(Doe, 2000b)\n(Abel, 2002)\n(J. Doe, 2001)\n\
+         Abel, J. (2002). Book C.\n\
+         Doe, J. (2001). Book D.\n\
+         Doe, J. (2000b). An Apple.\n\
+         Doe, J. (2000a). The Zebra.\n"
+    );
+
+    let compiled = CompiledStyle::new(&style, &locales);
+    std::thread::scope(|s| {
+        let threads: Vec<_> =
+            (0..4).map(|_| s.spawn(|| render(Some(&compiled)))).collect();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), expected);
+        }
+    });
+}
+
+#[test]
+fn affixes() {
+    let style = ArchivedStyle::by_name("apa").unwrap().get();
+    let locales = locales();
+    let Style::Independent(style) = style else {
+        panic!("test has dependent style");
+    };
+
+    let lib = from_biblatex_str(
+        r#"
+        @book{a, title = {Book A}, author = {Doe, John}, date = {2000}}
+        @book{b, title = {Book B}, author = {Roe, Jane}, date = {2001}}
+      "#,
+    )
+    .unwrap();
+
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    driver.citation(CitationRequest::from_items(
+        vec![
+            CitationItem::with_entry(lib.get("a").unwrap())
+                .prefix("see")
+                .suffix("for a critique;"),
+            CitationItem::with_entry(lib.get("b").unwrap()).suffix(", p. 4"),
+        ],
+        &style,
+        &locales,
+    ));
+
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    let mut buf = String::new();
+    rendered.citations[0]
+        .citation
+        .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+        .unwrap();
+    assert_eq!(buf, "(see Doe, 2000 for a critique; Roe, 2001, p. 4)");
+
+    // Affixes can contain rich-text markup.
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    driver.citation(CitationRequest::from_items(
+        vec![CitationItem::with_entry(lib.get("a").unwrap())
+            .prefix(hayagriva::types::ChunkedString::from_rich_text("see <i>also</i>"))],
+        &style,
+        &locales,
+    ));
+
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    let mut buf = String::new();
+    rendered.citations[0]
+        .citation
+        .write_buf(&mut buf, hayagriva::BufWriteFormat::Html)
+        .unwrap();
+    assert_eq!(buf, r#"(see <span style="font-style: italic;">also</span> Doe, 2000)"#);
+}
+
+#[test]
+fn givenname_disambiguation_rules() {
+    let locales = locales();
+    let lib = from_biblatex_str(
+        r#"
+        @book{a, title = {Book A}, author = {Smith, John}, date = {2000}}
+        @book{b, title = {Book B}, author = {Smith, Jane}, date = {2001}}
+        @book{c, title = {Book C}, author = {Smith, Peter and Roe, Jane}, date = {2002}}
+        @book{d, title = {Book D}, author = {Roe, Jim}, date = {2003}}
+      "#,
+    )
+    .unwrap();
+
+    let render = |rule: &str| {
+        let style = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+            <style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+              <info><id>test</id><title>Test</title><updated>2024-01-01T00:00:00+00:00</updated></info>
+              <citation disambiguate-add-givenname="true" givenname-disambiguation-rule="{rule}">
+                <layout delimiter="; ">
+                  <group delimiter=" ">
+                    <names variable="author">
+                      <name form="short" and="symbol" initialize-with=". "/>
+                    </names>
+                    <date variable="issued"><date-part name="year"/></date>
+                  </group>
+                </layout>
+              </citation>
+            </style>"#
+        );
+        let style = citationberg::IndependentStyle::from_xml(&style).unwrap();
+
+        let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+        for entry in lib.iter() {
+            driver.citation(CitationRequest::from_items(
+                vec![CitationItem::with_entry(entry)],
+                &style,
+                &locales,
+            ));
+        }
+
+        driver
+            .finish(BibliographyRequest::new(&style, None, &locales))
+            .citations
+            .iter()
+            .map(|c| {
+                let mut buf = String::new();
+                c.citation
+                    .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+                    .unwrap();
+                buf
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        render("by-cite"),
+        ["Smith 2000", "Smith 2001", "Smith & Roe 2002", "Roe 2003"]
+    );
+    assert_eq!(
+        render("all-names"),
+        [
+            "John Smith 2000",
+            "Jane Smith 2001",
+            "P. Smith & Jane Roe 2002",
+            "Jim Roe 2003"
+        ]
+    );
+    assert_eq!(
+        render("all-names-with-initials"),
+        ["J. Smith 2000", "J. Smith 2001", "P. Smith & J. Roe 2002", "J. Roe 2003"]
+    );
+    assert_eq!(
+        render("primary-name"),
+        ["John Smith 2000", "Jane Smith 2001", "P. Smith & Roe 2002", "Roe 2003"]
+    );
+    assert_eq!(
+        render("primary-name-with-initials"),
+        ["J. Smith 2000", "J. Smith 2001", "P. Smith & Roe 2002", "Roe 2003"]
+    );
+}
+
+#[test]
+fn year_suffix_collapsing() {
+    let locales = locales();
+    let lib = from_biblatex_str(
+        r#"
+        @book{a, title = {Book A}, author = {Smith, John}, date = {2020}}
+        @book{b, title = {Book B}, author = {Smith, John}, date = {2020}}
+        @book{c, title = {Book C}, author = {Smith, John}, date = {2020}}
+        @book{d, title = {Book D}, author = {Smith, John}, date = {2020}}
+        @book{e, title = {Book E}, author = {Smith, John}, date = {2021}}
+        @book{f, title = {Book F}, author = {Roe, Jane}, date = {2020}}
+      "#,
+    )
+    .unwrap();
+
+    let render = |collapse: &str, keys: &[&str]| {
+        let style = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+            <style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+              <info><id>test</id><title>Test</title><updated>2024-01-01T00:00:00+00:00</updated></info>
+              <citation disambiguate-add-year-suffix="true" collapse="{collapse}"
+                  cite-group-delimiter=", " year-suffix-delimiter=","
+                  after-collapse-delimiter="; ">
+                <layout prefix="(" suffix=")" delimiter="; ">
+                  <group delimiter=" ">
+                    <names variable="author"><name form="short"/></names>
+                    <date variable="issued"><date-part name="year"/></date>
+                  </group>
+                </layout>
+              </citation>
+            </style>"#
+        );
+        let style = citationberg::IndependentStyle::from_xml(&style).unwrap();
+
+        let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+        driver.citation(CitationRequest::from_items(
+            keys.iter()
+                .map(|key| CitationItem::with_entry(lib.get(key).unwrap()))
+                .collect(),
+            &style,
+            &locales,
+        ));
+
+        let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+        let mut buf = String::new();
+        rendered.citations[0]
+            .citation
+            .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+            .unwrap();
+        buf
+    };
+
+    let keys = ["a", "b", "c", "d", "e", "f"];
+    assert_eq!(
+        render("year", &keys),
+        "(Smith 2020a, 2020b, 2020c, 2020d, 2021; Roe 2020)"
+    );
+    assert_eq!(render("year-suffix", &keys), "(Smith 2020a,b,c,d, 2021; Roe 2020)");
+    assert_eq!(render("year-suffix-ranged", &keys), "(Smith 2020a–d, 2021; Roe 2020)");
+    assert_eq!(
+        render("year-suffix-ranged", &["a", "b", "f"]),
+        "(Smith 2020a,b; Roe 2020)"
+    );
+}
+
+#[test]
+fn render_trace() {
+    let locales = locales();
+    let lib = from_biblatex_str(
+        r#"@book{a, title = {Book A}, editor = {Smith, John}, date = {2020}}"#,
+    )
+    .unwrap();
+
+    let style = citationberg::IndependentStyle::from_xml(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+        <style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+          <info><id>test</id><title>Test</title><updated>2024-01-01T00:00:00+00:00</updated></info>
+          <citation>
+            <layout>
+              <choose>
+                <if type="article-journal"><text value="article"/></if>
+                <else>
+                  <names variable="author">
+                    <name/>
+                    <substitute><names variable="editor"/></substitute>
+                  </names>
+                  <group prefix=" (" suffix=")">
+                    <text term="edition"/>
+                    <text variable="edition"/>
+                  </group>
+                </else>
+              </choose>
+            </layout>
+          </citation>
+        </style>"#,
+    )
+    .unwrap();
+
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    driver.citation(CitationRequest::from_items(
+        vec![CitationItem::with_entry(lib.get("a").unwrap())],
+        &style,
+        &locales,
+    ));
+
+    let rendered =
+        driver.finish(BibliographyRequest::new(&style, None, &locales).trace(true));
+    let trace = &rendered.citations[0].traces()[0];
+    assert_eq!(trace.key, "a");
+
+    let has = |path: &[&str], kind: TraceKind| {
+        trace.events.iter().any(|e| e.path == path && e.kind == kind)
+    };
+    assert!(has(
+        &["layout", "choose"],
+        TraceKind::Branch {
+            test: "if type=\"article-journal\"".into(),
+            matched: false
+        }
+    ));
+    assert!(has(
+        &["layout", "choose"],
+        TraceKind::Branch { test: "else".into(), matched: true }
+    ));
+    assert!(has(
+        &["layout", "choose", "else", "names variable=\"author\""],
+        TraceKind::Variable { variable: "author".into(), present: false }
+    ));
+    assert!(has(
+        &[
+            "layout",
+            "choose",
+            "else",
+            "names variable=\"author\"",
+            "substitute",
+            "names variable=\"editor\""
+        ],
+        TraceKind::Variable { variable: "editor".into(), present: true }
+    ));
+    assert!(has(
+        &["layout", "choose", "else", "group"],
+        TraceKind::Suppressed(SuppressionReason::EmptyGroup)
+    ));
+
+    // Traces are only recorded on request.
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    driver.citation(CitationRequest::from_items(
+        vec![CitationItem::with_entry(lib.get("a").unwrap())],
+        &style,
+        &locales,
+    ));
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    assert!(rendered.citations[0].traces().is_empty());
+}
+
+#[test]
+fn intext_purposes() {
+    let locales = locales();
+    let lib = from_biblatex_str(
+        r#"
+        @book{a, title = {Book A}, author = {Smith, John}, date = {2020}}
+        @book{b, title = {Book B}, author = {Smith, John}, date = {2020}}
+      "#,
+    )
+    .unwrap();
+
+    let xml = |intext: &str| {
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+            <style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0.2">
+              <info><id>test</id><title>Test</title><updated>2024-01-01T00:00:00+00:00</updated></info>
+              <citation disambiguate-add-year-suffix="true">
+                <layout prefix="(" suffix=")" delimiter="; ">
+                  <group delimiter=" ">
+                    <names variable="author"><name form="short"/></names>
+                    <date variable="issued"><date-part name="year"/></date>
+                  </group>
+                </layout>
+              </citation>
+              {intext}
+            </style>"#
+        )
+    };
+
+    let render = |xml: &str, purposes: &[(&str, Option<CitePurpose>)]| {
+        let style = citationberg::IndependentStyle::from_xml(xml).unwrap();
+        let compiled = CompiledStyle::from_xml(&style, xml, &locales).unwrap();
+
+        let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+        for &(key, purpose) in purposes {
+            let item =
+                CitationItem::new(lib.get(key).unwrap(), None, None, false, purpose);
+            driver.citation(CitationRequest::with_compiled(
+                vec![item],
+                &compiled,
+                None,
+                None,
+            ));
+        }
+
+        let rendered = driver.finish(BibliographyRequest::with_compiled(&compiled, None));
+        rendered
+            .citations
+            .iter()
+            .map(|c| {
+                let mut buf = String::new();
+                c.citation
+                    .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+                    .unwrap();
+                buf
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let purposes = [
+        ("a", Some(CitePurpose::Prose)),
+        ("b", Some(CitePurpose::Year)),
+        ("b", Some(CitePurpose::Author)),
+        ("a", None),
+    ];
+
+    let intext = xml(r#"<intext>
+            <layout>
+              <group delimiter=" ">
+                <names variable="author"><name form="short"/></names>
+                <date variable="issued" prefix="[" suffix="]"><date-part name="year"/></date>
+              </group>
+            </layout>
+          </intext>"#);
+    assert_eq!(
+        render(&intext, &purposes),
+        ["Smith [2020a]", "2020b", "Smith", "(Smith 2020a)"]
+    );
+
+    // Items are disambiguated by their regular form, even if they are only
+    // cited with a purpose whose output differs between the items.
+    assert_eq!(
+        render(
+            &intext,
+            &[("a", Some(CitePurpose::Prose)), ("b", Some(CitePurpose::Year))]
+        ),
+        ["Smith [2020a]", "2020b"]
+    );
+
+    // Without `cs:intext`, the narrative citation is derived from the
+    // citation layout.
+    assert_eq!(
+        render(&xml(""), &purposes),
+        ["Smith (2020a)", "2020b", "Smith", "(Smith 2020a)"]
+    );
+}
+
+#[test]
+fn abbreviations() {
+    let locales = locales();
+    let lib = hayagriva::io::from_yaml_str(
+        r#"
+        listed:
+            type: article
+            title: A
+            parent:
+                type: periodical
+                title: Physical Review Letters
+        ltwa:
+            type: article
+            title: B
+            parent:
+                type: periodical
+                title: Journal of the American Chemical Society
+        own:
+            type: article
+            title: C
+            parent:
+                type: periodical
+                title:
+                    value: Physical Review Letters
+                    short: PRL
+        "#,
+    )
+    .unwrap();
+
+    let style = citationberg::IndependentStyle::from_xml(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+        <style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+          <info><id>test</id><title>Test</title><updated>2024-01-01T00:00:00+00:00</updated></info>
+          <citation>
+            <layout><text variable="container-title" form="short"/></layout>
+          </citation>
+        </style>"#,
+    )
+    .unwrap();
+
+    let mut abbreviations = hayagriva::Abbreviations::from_json(
+        r#"{"default": {"container-title": {"Physical Review Letters": "Phys. Rev. Lett."}}}"#,
+    )
+    .unwrap();
+    abbreviations
+        .extend_from_ltwa("american\tam.\nchemical\tchem.\njournal\tj.\nsociety-\tsoc.");
+
+    let render = |abbreviations: Option<&hayagriva::Abbreviations>| {
+        let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+        for key in ["listed", "ltwa", "own"] {
+            let mut request = CitationRequest::from_items(
+                vec![CitationItem::with_entry(lib.get(key).unwrap())],
+                &style,
+                &locales,
+            );
+            if let Some(abbreviations) = abbreviations {
+                request = request.with_abbreviations(abbreviations);
+            }
+            driver.citation(request);
+        }
+
+        let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+        rendered
+            .citations
+            .iter()
+            .map(|c| {
+                let mut buf = String::new();
+                c.citation
+                    .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+                    .unwrap();
+                buf
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        render(None),
+        ["Physical Review Letters", "Journal of the American Chemical Society", "PRL"]
+    );
+    assert_eq!(
+        render(Some(&abbreviations)),
+        ["Phys. Rev. Lett.", "J. Am. Chem. Soc.", "PRL"]
+    );
+
+    // CSL-JSON items are abbreviated in the same way.
+    let items: Vec<csl_json::Item> = serde_json::from_str(
+        r#"[
+            {"id": "listed", "type": "article-journal", "container-title": "Physical Review Letters"},
+            {"id": "own", "type": "article-journal", "container-title": "Physical Review Letters", "container-title-short": "PRL"}
+        ]"#,
+    )
+    .unwrap();
+    let mut driver: BibliographyDriver<'_, csl_json::Item> = BibliographyDriver::new();
+    for item in &items {
+        driver.citation(
+            CitationRequest::from_items(
+                vec![CitationItem::with_entry(item)],
+                &style,
+                &locales,
+            )
+            .with_abbreviations(&abbreviations),
+        );
+    }
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    let rendered: Vec<_> = rendered
+        .citations
+        .iter()
+        .map(|c| {
+            let mut buf = String::new();
+            c.citation
+                .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+                .unwrap();
+            buf
+        })
+        .collect();
+    assert_eq!(rendered, ["Phys. Rev. Lett.", "PRL"]);
+}
+
+#[test]
+fn language_variants() {
+    use hayagriva::types::LanguageVariant;
+    use hayagriva::LanguageVariants;
+
+    let locales = locales();
+    let lib = hayagriva::io::from_yaml_str(
+        r#"
+        war-and-peace:
+            type: book
+            title:
+                value: Война и мир
+                transliterations:
+                    ru-Latn: Voina i mir
+                translations:
+                    en: War and Peace
+            author:
+                name: Толстой
+                given-name: Лев
+                transliterations:
+                    ru-Latn: Tolstoy, Lev
+            date: 1869
+        "#,
+    )
+    .unwrap();
+    let entry = lib.get("war-and-peace").unwrap();
+
+    let style = citationberg::IndependentStyle::from_xml(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+        <style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+          <info><id>test</id><title>Test</title><updated>2024-01-01T00:00:00+00:00</updated></info>
+          <citation>
+            <layout delimiter="; ">
+              <group delimiter=", ">
+                <names variable="author"><name form="short"/></names>
+                <text variable="title"/>
+              </group>
+            </layout>
+          </citation>
+        </style>"#,
+    )
+    .unwrap();
+
+    let render = |variants: LanguageVariants| {
+        let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+        driver.citation(
+            CitationRequest::from_items(
+                vec![CitationItem::with_entry(entry)],
+                &style,
+                &locales,
+            )
+            .with_language_variants(variants),
+        );
+
+        let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+        let mut buf = String::new();
+        rendered.citations[0]
+            .citation
+            .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+            .unwrap();
+        buf
+    };
+
+    let translit = LanguageVariant::Transliteration("ru-Latn".parse().unwrap());
+    let transl = LanguageVariant::Translation("en".parse().unwrap());
+
+    assert_eq!(render(LanguageVariants::default()), "Толстой, Война и мир");
+    assert_eq!(
+        render(LanguageVariants {
+            fields: vec![translit.clone(), transl.clone()],
+            names: vec![translit.clone()],
+            ..Default::default()
+        }),
+        "Tolstoy, Voina i mir [War and Peace]"
+    );
+    assert_eq!(
+        render(LanguageVariants {
+            fields: vec![translit.clone(), transl.clone()],
+            prefix: " (".into(),
+            suffix: ")".into(),
+            ..Default::default()
+        }),
+        "Толстой, Voina i mir (War and Peace)"
+    );
+    assert_eq!(
+        render(LanguageVariants {
+            fields: vec![LanguageVariant::Translation("de".parse().unwrap())],
+            names: vec![transl],
+            ..Default::default()
+        }),
+        "Толстой, Война и мир"
+    );
+}
+
+#[test]
+fn rich_text_flip_flop() {
+    let style = ArchivedStyle::by_name("apa").unwrap().get();
+    let locales = locales();
+    let Style::Independent(style) = style else {
+        panic!("test has dependent style");
+    };
+
+    let lib = from_biblatex_str(
+        r#"
+        @book{book, title = {Life of \emph{E. coli} in CO\textsubscript{2}}, author = {Doe, Jane}, date = {2020}, publisher = {Acme}}
+        @article{article, title = {On \emph{E. coli} bacteria}, author = {Roe, Rick}, date = {2021}, journaltitle = {Nature}}
+      "#,
+    )
+    .unwrap();
+
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    for entry in lib.iter() {
+        driver.citation(CitationRequest::from_items(
+            vec![CitationItem::with_entry(entry)],
+            &style,
+            &locales,
+        ));
+    }
+
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    let items: Vec<_> = rendered
+        .bibliography
+        .unwrap()
+        .items
+        .iter()
+        .map(|item| {
+            let mut buf = String::new();
+            item.content
+                .write_buf(&mut buf, hayagriva::BufWriteFormat::Html)
+                .unwrap();
+            buf
+        })
+        .collect();
+
+    // Italics within the italic book title are set upright.
+    let italic = r#"<span style="font-style: italic;">"#;
+    assert!(items[0].contains(&format!("{italic}Life of </span>E. coli{italic} in CO")));
+    assert!(items[0].contains(r#"vertical-align: sub;">2</span>"#));
+    // The article title continues in sentence case after the italics.
+    assert!(items[1].contains(&format!("On {italic}E. coli</span> bacteria")));
+}
+
+#[test]
+fn name_particles() {
+    let locales = locales();
+    let mut lib = hayagriva::io::from_yaml_str(
+        r#"
+        beethoven:
+            type: book
+            title: Symphonies
+            author: { name: Beethoven, given-name: Ludwig, dropping-particle: van }
+            date: 1800
+        gogh:
+            type: book
+            title: Letters
+            author: { name: Gogh, given-name: Vincent, non-dropping-particle: van }
+            date: 1888
+        "#,
+    )
+    .unwrap();
+    let tex = from_biblatex_str(
+        "@book{hals, title = {Portraits}, author = {Hals, Frans}, date = {1650}}
+         @book{hooch, title = {Courtyards}, author = {de Hooch, Pieter}, date = {1658}, options = {useprefix}}",
+    )
+    .unwrap();
+    for entry in tex.iter() {
+        lib.push(entry);
+    }
+
+    let bibliography = |name: &str| {
+        let style = ArchivedStyle::by_name(name).unwrap().get();
+        let Style::Independent(style) = style else {
+            panic!("test has dependent style");
+        };
+
+        let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+        for entry in lib.iter() {
+            driver.citation(CitationRequest::from_items(
+                vec![CitationItem::with_entry(entry)],
+                &style,
+                &locales,
+            ));
+        }
+
+        let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+        rendered
+            .bibliography
+            .unwrap()
+            .items
+            .iter()
+            .map(|item| {
+                let mut buf = String::new();
+                item.content
+                    .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+                    .unwrap();
+                buf
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // APA never demotes non-dropping particles.
+    let apa = bibliography("apa");
+    assert!(apa[0].starts_with("Beethoven, L. van."));
+    assert!(apa[1].starts_with("de Hooch, P."));
+    assert!(apa[2].starts_with("Hals, F."));
+    assert!(apa[3].starts_with("van Gogh, V."));
+
+    // Chicago demotes them for display and sorting.
+    let chicago = bibliography("chicago-author-date");
+    assert!(chicago[0].starts_with("Beethoven, Ludwig van."));
+    assert!(chicago[1].starts_with("Gogh, Vincent van."));
+    assert!(chicago[2].starts_with("Hals, Frans."));
+    assert!(chicago[3].starts_with("Hooch, Pieter de."));
+}
+
+#[test]
+fn math_rendering() {
+    let style = ArchivedStyle::by_name("ieee").unwrap().get();
+    let locales = locales();
+    let Style::Independent(style) = style else {
+        panic!("test has dependent style");
+    };
+
+    let lib = from_biblatex_str(
+        r#"@article{helix, title = {The $\alpha$-helix of 50\% of $x^2$}, author = {Doe, Jane}, date = {2020}}"#,
+    )
+    .unwrap();
+
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    driver.citation(CitationRequest::from_items(
+        vec![CitationItem::with_entry(lib.get("helix").unwrap())],
+        &style,
+        &locales,
+    ));
+
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    let content = &rendered.bibliography.unwrap().items[0].content;
+    let write = |format, math: &dyn hayagriva::MathRenderer| {
+        let mut buf = String::new();
+        content.write_buf_with(&mut buf, format, math).unwrap();
+        buf
+    };
+
+    let builtin = &hayagriva::BuiltinMathRenderer;
+    assert!(write(hayagriva::BufWriteFormat::Plain, builtin)
+        .contains("The α-helix of 50% of x²"));
+    assert!(write(hayagriva::BufWriteFormat::Html, builtin).contains(
+        "<math><mi>α</mi></math>-helix of 50% of <math><msup><mi>x</mi><mn>2</mn></msup></math>"
+    ));
+    assert!(write(hayagriva::BufWriteFormat::Latex, builtin)
+        .contains(r"The $\alpha$-helix of 50\% of $x^2$"));
+    assert!(write(hayagriva::BufWriteFormat::Plain, &hayagriva::RawMathRenderer)
+        .contains(r"The \alpha-helix"));
+}
+
+#[test]
+fn timestamp_locators() {
+    let style = ArchivedStyle::by_name("apa").unwrap().get();
+    let locales = locales();
+    let Style::Independent(style) = style else {
+        panic!("test has dependent style");
+    };
+
+    let lib = hayagriva::io::from_yaml_str(
+        r#"
+        film:
+            type: video
+            title: The Film
+            author: Doe, Jane
+            date: 2020
+            runtime: PT2H5M
+        "#,
+    )
+    .unwrap();
+    let film = lib.get("film").unwrap();
+    assert_eq!(film.runtime().unwrap().to_string(), "02:05:00");
+
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    for locator in ["1:02:03.5-1:05:00", "01:02:03:12", "PT90M"] {
+        driver.citation(CitationRequest::from_items(
+            vec![CitationItem::with_locator(
+                film,
+                Some(SpecificLocator(Locator::Timestamp, LocatorPayload::Str(locator))),
+            )],
+            &style,
+            &locales,
+        ));
+    }
+
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    let citations: Vec<_> = rendered
+        .citations
+        .iter()
+        .map(|c| {
+            let mut buf = String::new();
+            c.citation
+                .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+                .unwrap();
+            buf
+        })
+        .collect();
+    assert_eq!(
+        citations,
+        [
+            "(Doe, 2020, 01:02:03,500–01:05:00)",
+            "(Doe, 2020, 01:02:03;12)",
+            "(Doe, 2020, 01:30:00)"
+        ]
+    );
+}
+
+#[test]
+fn entry_types() {
+    let locales = locales();
+    let mut lib = hayagriva::io::from_yaml_str(
+        r#"
+        data:
+            type: dataset
+            title: Survey Data
+            author: Doe, Jane
+            date: 2020
+            serial-number: { version: "2.1" }
+        review:
+            type: review
+            title: A Fine Book
+            author: Critic, Carl
+            date: 2022
+            parent:
+                - { type: periodical, title: Review Journal }
+                - { type: book, title: The Novel, author: "Writer, Wanda" }
+        serendipity:
+            type: entry
+            title: Serendipity
+            date: 2023
+            parent: { type: dictionary, title: Oxford English Dictionary }
+        interview:
+            type: interview
+            title: On Writing
+            author: Writer, Wanda
+            date: 2018
+            affiliated: { role: interviewer, names: "Host, Harry" }
+        "#,
+    )
+    .unwrap();
+    let tex = from_biblatex_str(
+        "@software{tool, title = {Tool}, author = {Roe, Rick}, date = {2021}, version = {1.0.3}}",
+    )
+    .unwrap();
+    assert_eq!(
+        tex.get("tool").unwrap().entry_type(),
+        &hayagriva::types::EntryType::Software
+    );
+    for entry in tex.iter() {
+        lib.push(entry);
+    }
+
+    let style = ArchivedStyle::by_name("apa").unwrap().get();
+    let Style::Independent(style) = style else {
+        panic!("test has dependent style");
+    };
+
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    driver.nocite_all(&lib);
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    let items: Vec<_> = rendered
+        .bibliography
+        .unwrap()
+        .items
+        .iter()
+        .map(|item| {
+            let mut buf = String::new();
+            item.content
+                .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+                .unwrap();
+            buf
+        })
+        .collect();
+
+    assert_eq!(
+        items[..4],
+        [
+            "Critic, C. (2022). A Fine Book [Review of The Novel, by W. Writer]. Review Journal.",
+            "Doe, J. (2020). Survey Data (Version 2.1) [dataset].",
+            "Roe, R. (2021). Tool (Version 1.0.3) [Computer software].",
+            "Serendipity. (2023). In Oxford English Dictionary.",
+        ]
+    );
+    assert!(items[4].contains("On Writing (H. Host, Interviewer)"));
+}
+
+#[test]
+fn archived_urls() {
+    let locales = locales();
+    let style = citationberg::IndependentStyle::from_xml(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+        <style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+          <info><id>test</id><title>Test</title><updated>2024-01-01T00:00:00+00:00</updated></info>
+          <citation>
+            <layout delimiter="; ">
+              <group delimiter=" ">
+                <text variable="title"/>
+                <text variable="archive" prefix="(" suffix=")"/>
+                <text variable="archive_location"/>
+              </group>
+            </layout>
+          </citation>
+        </style>"#,
+    )
+    .unwrap();
+
+    let items: Vec<csl_json::Item> = serde_json::from_str(
+        r#"[
+            {"id": "a", "type": "webpage", "title": "A",
+             "archive_location": "https://perma.cc/ABC1-D2EF"},
+            {"id": "b", "type": "webpage", "title": "B", "archive": "My Archive",
+             "archive_location": "https://web.archive.org/web/20210314095126/https://example.com/"},
+            {"id": "c", "type": "manuscript", "title": "C", "archive": "State Archive",
+             "archive_location": "Box 3"}
+        ]"#,
+    )
+    .unwrap();
+
+    let mut driver: BibliographyDriver<'_, csl_json::Item> = BibliographyDriver::new();
+    driver.citation(CitationRequest::from_items(
+        items.iter().map(CitationItem::with_entry).collect(),
+        &style,
+        &locales,
+    ));
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    let mut buf = String::new();
+    rendered.citations[0]
+        .citation
+        .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
+        .unwrap();
+    assert_eq!(
+        buf,
+        "A (Perma.cc) https://perma.cc/ABC1-D2EF; \
+         B (My Archive) https://web.archive.org/web/20210314095126/https://example.com/; \
+         C (State Archive) Box 3"
+    );
+}